@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// run with: cargo +nightly fuzz run day3
+fuzz_target!(|input: &str| {
+    let _ = aoc::fuzz::parse(3, input);
+});
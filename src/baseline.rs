@@ -0,0 +1,140 @@
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+use crate::bench::{Measurement, Stats};
+
+/// Median timings in milliseconds, by day and then by measurement label.
+pub type Baseline = BTreeMap<u32, BTreeMap<String, f64>>;
+
+const BASELINE_DIR: &str = "baselines";
+
+/// Where a named baseline is stored.
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(BASELINE_DIR).join(format!("{name}.json"))
+}
+
+/// Loads a named baseline. A baseline which was never saved is empty.
+pub fn load(name: &str) -> Result<Baseline, String> {
+    match std::fs::read_to_string(path(name)) {
+        Ok(json) => {
+            serde_json::from_str(&json).map_err(|e| format!("could not parse baseline {name}: {e}"))
+        }
+        Err(_) => Ok(Baseline::new()),
+    }
+}
+
+/// Saves one day's measurements into a named baseline, replacing whatever was saved for that day
+/// before. Other days in the baseline are kept.
+pub fn save(name: &str, day: u32, measurements: &[Measurement]) -> Result<(), String> {
+    let mut baseline = load(name)?;
+    baseline.insert(day, medians(measurements));
+
+    let json = serde_json::to_string_pretty(&baseline).expect("baseline should serialize");
+    std::fs::create_dir_all(BASELINE_DIR)
+        .and_then(|_| std::fs::write(path(name), json))
+        .map_err(|e| format!("could not save baseline {name}: {e}"))
+}
+
+fn medians(measurements: &[Measurement]) -> BTreeMap<String, f64> {
+    measurements
+        .iter()
+        .filter_map(|m| Stats::new(&m.samples).map(|s| (m.label.clone(), millis(s.median))))
+        .collect()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Renders how each measurement's median compares to the baseline's, as a percentage change.
+pub fn render_comparison(
+    name: &str,
+    baseline: &BTreeMap<String, f64>,
+    measurements: &[Measurement],
+) -> String {
+    let width = measurements
+        .iter()
+        .map(|m| m.label.len())
+        .max()
+        .unwrap_or_default()
+        .max(name.len() + 3);
+
+    let mut lines = vec![format!(
+        "{:width$}  {:>10}  {:>10}  {:>8}",
+        format!("vs {name}"),
+        "median",
+        "baseline",
+        "change"
+    )];
+
+    for (label, median) in medians(measurements) {
+        let (previous, change) = match baseline.get(&label) {
+            Some(&previous) => (
+                format!("{previous:.3}ms"),
+                format!("{:+.1}%", percent_change(previous, median)),
+            ),
+            None => ("-".to_string(), "new".to_string()),
+        };
+
+        lines.push(format!(
+            "{label:width$}  {:>10}  {previous:>10}  {change:>8}",
+            format!("{median:.3}ms"),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn percent_change(previous: f64, current: f64) -> f64 {
+    (current - previous) / previous * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(label: &str, ms: &[u64]) -> Measurement {
+        Measurement {
+            label: label.to_string(),
+            samples: ms.iter().map(|&ms| Duration::from_millis(ms)).collect(),
+        }
+    }
+
+    #[test]
+    fn medians_by_label() {
+        let medians = medians(&[measurement("part1", &[1, 2, 3]), measurement("part2", &[8])]);
+
+        assert_eq!(medians["part1"], 2.0);
+        assert_eq!(medians["part2"], 8.0);
+    }
+
+    #[test]
+    fn percent_changes() {
+        assert_eq!(percent_change(10.0, 5.0), -50.0);
+        assert_eq!(percent_change(10.0, 12.5), 25.0);
+    }
+
+    #[test]
+    fn compare_to_baseline() {
+        let baseline = BTreeMap::from([("part1".to_string(), 4.0)]);
+        let rendered = render_comparison(
+            "main",
+            &baseline,
+            &[measurement("part1", &[3]), measurement("part2", &[5])],
+        );
+
+        assert_eq!(
+            rendered,
+            [
+                "vs main      median    baseline    change",
+                "part1       3.000ms     4.000ms    -25.0%",
+                "part2       5.000ms           -       new",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn unsaved_baseline_is_empty() {
+        assert!(load("never-saved-by-anyone").unwrap().is_empty());
+    }
+}
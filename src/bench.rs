@@ -0,0 +1,201 @@
+use std::time::{Duration, Instant};
+
+use aoc::{
+    ctx::{self, SolveCtx, SolveEvent},
+    error::AocError,
+    solver::{AnswerValue, Part, Solver},
+};
+
+/// Summary statistics over repeated timings of the same thing.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarizes the samples, or returns None if there aren't any.
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]) / 2
+        } else {
+            sorted[sorted.len() / 2]
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+
+        Some(Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Every timing collected for one thing being measured, like "part2" or "part2 / search rects".
+#[derive(Debug)]
+pub struct Measurement {
+    pub label: String,
+    pub samples: Vec<Duration>,
+}
+
+/// Runs the solver `warmup` times without measuring, then `iterations` times while measuring.
+///
+/// Each part is timed as a whole, and each phase it reports through its `SolveCtx` is timed too.
+pub fn bench(
+    solver: &dyn Solver,
+    input: &str,
+    part: Part,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Vec<Measurement>, AocError> {
+    for _ in 0..warmup {
+        solver.solve_parts(input, part, &SolveCtx::default())?;
+    }
+
+    let mut measurements: Vec<Measurement> = Vec::new();
+    for _ in 0..iterations {
+        if part.includes_part1() {
+            let timings = time_part(|ctx| solver.solve_part1(input, ctx))?;
+            record(&mut measurements, "part1", timings);
+        }
+        if solver.solves_part2(part) {
+            let timings = time_part(|ctx| solver.solve_part2(input, ctx))?;
+            record(&mut measurements, "part2", timings);
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// The total time one run of a part took, and the time spent in each phase it reported.
+type PartTimings = (Duration, Vec<(&'static str, Duration)>);
+
+/// Times one run of a part.
+fn time_part(
+    solve: impl FnOnce(&SolveCtx) -> Result<AnswerValue, AocError>,
+) -> Result<PartTimings, AocError> {
+    let (ctx, events) = SolveCtx::new();
+    let start = Instant::now();
+    solve(&ctx)?;
+    let end = Instant::now();
+    drop(ctx);
+
+    let phase_starts: Vec<(&'static str, Instant)> = events
+        .iter()
+        .filter_map(|event| match event {
+            SolveEvent::Phase { name, at } => Some((name, at)),
+            _ => None,
+        })
+        .collect();
+
+    Ok((end - start, ctx::phase_durations(&phase_starts, end)))
+}
+
+fn record(measurements: &mut Vec<Measurement>, part: &str, (total, phases): PartTimings) {
+    let labelled = std::iter::once((part.to_string(), total)).chain(
+        phases
+            .into_iter()
+            .map(|(name, d)| (format!("{part} / {name}"), d)),
+    );
+
+    for (label, duration) in labelled {
+        match measurements.iter_mut().find(|m| m.label == label) {
+            Some(measurement) => measurement.samples.push(duration),
+            None => measurements.push(Measurement {
+                label,
+                samples: vec![duration],
+            }),
+        }
+    }
+}
+
+/// Renders measurements as a table, one row per measurement.
+pub fn render(measurements: &[Measurement]) -> String {
+    let width = measurements
+        .iter()
+        .map(|m| m.label.len())
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![format!(
+        "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5}",
+        "", "min", "median", "mean", "stddev", "runs"
+    )];
+
+    for measurement in measurements {
+        let Some(stats) = Stats::new(&measurement.samples) else {
+            continue;
+        };
+
+        lines.push(format!(
+            "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5}",
+            measurement.label,
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.mean),
+            format!("{:.2?}", stats.stddev),
+            measurement.samples.len(),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{examples, solver};
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn stats_odd_samples() {
+        let stats = Stats::new(&millis(&[4, 2, 6])).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(4));
+        assert_eq!(stats.mean, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn stats_even_samples() {
+        let stats = Stats::new(&millis(&[2, 4, 4, 4, 5, 5, 7, 9])).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_micros(4500));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_millis(2));
+    }
+
+    #[test]
+    fn stats_no_samples() {
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn bench_measures_parts_and_phases() {
+        let input = examples::read("9");
+        let solver = solver::find("9").unwrap();
+
+        let measurements = bench(solver, &input, Part::Both, 1, 3).unwrap();
+        let labels: Vec<&str> = measurements.iter().map(|m| m.label.as_str()).collect();
+
+        assert!(labels.contains(&"part1"));
+        assert!(labels.contains(&"part2"));
+        assert!(labels.contains(&"part2 / search rects"));
+        assert!(measurements.iter().all(|m| m.samples.len() == 3));
+    }
+}
@@ -0,0 +1,888 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use aoc::{
+    error::AocError,
+    examples,
+    solver::{self, AnswerValue, Part},
+};
+
+use crate::{report::Format, serve};
+
+/// How long a solver can run before the watchdog starts printing status lines.
+const DEFAULT_SOFT_DEADLINE: Duration = Duration::from_secs(10);
+
+/// How long `--profile` keeps re-running the solver, so there are enough samples to look at.
+const DEFAULT_PROFILE_DURATION: Duration = Duration::from_secs(10);
+
+const DEFAULT_WARMUP: usize = 3;
+const DEFAULT_ITERATIONS: usize = 10;
+
+/// How much input `gen` generates, unless asked for more or less.
+const DEFAULT_GEN_SIZE: usize = 100;
+
+/// The subcommands, which is how a bare day like `cargo run 9` is told apart from a subcommand.
+const SUBCOMMANDS: [&str; 11] = [
+    "run",
+    "bench",
+    "list",
+    "new",
+    "gen",
+    "submit",
+    "completions",
+    "serve",
+    "daemon",
+    "track",
+    "help",
+];
+
+/// What the runner should do with the selected day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Solve once and print the answer.
+    Run,
+
+    /// Solve repeatedly and print timing statistics, like: `cargo run bench 9 --iterations 20`
+    ///
+    /// Timings can be saved as a named baseline, or compared against one saved earlier.
+    Bench {
+        warmup: usize,
+        iterations: usize,
+        save_baseline: Option<String>,
+        compare: Option<String>,
+    },
+
+    /// Re-run whenever the day's source or input changes, like: `cargo run -- run 9 --watch`
+    Watch,
+
+    /// Cross-check the solver against a brute-force solution on small generated inputs, like:
+    /// `cargo run 9 --verify`
+    Verify,
+
+    /// Solve in a loop for `duration` while sampling stacks, then write a pprof profile and a
+    /// flamegraph. Build with debug symbols so the stacks have names, like:
+    /// `cargo run --profile profiling -- run 9 --profile --duration 30`
+    Profile { duration: Duration },
+
+    /// Print the catalog of solvers, like: `cargo run list`
+    ///
+    /// Lists every day unless days are selected, like: `cargo run list 3..=8`
+    List,
+
+    /// Scaffold a new day's solver and example file, like: `cargo run new 13`
+    New,
+
+    /// Print a random input for the day, like: `cargo run gen 9 --size 500 --seed 1`
+    ///
+    /// Without a seed, a random one is used and printed to stderr, so the input can be generated
+    /// again.
+    Gen { size: usize, seed: Option<u64> },
+
+    /// Submit an answer for the real input, like: `cargo run submit 9 2`
+    ///
+    /// Without an answer, the part is solved and its answer submitted.
+    Submit {
+        part: u8,
+        answer: Option<AnswerValue>,
+    },
+
+    /// Print a shell completion script, like: `cargo run completions zsh`
+    Completions { shell: Shell },
+
+    /// Serve the solvers over HTTP, like: `cargo run --release serve --addr 127.0.0.1:8025`
+    Serve { addr: String },
+
+    /// Keep the inputs in memory and take `run` and `bench` commands from stdin, like:
+    /// `cargo run --release daemon`
+    Daemon,
+
+    /// Solve the real inputs, recording when each part was first verified correct, then print how
+    /// long after release each part was solved, like: `cargo run track`
+    Track,
+}
+
+/// Arguments for the runner, like: `cargo run 9 --part 2 --input inputs/9-stress.txt`
+///
+/// The day can also select several days: `all`, a range like `3..=8`, or a list like `1,4,9`.
+/// Without a subcommand, `run` is assumed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    pub command: Command,
+
+    // empty for commands which aren't about a day, like completions
+    pub day: String,
+
+    // the selected days, when more than one day was selected
+    pub days: Option<Vec<u32>>,
+    pub soft_deadline: Duration,
+
+    // gives up on solving after this long, rather than waiting forever
+    pub timeout: Option<Duration>,
+
+    // overrides the default of inputs/{day}.txt
+    pub input: Option<PathBuf>,
+
+    // use examples/{day}.txt instead of the real input
+    pub example: bool,
+
+    pub part: Part,
+    pub format: Format,
+
+    // false when --no-color is passed
+    pub color: bool,
+
+    // append each day's results to the history log
+    pub history: bool,
+
+    // print only the answers, with everything else going to stderr
+    pub quiet: bool,
+
+    // draw frames of what the solver is doing, for solvers which can
+    pub visualize: bool,
+
+    // also save the frames drawn by --visualize as an asciinema cast
+    pub record: Option<PathBuf>,
+
+    // values for the day's params, by name, in place of the puzzle's own
+    pub params: HashMap<String, String>,
+}
+
+/// Advent of Code 2025 solutions.
+#[derive(Debug, Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Solve a day, or several, and print the answers. This is the default subcommand.
+    Run {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Print a status line when solving takes longer than this many seconds [default: 10]
+        #[arg(long, value_parser = seconds)]
+        soft_deadline: Option<Duration>,
+
+        /// Give up on a day after this many seconds, reporting it as timed out.
+        #[arg(long, value_parser = seconds)]
+        timeout: Option<Duration>,
+
+        /// Print results as text or json.
+        #[arg(long, default_value = "text")]
+        format: Format,
+
+        /// Don't color text output.
+        #[arg(long)]
+        no_color: bool,
+
+        /// Print only the answers, part 1 then part 2 on their own lines.
+        #[arg(long, conflicts_with_all = ["format", "watch", "profile"])]
+        quiet: bool,
+
+        /// Append each day's answers and timing to results/history.jsonl.
+        #[arg(long)]
+        history: bool,
+
+        /// Animate what the solver is doing on stderr, for days which can. Slows solving down.
+        #[arg(long, conflicts_with_all = ["quiet", "verify", "watch", "profile"])]
+        visualize: bool,
+
+        /// Save the frames drawn by --visualize to this file, as an asciinema cast.
+        #[arg(long, value_name = "PATH", requires = "visualize")]
+        record: Option<PathBuf>,
+
+        /// Change a number from the puzzle's rules, like --param part1_batteries=3 for day 3. Can
+        /// be given more than once.
+        #[arg(
+            long = "param",
+            value_name = "NAME=VALUE",
+            value_parser = param,
+            conflicts_with_all = ["verify", "profile"]
+        )]
+        params: Vec<(String, String)>,
+
+        /// Check the answers against a brute-force solution, on small generated inputs.
+        #[arg(long, conflicts_with_all = ["input", "example", "watch", "profile", "quiet"])]
+        verify: bool,
+
+        /// Re-run whenever the day's source or input changes.
+        #[arg(long, conflicts_with = "profile")]
+        watch: bool,
+
+        /// Solve in a loop while sampling stacks, writing a pprof profile and a flamegraph.
+        #[arg(long)]
+        profile: bool,
+
+        /// How many seconds to keep solving while profiling [default: 10]
+        #[arg(long, value_parser = seconds, requires = "profile")]
+        duration: Option<Duration>,
+    },
+
+    /// Solve a day repeatedly and print timing statistics.
+    Bench {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Runs before measuring starts.
+        #[arg(long, default_value_t = DEFAULT_WARMUP)]
+        warmup: usize,
+
+        /// Measured runs.
+        #[arg(long, default_value_t = DEFAULT_ITERATIONS)]
+        iterations: usize,
+
+        /// Save the timings as a named baseline.
+        #[arg(long, value_name = "NAME")]
+        save_baseline: Option<String>,
+
+        /// Compare the timings to a named baseline.
+        #[arg(long, value_name = "NAME")]
+        compare: Option<String>,
+    },
+
+    /// Print the catalog of solvers.
+    List {
+        /// The days to list, like 3..=8. Lists every day by default.
+        #[arg(default_value = "all")]
+        day: String,
+
+        /// Print the catalog as text or json.
+        #[arg(long, default_value = "text")]
+        format: Format,
+    },
+
+    /// Scaffold a new day's solver and example file.
+    New { day: u32 },
+
+    /// Print a random, valid input for a day, for stress testing.
+    Gen {
+        day: u32,
+
+        /// How much to generate, like junctions for day 8 or columns of the polygon for day 9.
+        #[arg(long, default_value_t = DEFAULT_GEN_SIZE)]
+        size: usize,
+
+        /// Seed the generator, to generate the same input again.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Submit an answer for the real input to adventofcode.com.
+    Submit {
+        day: u32,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// The answer to submit. Solves the part when left out.
+        answer: Option<AnswerValue>,
+    },
+
+    /// Print a shell completion script.
+    Completions { shell: Shell },
+
+    /// Serve the solvers over HTTP, with POST /solve/{day} and GET /solvers.
+    Serve {
+        /// The address to listen on.
+        #[arg(long, default_value = serve::DEFAULT_ADDR)]
+        addr: String,
+    },
+
+    /// Keep the inputs in memory, taking commands like `run 9` or `bench 10` from stdin.
+    Daemon,
+
+    /// Record when each part was first solved correctly, and show how long after release it was.
+    Track {
+        /// The days to track, like 3..=8. Tracks every day by default.
+        #[arg(default_value = "all")]
+        day: String,
+    },
+}
+
+/// Which day(s) to solve, and with which input.
+#[derive(Debug, clap::Args)]
+struct Selection {
+    /// The day, "all", a range like 3..=8, or a list like 1,4,9.
+    day: String,
+
+    /// Read the input from this file, instead of inputs/{day}.txt.
+    #[arg(long, conflicts_with = "example")]
+    input: Option<PathBuf>,
+
+    /// Use the day's example from examples/{day}.txt.
+    #[arg(long)]
+    example: bool,
+
+    /// Which part to solve: 1, 2, or both.
+    #[arg(long, default_value = "both")]
+    part: Part,
+}
+
+impl Args {
+    /// Parses arguments, not including the program name. `run` is assumed when the first argument
+    /// isn't a subcommand, so `cargo run 9` still works.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, clap::Error> {
+        let mut args: Vec<String> = args.into_iter().collect();
+        let has_subcommand = args.first().is_some_and(|first| {
+            SUBCOMMANDS.contains(&first.as_str())
+                || ["-h", "--help", "-V", "--version"].contains(&first.as_str())
+        });
+        if !has_subcommand {
+            args.insert(0, "run".to_string());
+        }
+
+        let cli = Cli::try_parse_from(std::iter::once("aoc".to_string()).chain(args))?;
+        let mut parsed = Self {
+            command: Command::Run,
+            day: String::new(),
+            days: None,
+            soft_deadline: DEFAULT_SOFT_DEADLINE,
+            timeout: None,
+            input: None,
+            example: false,
+            part: Part::default(),
+            format: Format::default(),
+            color: true,
+            history: false,
+            quiet: false,
+            visualize: false,
+            record: None,
+            params: HashMap::new(),
+        };
+
+        match cli.command {
+            CliCommand::Run {
+                selection,
+                soft_deadline,
+                timeout,
+                format,
+                no_color,
+                quiet,
+                history,
+                visualize,
+                record,
+                params,
+                verify,
+                watch,
+                profile,
+                duration,
+            } => {
+                parsed.command = match (verify, watch, profile) {
+                    (true, _, _) => Command::Verify,
+                    (_, true, _) => Command::Watch,
+                    (_, _, true) => Command::Profile {
+                        duration: duration.unwrap_or(DEFAULT_PROFILE_DURATION),
+                    },
+                    _ => Command::Run,
+                };
+                parsed.soft_deadline = soft_deadline.unwrap_or(DEFAULT_SOFT_DEADLINE);
+                parsed.timeout = timeout;
+                parsed.format = format;
+                parsed.color = !no_color;
+                parsed.quiet = quiet;
+                parsed.history = history;
+                parsed.visualize = visualize;
+                parsed.record = record;
+                parsed.params = params.into_iter().collect();
+                parsed.select(selection);
+            }
+            CliCommand::Bench {
+                selection,
+                warmup,
+                iterations,
+                save_baseline,
+                compare,
+            } => {
+                parsed.command = Command::Bench {
+                    warmup,
+                    iterations,
+                    save_baseline,
+                    compare,
+                };
+                parsed.select(selection);
+            }
+            CliCommand::List { day, format } => {
+                parsed.command = Command::List;
+                parsed.day = day;
+                parsed.format = format;
+            }
+            CliCommand::New { day } => {
+                parsed.command = Command::New;
+                parsed.day = day.to_string();
+            }
+            CliCommand::Gen { day, size, seed } => {
+                parsed.command = Command::Gen { size, seed };
+                parsed.day = day.to_string();
+            }
+            CliCommand::Submit { day, part, answer } => {
+                parsed.command = Command::Submit { part, answer };
+                parsed.day = day.to_string();
+                parsed.part = if part == 1 { Part::One } else { Part::Two };
+            }
+            CliCommand::Completions { shell } => parsed.command = Command::Completions { shell },
+            CliCommand::Serve { addr } => parsed.command = Command::Serve { addr },
+            CliCommand::Daemon => parsed.command = Command::Daemon,
+            CliCommand::Track { day } => {
+                parsed.command = Command::Track;
+                parsed.day = day;
+            }
+        }
+
+        parsed.days = parse_days(&parsed.day)
+            .map_err(|e| Cli::command().error(clap::error::ErrorKind::InvalidValue, e))?;
+
+        // several days solve at once, and their frames would draw over each other
+        if parsed.visualize && parsed.days.is_some() {
+            return Err(Cli::command().error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--visualize can only show one day at a time",
+            ));
+        }
+
+        // each day has its own params, so they can't apply to several at once
+        if !parsed.params.is_empty() && parsed.days.is_some() {
+            return Err(Cli::command().error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--param can only be given for one day at a time",
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    fn select(&mut self, selection: Selection) {
+        self.day = selection.day;
+        self.input = selection.input;
+        self.example = selection.example;
+        self.part = selection.part;
+    }
+
+    /// The full command line interface, for generating completions.
+    pub fn command() -> clap::Command {
+        Cli::command()
+    }
+
+    /// The file to read the puzzle input for a day from.
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        if self.example {
+            return examples::path(&day.to_string());
+        }
+
+        self.input
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("inputs/{day}.txt")))
+    }
+
+    /// Whether the real puzzle input is being solved, rather than some other input.
+    pub fn is_real_input(&self) -> bool {
+        self.input.is_none() && !self.example
+    }
+}
+
+/// Describes bad arguments as a usage error, without clap's `error: ` prefix.
+pub fn usage_error(e: clap::Error) -> AocError {
+    let message = e.render().to_string();
+    AocError::Usage(message.trim_start_matches("error: ").trim_end().to_string())
+}
+
+/// Parses a number of seconds, like `2.5`.
+fn seconds(secs: &str) -> Result<Duration, String> {
+    secs.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("should be a number of seconds: {secs}"))
+}
+
+/// Parses a `--param`, like `part1_batteries=3`.
+fn param(param: &str) -> Result<(String, String), String> {
+    param
+        .split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("should be like name=value: {param}"))
+}
+
+/// Parses a selection of several days: `all`, a range like `3..=8` or `3..9`, or a list like
+/// `1,4,9`. Anything else is a single day, which is None.
+fn parse_days(day: &str) -> Result<Option<Vec<u32>>, String> {
+    let number = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("day should be a number: {s}"))
+    };
+
+    let days: Vec<u32> = if day == "all" {
+        solver::SOLVERS.iter().map(|s| s.day()).collect()
+    } else if let Some((start, end)) = day.split_once("..=") {
+        (number(start)?..=number(end)?).collect()
+    } else if let Some((start, end)) = day.split_once("..") {
+        (number(start)?..number(end)?).collect()
+    } else if day.contains(',') {
+        day.split(',').map(number).collect::<Result<_, _>>()?
+    } else {
+        return Ok(None);
+    };
+
+    if days.is_empty() {
+        return Err(format!("no days selected by {day}"));
+    }
+
+    Ok(Some(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parse_day_only() {
+        let args = parse(&["9"]).unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.day, "9");
+        assert_eq!(args.soft_deadline, DEFAULT_SOFT_DEADLINE);
+    }
+
+    #[test]
+    fn parse_bench() {
+        let args = parse(&["bench", "9"]).unwrap();
+        assert_eq!(args.day, "9");
+        assert_eq!(
+            args.command,
+            Command::Bench {
+                warmup: DEFAULT_WARMUP,
+                iterations: DEFAULT_ITERATIONS,
+                save_baseline: None,
+                compare: None,
+            }
+        );
+
+        let args = parse(&["bench", "9", "--warmup", "0", "--iterations", "50"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Bench {
+                warmup: 0,
+                iterations: 50,
+                save_baseline: None,
+                compare: None,
+            }
+        );
+
+        let args = parse(&["bench", "9", "--save-baseline", "new", "--compare", "old"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Bench {
+                warmup: DEFAULT_WARMUP,
+                iterations: DEFAULT_ITERATIONS,
+                save_baseline: Some("new".to_string()),
+                compare: Some("old".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_soft_deadline() {
+        let args = parse(&["--soft-deadline", "2.5", "12"]).unwrap();
+        assert_eq!(args.day, "12");
+        assert_eq!(args.soft_deadline, Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn parse_timeout() {
+        assert_eq!(parse(&["9"]).unwrap().timeout, None);
+
+        let args = parse(&["all", "--timeout", "30"]).unwrap();
+        assert_eq!(args.timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn parse_quiet() {
+        assert!(!parse(&["9"]).unwrap().quiet);
+        assert!(parse(&["9", "--quiet"]).unwrap().quiet);
+        assert!(parse(&["9", "--quiet", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn parse_verify() {
+        assert_eq!(parse(&["9", "--verify"]).unwrap().command, Command::Verify);
+        assert_eq!(
+            parse(&["all", "--verify"]).unwrap().command,
+            Command::Verify
+        );
+        assert!(parse(&["9", "--verify", "--example"]).is_err());
+    }
+
+    #[test]
+    fn parse_visualize() {
+        assert!(!parse(&["4"]).unwrap().visualize);
+        assert!(parse(&["4", "--visualize"]).unwrap().visualize);
+        assert!(parse(&["4", "--visualize", "--quiet"]).is_err());
+        assert!(parse(&["all", "--visualize"]).is_err());
+    }
+
+    #[test]
+    fn parse_record() {
+        assert_eq!(parse(&["4", "--visualize"]).unwrap().record, None);
+        assert_eq!(
+            parse(&["4", "--visualize", "--record", "4.cast"])
+                .unwrap()
+                .record,
+            Some(PathBuf::from("4.cast"))
+        );
+        assert!(parse(&["4", "--record", "4.cast"]).is_err());
+    }
+
+    #[test]
+    fn parse_params() {
+        assert!(parse(&["3"]).unwrap().params.is_empty());
+        assert_eq!(
+            parse(&[
+                "3",
+                "--param",
+                "part1_batteries=3",
+                "--param",
+                "part2_batteries=30"
+            ])
+            .unwrap()
+            .params,
+            HashMap::from([
+                ("part1_batteries".to_string(), "3".to_string()),
+                ("part2_batteries".to_string(), "30".to_string()),
+            ])
+        );
+        assert!(parse(&["3", "--param", "part1_batteries"]).is_err());
+        assert!(parse(&["3", "--param", "=5"]).is_err());
+        assert!(parse(&["all", "--param", "part1_batteries=3"]).is_err());
+        assert!(parse(&["3", "--verify", "--param", "part1_batteries=3"]).is_err());
+    }
+
+    #[test]
+    fn parse_history() {
+        assert!(!parse(&["9"]).unwrap().history);
+        assert!(parse(&["all", "--history"]).unwrap().history);
+    }
+
+    #[test]
+    fn parse_profile() {
+        let args = parse(&["run", "9", "--profile"]).unwrap();
+        assert_eq!(args.day, "9");
+        assert_eq!(
+            args.command,
+            Command::Profile {
+                duration: DEFAULT_PROFILE_DURATION
+            }
+        );
+
+        let args = parse(&["9", "--profile", "--duration", "0.5"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Profile {
+                duration: Duration::from_millis(500)
+            }
+        );
+
+        assert!(parse(&["bench", "--profile", "9"]).is_err());
+        assert!(parse(&["--watch", "--profile", "9"]).is_err());
+        assert!(parse(&["9", "--duration", "5"]).is_err());
+    }
+
+    #[test]
+    fn parse_day_selections() {
+        assert_eq!(parse(&["9"]).unwrap().days, None);
+        assert_eq!(
+            parse(&["all"]).unwrap().days,
+            Some((1..=12).collect::<Vec<_>>())
+        );
+        assert_eq!(parse(&["3..=5"]).unwrap().days, Some(vec![3, 4, 5]));
+        assert_eq!(parse(&["3..5"]).unwrap().days, Some(vec![3, 4]));
+        assert_eq!(parse(&["1,4,9"]).unwrap().days, Some(vec![1, 4, 9]));
+
+        assert!(parse(&["5..=3"]).is_err());
+        assert!(parse(&["1,four"]).is_err());
+        assert!(parse(&["..=3"]).is_err());
+    }
+
+    #[test]
+    fn parse_list() {
+        let args = parse(&["list"]).unwrap();
+        assert_eq!(args.command, Command::List);
+        assert_eq!(args.day, "all");
+
+        let args = parse(&["list", "3..=4", "--format", "json"]).unwrap();
+        assert_eq!(args.days, Some(vec![3, 4]));
+        assert_eq!(args.format, Format::Json);
+    }
+
+    #[test]
+    fn parse_watch() {
+        let args = parse(&["--watch", "9", "--part", "1"]).unwrap();
+        assert_eq!(args.command, Command::Watch);
+        assert_eq!(args.day, "9");
+        assert_eq!(args.part, Part::One);
+    }
+
+    #[test]
+    fn parse_new() {
+        let args = parse(&["new", "13"]).unwrap();
+        assert_eq!(args.command, Command::New);
+        assert_eq!(args.day, "13");
+
+        assert!(parse(&["new", "thirteen"]).is_err());
+    }
+
+    #[test]
+    fn parse_gen() {
+        let args = parse(&["gen", "9"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Gen {
+                size: DEFAULT_GEN_SIZE,
+                seed: None
+            }
+        );
+        assert_eq!(args.day, "9");
+
+        let args = parse(&["gen", "10", "--size", "5", "--seed", "42"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Gen {
+                size: 5,
+                seed: Some(42)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_submit() {
+        let args = parse(&["submit", "9", "2"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Submit {
+                part: 2,
+                answer: None
+            }
+        );
+        assert_eq!(args.day, "9");
+
+        let args = parse(&["submit", "9", "1", "4750092396"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Submit {
+                part: 1,
+                answer: Some(AnswerValue::Int(4750092396))
+            }
+        );
+
+        assert!(parse(&["submit", "9", "3"]).is_err());
+    }
+
+    #[test]
+    fn parse_completions() {
+        let args = parse(&["completions", "zsh"]).unwrap();
+        assert_eq!(args.command, Command::Completions { shell: Shell::Zsh });
+    }
+
+    #[test]
+    fn parse_track() {
+        let args = parse(&["track"]).unwrap();
+        assert_eq!(args.command, Command::Track);
+        assert_eq!(args.days, Some((1..=12).collect()));
+
+        let args = parse(&["track", "9"]).unwrap();
+        assert_eq!(args.day, "9");
+        assert_eq!(args.days, None);
+    }
+
+    #[test]
+    fn parse_daemon() {
+        assert_eq!(parse(&["daemon"]).unwrap().command, Command::Daemon);
+    }
+
+    #[test]
+    fn parse_serve() {
+        let args = parse(&["serve"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Serve {
+                addr: serve::DEFAULT_ADDR.to_string()
+            }
+        );
+
+        let args = parse(&["serve", "--addr", "0.0.0.0:80"]).unwrap();
+        assert_eq!(
+            args.command,
+            Command::Serve {
+                addr: "0.0.0.0:80".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn cli_is_well_formed() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn default_input_path() {
+        let args = parse(&["9"]).unwrap();
+        assert_eq!(args.input_path(9), PathBuf::from("inputs/9.txt"));
+    }
+
+    #[test]
+    fn override_input_path() {
+        let args = parse(&["9", "--input", "examples/9.txt"]).unwrap();
+        assert_eq!(args.input_path(9), PathBuf::from("examples/9.txt"));
+    }
+
+    #[test]
+    fn example_input_path() {
+        let args = parse(&["9", "--example"]).unwrap();
+        assert_eq!(args.input_path(9), PathBuf::from("examples/9.txt"));
+        assert!(!args.is_real_input());
+    }
+
+    #[test]
+    fn parse_part() {
+        assert_eq!(parse(&["9"]).unwrap().part, Part::Both);
+        assert_eq!(parse(&["9", "--part", "2"]).unwrap().part, Part::Two);
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(parse(&["9"]).unwrap().format, Format::Text);
+        assert_eq!(
+            parse(&["9", "--format", "json"]).unwrap().format,
+            Format::Json
+        );
+    }
+
+    #[test]
+    fn parse_no_color() {
+        assert!(parse(&["9"]).unwrap().color);
+        assert!(!parse(&["9", "--no-color"]).unwrap().color);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["9", "--soft-deadline"]).is_err());
+        assert!(parse(&["9", "--input"]).is_err());
+        assert!(parse(&["9", "--part", "3"]).is_err());
+        assert!(parse(&["9", "--format", "yaml"]).is_err());
+        assert!(parse(&["9", "--soft-deadline", "soon"]).is_err());
+        assert!(parse(&["9", "--bogus"]).is_err());
+        assert!(parse(&["9", "10"]).is_err());
+        assert!(parse(&["bench"]).is_err());
+        assert!(parse(&["9", "--example", "--input", "x.txt"]).is_err());
+        assert!(parse(&["bench", "--watch", "9"]).is_err());
+        assert!(parse(&["bench", "9", "--iterations", "many"]).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    panic,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::error::AocError;
+
+/// Something a solver reported while it was running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveEvent {
+    /// `done` out of `total` units of work have been completed.
+    Progress { done: usize, total: usize },
+
+    /// The solver moved on to a new phase of work (ex: "parse", "search") at the given time.
+    Phase { name: &'static str, at: Instant },
+
+    /// A picture of what the solver is doing, only drawn for `--visualize`.
+    Frame(String),
+}
+
+/// Receives reports of how far along a long-running loop is.
+///
+/// Solvers usually report through their `SolveCtx`, but helpers deep inside a solver can take a
+/// `&dyn Progress` instead, so tests can hand them a closure.
+pub trait Progress {
+    /// Reports that `done` of `total` units of work are complete.
+    fn report(&self, done: usize, total: usize);
+}
+
+impl<F: Fn(usize, usize)> Progress for F {
+    fn report(&self, done: usize, total: usize) {
+        self(done, total)
+    }
+}
+
+/// Asks a running solver to give up, shared between the runner and the solver's `SolveCtx`.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    /// Cancels the solver. It stops the next time it reports anything, so a solver which never
+    /// reports keeps running until it's done.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Handed to solvers so long-running ones can report what they're doing.
+///
+/// Reporting is fire and forget. A default context has nobody listening, so solvers can always
+/// report without caring whether the runner is watching.
+#[derive(Default)]
+pub struct SolveCtx {
+    events: Option<Sender<SolveEvent>>,
+    cancel: Cancel,
+
+    // whether anyone wants frames, which are often too slow to draw otherwise
+    visualize: bool,
+
+    // values given with --param, by name, for solvers whose puzzle rules have numbers in them
+    params: HashMap<String, String>,
+}
+
+impl SolveCtx {
+    /// Creates a context, along with the receiving end of everything reported through it.
+    pub fn new() -> (Self, Receiver<SolveEvent>) {
+        let (sender, receiver) = channel();
+        let ctx = Self {
+            events: Some(sender),
+            cancel: Cancel::default(),
+            visualize: false,
+            params: HashMap::new(),
+        };
+
+        (ctx, receiver)
+    }
+
+    /// Asks the solver to draw frames of what it's doing, for `--visualize`.
+    pub fn visualized(mut self, visualize: bool) -> Self {
+        self.visualize = visualize;
+        self
+    }
+
+    /// Hands the solver values for its params, by name, from `--param name=value`.
+    pub fn with_params(mut self, params: HashMap<String, String>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// The value given for the param called `name`, or `default` when none was given, which is
+    /// the number the puzzle itself uses.
+    pub fn param<T: FromStr>(&self, name: &str, default: T) -> Result<T, AocError> {
+        match self.params.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| AocError::Usage(format!("--param {name} is not valid: {value}"))),
+            None => Ok(default),
+        }
+    }
+
+    /// Reports that `done` of `total` units of work are complete.
+    pub fn progress(&self, done: usize, total: usize) {
+        self.send(SolveEvent::Progress { done, total });
+    }
+
+    /// Reports that the solver has started a new phase of work.
+    pub fn phase(&self, name: &'static str) {
+        tracing::debug!(phase = name, "phase started");
+        // only read the clock when someone's listening, since there isn't one in the browser
+        if self.events.is_some() {
+            self.send(SolveEvent::Phase {
+                name,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    /// Reports a picture of what the solver is doing. Drawing one can take far longer than the
+    /// work it shows, so `draw` is only called when visualizing.
+    pub fn frame(&self, draw: impl FnOnce() -> String) {
+        if self.visualize {
+            self.send(SolveEvent::Frame(draw()));
+        }
+    }
+
+    /// A handle for cancelling the solver using this context.
+    pub fn cancel_handle(&self) -> Cancel {
+        self.cancel.clone()
+    }
+
+    fn send(&self, event: SolveEvent) {
+        if self.cancel.is_cancelled() {
+            // unwinds without running the panic hook, since this isn't a bug
+            panic::resume_unwind(Box::new("solver was cancelled"));
+        }
+
+        if let Some(events) = &self.events {
+            // the listener hanging up shouldn't stop the solver, so ignore failures
+            let _ = events.send(event);
+        }
+    }
+}
+
+impl Progress for SolveCtx {
+    fn report(&self, done: usize, total: usize) {
+        self.progress(done, total);
+    }
+}
+
+/// How long each phase lasted, given when each one started. A phase lasts until the next one
+/// starts, or until `end` for the last one.
+pub fn phase_durations(
+    starts: &[(&'static str, Instant)],
+    end: Instant,
+) -> Vec<(&'static str, Duration)> {
+    let ends = starts.iter().skip(1).map(|(_, at)| *at).chain([end]);
+    starts
+        .iter()
+        .zip(ends)
+        .map(|((name, start), end)| (*name, end - *start))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_received_in_order() {
+        let (ctx, events) = SolveCtx::new();
+        ctx.phase("parse");
+        ctx.progress(1, 10);
+        drop(ctx);
+
+        let received: Vec<SolveEvent> = events.iter().collect();
+        assert!(matches!(
+            received[..],
+            [
+                SolveEvent::Phase { name: "parse", .. },
+                SolveEvent::Progress { done: 1, total: 10 },
+            ]
+        ));
+    }
+
+    #[test]
+    fn ctx_reports_progress_as_events() {
+        let (ctx, events) = SolveCtx::new();
+        (&ctx as &dyn Progress).report(3, 4);
+        drop(ctx);
+
+        assert_eq!(
+            events.iter().collect::<Vec<_>>(),
+            [SolveEvent::Progress { done: 3, total: 4 }]
+        );
+    }
+
+    #[test]
+    fn closures_receive_progress() {
+        let reports = std::cell::RefCell::new(vec![]);
+        let progress = |done, total| reports.borrow_mut().push((done, total));
+
+        progress.report(1, 2);
+        progress.report(2, 2);
+        assert_eq!(reports.into_inner(), [(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn phases_last_until_the_next_one() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(
+            phase_durations(&[("parse", at(0)), ("search", at(3))], at(10)),
+            [
+                ("parse", Duration::from_millis(3)),
+                ("search", Duration::from_millis(7))
+            ]
+        );
+        assert_eq!(phase_durations(&[], at(10)), []);
+    }
+
+    #[test]
+    fn frames_are_only_drawn_when_visualizing() {
+        let (ctx, events) = SolveCtx::new();
+        ctx.frame(|| unreachable!("nobody asked for frames"));
+
+        let ctx = ctx.visualized(true);
+        ctx.frame(|| "@.@".to_string());
+        drop(ctx);
+
+        assert_eq!(
+            events.iter().collect::<Vec<_>>(),
+            [SolveEvent::Frame("@.@".to_string())]
+        );
+    }
+
+    #[test]
+    fn cancelled_solvers_stop_when_they_report() {
+        let ctx = SolveCtx::default();
+        ctx.progress(1, 10);
+
+        ctx.cancel_handle().cancel();
+        let stopped = panic::catch_unwind(|| ctx.progress(2, 10));
+        assert!(stopped.is_err());
+    }
+
+    #[test]
+    fn params_fall_back_to_their_defaults() {
+        let params = HashMap::from([
+            ("threshold".to_string(), "5".to_string()),
+            ("size".to_string(), "big".to_string()),
+        ]);
+        let ctx = SolveCtx::default().with_params(params);
+
+        assert_eq!(ctx.param("threshold", 4), Ok(5));
+        assert_eq!(ctx.param("count", 12), Ok(12));
+        assert!(matches!(ctx.param("size", 0), Err(AocError::Usage(_))));
+    }
+
+    #[test]
+    fn default_ctx_reports_to_nobody() {
+        let ctx = SolveCtx::default();
+        ctx.phase("parse");
+        ctx.progress(1, 10);
+    }
+}
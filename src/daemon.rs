@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use aoc::{
+    error::AocError,
+    solver::{self, SOLVERS},
+};
+
+use crate::{
+    bench,
+    cli::{self, Args, Command},
+    expected::Expected,
+    report::Style,
+    runner,
+};
+
+/// Puzzle inputs kept in memory, by the path they were read from. Each solver parses its own
+/// input, so the text is what's kept.
+#[derive(Default)]
+struct Inputs(HashMap<PathBuf, String>);
+
+impl Inputs {
+    /// Reads every day's real input. Days without one are left out, and read if they show up.
+    fn preload() -> Self {
+        let defaults = Args::parse(["run".to_string(), "all".to_string()])
+            .expect("run all should always parse");
+
+        let inputs = SOLVERS
+            .iter()
+            .map(|solver| defaults.input_path(solver.day()))
+            .filter_map(|path| Some((path.clone(), std::fs::read_to_string(path).ok()?)))
+            .collect();
+
+        Self(inputs)
+    }
+
+    /// The input read from the path, reading it only the first time it's asked for.
+    fn get(&mut self, path: &Path) -> Result<&str, AocError> {
+        if !self.0.contains_key(path) {
+            let input = std::fs::read_to_string(path)
+                .map_err(|_| AocError::MissingInput(path.to_path_buf()))?;
+            self.0.insert(path.to_path_buf(), input);
+        }
+
+        Ok(&self.0[path])
+    }
+}
+
+/// Keeps the inputs in memory and answers commands from stdin, a line each, so repeated runs
+/// during an optimization session skip starting a process and reading files:
+///
+/// - A `run` or `bench` command line for one day, like `run 9 --part 2` or `bench 10`.
+/// - `reload` forgets the inputs, so edited ones are read again.
+/// - `quit` stops, as does closing stdin.
+pub fn daemon(answers: &HashMap<u32, Expected>) -> Result<(), String> {
+    let mut inputs = Inputs::preload();
+    eprintln!(
+        "cached {} inputs, waiting for commands like `run 9` or `bench 10`",
+        inputs.0.len()
+    );
+
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("could not read command: {e}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match handle(&line, &mut inputs, answers) {
+            Ok(Some(response)) => response,
+            Ok(None) => break,
+            Err(e) => format!("ERROR: {e}"),
+        };
+        writeln!(stdout, "{response}")
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("could not respond: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Carries out one command, returning the response to print, or None when it's time to stop.
+fn handle(
+    line: &str,
+    inputs: &mut Inputs,
+    answers: &HashMap<u32, Expected>,
+) -> Result<Option<String>, AocError> {
+    match line.trim() {
+        "quit" => return Ok(None),
+        "reload" => {
+            inputs.0.clear();
+            return Ok(Some("forgot the cached inputs".to_string()));
+        }
+        _ => {}
+    }
+
+    let args = match Args::parse(line.split_whitespace().map(String::from)) {
+        Ok(args) => args,
+        Err(e) if !e.use_stderr() => return Ok(Some(e.render().to_string())),
+        Err(e) => return Err(cli::usage_error(e)),
+    };
+    if !matches!(args.command, Command::Run | Command::Bench { .. }) {
+        return Err(AocError::Usage(
+            "the daemon only runs or benches".to_string(),
+        ));
+    }
+    if args.days.is_some() {
+        return Err(AocError::Usage(
+            "the daemon runs one day at a time".to_string(),
+        ));
+    }
+
+    let solver = solver::find(&args.day).ok_or_else(|| AocError::UnknownDay(args.day.clone()))?;
+    let input = inputs.get(&args.input_path(solver.day()))?;
+
+    match args.command {
+        Command::Run => {
+            let mut report = runner::solve(solver, input, &args, Instant::now(), false)?;
+            report.expected = runner::expected_for(solver.day(), &args, answers);
+            Ok(Some(report.render(args.format, Style { color: false })))
+        }
+        Command::Bench {
+            warmup,
+            iterations,
+            save_baseline: None,
+            compare: None,
+        } => {
+            let measurements = bench::bench(solver, input, args.part, warmup, iterations)?;
+            Ok(Some(bench::render(&measurements)))
+        }
+        Command::Bench { .. } => Err(AocError::Usage(
+            "baselines can only be saved or compared by the bench command".to_string(),
+        )),
+        _ => unreachable!("only runs and benches get this far"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::examples;
+
+    fn respond(line: &str, inputs: &mut Inputs) -> String {
+        handle(line, inputs, &HashMap::new()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn run_from_the_cache() {
+        // cached under the real input's path, which doesn't exist here
+        let mut inputs = Inputs::default();
+        inputs
+            .0
+            .insert(PathBuf::from("inputs/1.txt"), examples::read("1"));
+
+        let response = respond("run 1 --part 2", &mut inputs);
+        assert!(response.contains("part2: 6"), "{response}");
+        assert!(!response.contains("part1"), "{response}");
+    }
+
+    #[test]
+    fn bare_days_are_run() {
+        let response = respond("1 --example", &mut Inputs::default());
+        assert!(response.starts_with("Day 1\n  part1: 3"), "{response}");
+    }
+
+    #[test]
+    fn bench_from_the_cache() {
+        let response = respond(
+            "bench 1 --example --warmup 0 --iterations 2",
+            &mut Inputs::default(),
+        );
+        assert!(response.contains("part1"), "{response}");
+    }
+
+    #[test]
+    fn inputs_are_read_once() {
+        let mut inputs = Inputs::default();
+        respond("run 1 --example", &mut inputs);
+        assert_eq!(inputs.0.len(), 1);
+
+        respond("reload", &mut inputs);
+        assert!(inputs.0.is_empty());
+    }
+
+    #[test]
+    fn quit_stops() {
+        let stopped = handle("quit", &mut Inputs::default(), &HashMap::new());
+        assert_eq!(stopped, Ok(None));
+    }
+
+    #[test]
+    fn unsupported_commands_are_errors() {
+        let mut inputs = Inputs::default();
+        let mut handle = |line| handle(line, &mut inputs, &HashMap::new());
+
+        assert!(matches!(handle("list"), Err(AocError::Usage(_))));
+        assert!(matches!(handle("run all"), Err(AocError::Usage(_))));
+        assert!(matches!(handle("run --bogus"), Err(AocError::Usage(_))));
+        assert!(matches!(
+            handle("bench 1 --example --compare main"),
+            Err(AocError::Usage(_))
+        ));
+        assert!(matches!(
+            handle("run 1 --input does/not/exist.txt"),
+            Err(AocError::MissingInput(_))
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{path::PathBuf, time::Duration};
+
+use thiserror::Error;
+
+/// Everything which can stop the runner. Each kind of failure has its own exit status, so shell
+/// scripts and git hooks can tell them apart.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AocError {
+    /// The arguments didn't make sense.
+    #[error("{0}")]
+    Usage(String),
+
+    /// There's no solver for the requested day.
+    #[error("{0} is not yet implemented")]
+    UnknownDay(String),
+
+    /// The input file couldn't be read.
+    #[error("file does not exist: {}", .0.display())]
+    MissingInput(PathBuf),
+
+    /// The input couldn't be parsed.
+    #[error("could not parse input: {0}")]
+    Parse(String),
+
+    /// The input parsed, but the solver couldn't find an answer for it.
+    #[error("no solution found: {0}")]
+    Unsolvable(String),
+
+    /// The solver was still running when `--timeout` ran out.
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// Part 1's answer didn't match the expected answer.
+    #[error("part1 is wrong")]
+    WrongPart1,
+
+    /// Part 2's answer didn't match the expected answer.
+    #[error("part2 is wrong")]
+    WrongPart2,
+
+    /// The solver panicked. Solvers report bad input as errors, so this is a bug in the solver
+    /// rather than in the input.
+    #[error("solver crashed: {0}")]
+    Crashed(String),
+
+    /// Anything else, like an answers file or baseline which couldn't be read.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AocError {
+    /// The process exit status for this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AocError::Usage(_) | AocError::UnknownDay(_) | AocError::Other(_) => 1,
+            AocError::MissingInput(_) => 2,
+            AocError::Parse(_) => 3,
+            AocError::WrongPart1 => 4,
+            AocError::WrongPart2 => 5,
+            AocError::Unsolvable(_) => 6,
+            // what a panicking Rust program would exit with
+            AocError::Crashed(_) => 101,
+            AocError::Timeout(_) => 7,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_exit_codes_are_distinct() {
+        let codes = [
+            AocError::MissingInput(PathBuf::new()).exit_code(),
+            AocError::Parse(String::new()).exit_code(),
+            AocError::WrongPart1.exit_code(),
+            AocError::WrongPart2.exit_code(),
+            AocError::Unsolvable(String::new()).exit_code(),
+            AocError::Crashed(String::new()).exit_code(),
+            AocError::Timeout(Duration::ZERO).exit_code(),
+        ];
+
+        for (i, code) in codes.iter().enumerate() {
+            assert_ne!(*code, 0);
+            assert_ne!(*code, 1);
+            assert!(!codes[i + 1..].contains(code));
+        }
+    }
+}
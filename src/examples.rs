@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+const EXAMPLES_DIR: &str = "examples";
+
+/// Where an example input is stored. Most are named by day, like "9", but days with a separate
+/// example for part 2 also have one like "11-2".
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(EXAMPLES_DIR).join(format!("{name}.txt"))
+}
+
+/// Reads an example input, like the ones given in the puzzle descriptions.
+pub fn read(name: &str) -> String {
+    let path = path(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("example should exist: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_has_an_example() {
+        for day in 1..=12 {
+            assert!(path(&day.to_string()).exists(), "missing example for {day}");
+        }
+    }
+}
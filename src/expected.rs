@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use aoc::solver::AnswerValue;
+use serde::Deserialize;
+
+/// Where the known correct answers for the real inputs are kept, keyed by day.
+pub const ANSWERS_PATH: &str = "answers.json";
+
+/// The known correct answers for a day. Parts which haven't been solved yet are None.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Expected {
+    pub part1: Option<AnswerValue>,
+    pub part2: Option<AnswerValue>,
+}
+
+impl Expected {
+    /// Checks an answer for part 1, returning None when the correct answer isn't known.
+    pub fn check_part1(&self, answer: &AnswerValue) -> Option<bool> {
+        self.part1.as_ref().map(|expected| expected == answer)
+    }
+
+    /// Checks an answer for part 2, returning None when the correct answer isn't known.
+    pub fn check_part2(&self, answer: &AnswerValue) -> Option<bool> {
+        self.part2.as_ref().map(|expected| expected == answer)
+    }
+}
+
+/// Parses expected answers, like: `{"1": {"part1": 1076, "part2": 6379}}`. Answers too big for a
+/// JSON number, or which aren't numbers at all, are written as strings.
+pub fn parse(json: &str) -> Result<HashMap<u32, Expected>, String> {
+    serde_json::from_str(json).map_err(|e| format!("could not parse expected answers: {e}"))
+}
+
+/// Loads the expected answers from `ANSWERS_PATH`. Having no answers file is fine, it just means
+/// nothing can be checked.
+pub fn load() -> Result<HashMap<u32, Expected>, String> {
+    match std::fs::read_to_string(ANSWERS_PATH) {
+        Ok(json) => parse(&json),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers =
+            parse(r#"{"1": {"part1": 1076, "part2": 6379}, "12": {"part1": 3}}"#).unwrap();
+
+        assert_eq!(
+            answers[&1],
+            Expected {
+                part1: Some(AnswerValue::Int(1076)),
+                part2: Some(AnswerValue::Int(6379))
+            }
+        );
+        assert_eq!(answers[&12].part2, None);
+    }
+
+    #[test]
+    fn parse_text_answers() {
+        let answers = parse(r#"{"3": {"part1": "EFJKZ", "part2": "42"}}"#).unwrap();
+
+        assert_eq!(
+            answers[&3].part1,
+            Some(AnswerValue::Text("EFJKZ".to_string()))
+        );
+        assert_eq!(answers[&3].check_part2(&AnswerValue::Int(42)), Some(true));
+    }
+
+    #[test]
+    fn parse_malformed_answers() {
+        assert!(parse(r#"{"one": {"part1": 1}}"#).is_err());
+        assert!(parse("part1: 1").is_err());
+    }
+
+    #[test]
+    fn check_answers() {
+        let expected = Expected {
+            part1: Some(AnswerValue::Int(50)),
+            part2: None,
+        };
+
+        assert_eq!(expected.check_part1(&AnswerValue::Int(50)), Some(true));
+        assert_eq!(expected.check_part1(&AnswerValue::Int(51)), Some(false));
+        assert_eq!(expected.check_part2(&AnswerValue::Int(24)), None);
+    }
+
+    #[test]
+    fn committed_answers_parse() {
+        assert!(load().is_ok());
+    }
+}
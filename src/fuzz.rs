@@ -0,0 +1,66 @@
+//! Entry points for the fuzz targets in `fuzz/`, which throw arbitrary input at each day's
+//! parser. Parsers turn bad input into an error rather than panicking, so any panic the fuzzer
+//! finds is a bug.
+
+use crate::{error::AocError, problems::*};
+
+/// Parses the input the way the day's solver does, throwing away whatever was parsed.
+pub fn parse(day: u32, input: &str) -> Result<(), AocError> {
+    match day {
+        1 => problem1::parse(input).map(drop),
+        2 => problem2::parse(input).map(drop),
+        3 => problem3::parse(input).map(drop),
+        4 => problem4::HelpfulDiagram::parse(input).map(drop),
+        5 => problem5::parse(input).map(drop),
+        6 => problem6::parse(input).map(drop),
+        7 => problem7::parse(input).map(drop),
+        8 => problem8::parse(input).map(drop),
+        9 => problem9::parse(input).map(drop),
+        10 => problem10::parse(input).map(drop),
+        11 => problem11::parse(input).map(drop),
+        12 => problem12::parse(input).map(drop),
+        _ => Err(AocError::UnknownDay(day.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn examples_parse() {
+        for day in 1..=12 {
+            let input = examples::read(&day.to_string());
+            assert_eq!(parse(day, &input), Ok(()), "day {day}");
+        }
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        // each of these used to panic in some day's parser
+        let malformed = [
+            "x",
+            "L",
+            "1-",
+            "[",
+            "(",
+            "{",
+            "1,2",
+            "1,2,3,4",
+            "@\n@@",
+            "a:",
+            "7,1\n11,7",
+            "*\n1",
+            "[.#] (5) {1,2}",
+            "[.#] ( {1,2}",
+            "4x4: 1 2",
+        ];
+
+        for day in 1..=12 {
+            for input in malformed {
+                assert!(parse(day, input).is_err(), "day {day} parsed {input:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Synthetic puzzle inputs, for stress testing solvers and seeing how they scale beyond the one
+//! official input. Only days whose inputs are worth scaling up have a generator.
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{error::AocError, problems::*};
+
+/// Generates a valid input for a day, with `size` of whatever the day's input is made of. The same
+/// seed always generates the same input.
+pub fn generate(day: u32, size: usize, seed: u64) -> Result<String, AocError> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    match day {
+        2 => Ok(problem2::generate(size, rng)),
+        3 => Ok(problem3::generate(size, rng)),
+        5 => Ok(problem5::generate(size, rng)),
+        8 => Ok(problem8::generate(size, rng)),
+        9 => Ok(problem9::generate(size, rng)),
+        10 => Ok(problem10::generate(size, rng)),
+        _ => Err(AocError::Usage(format!("day {day} has no input generator"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ctx::SolveCtx, fuzz, solver};
+
+    const DAYS: [u32; 6] = [2, 3, 5, 8, 9, 10];
+
+    #[test]
+    fn generated_inputs_parse() {
+        for day in DAYS {
+            for seed in 0..20 {
+                let input = generate(day, 30, seed).unwrap();
+                assert_eq!(fuzz::parse(day, &input), Ok(()), "day {day}, seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        for day in DAYS {
+            let input = generate(day, 10, 1).unwrap();
+            let solver = solver::find(&day.to_string()).unwrap();
+            let ctx = SolveCtx::default();
+
+            solver.solve_part1(&input, &ctx).unwrap();
+            solver.solve_part2(&input, &ctx).unwrap();
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_input() {
+        for day in DAYS {
+            assert_eq!(generate(day, 10, 7), generate(day, 10, 7));
+            assert_ne!(generate(day, 10, 7), generate(day, 10, 8));
+        }
+    }
+
+    #[test]
+    fn days_without_a_generator() {
+        assert!(matches!(generate(1, 10, 0), Err(AocError::Usage(_))));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(generate(2, 25, 0).unwrap().split(',').count(), 25);
+        let banks = generate(3, 25, 0).unwrap();
+        assert_eq!(banks.lines().count(), 10);
+        assert!(banks.lines().all(|bank| bank.len() == 25));
+        assert_eq!(generate(5, 25, 0).unwrap().lines().count(), 51);
+        assert_eq!(generate(8, 25, 0).unwrap().lines().count(), 25);
+        assert_eq!(generate(9, 25, 0).unwrap().lines().count(), 100);
+        assert_eq!(generate(10, 25, 0).unwrap().lines().count(), 25);
+    }
+}
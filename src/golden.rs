@@ -0,0 +1,62 @@
+//! Full-input tests for every day, checking the solvers still get the known answers in
+//! answers.json. Inputs aren't checked in, so a day without its input (or without known answers)
+//! is skipped rather than failed.
+
+use aoc::solver;
+
+use crate::{cli::Args, expected, runner};
+
+/// Generates a test per day, named like `day9`, which checks that day's full input.
+macro_rules! golden_tests {
+    ($($name:ident => $day:literal,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check($day);
+            }
+        )*
+    };
+}
+
+golden_tests! {
+    day1 => 1,
+    day2 => 2,
+    day3 => 3,
+    day4 => 4,
+    day5 => 5,
+    day6 => 6,
+    day7 => 7,
+    day8 => 8,
+    day9 => 9,
+    day10 => 10,
+    day11 => 11,
+    day12 => 12,
+}
+
+/// Solves the day's real input, asserting each part with a known answer is still correct.
+fn check(day: u32) {
+    let args = Args::parse([day.to_string()]).unwrap();
+    let answers = expected::load().unwrap();
+    let Some(expected) = answers.get(&day) else {
+        eprintln!(
+            "skipping day {day}: no answers in {}",
+            expected::ANSWERS_PATH
+        );
+        return;
+    };
+
+    let input = args.input_path(day);
+    if !input.exists() {
+        eprintln!("skipping day {day}: {} is missing", input.display());
+        return;
+    }
+
+    let solver = solver::find(&day.to_string()).unwrap();
+    let report = runner::run_day(solver, &args, &answers).unwrap();
+    if expected.part1.is_some() {
+        assert_eq!(report.part1, expected.part1, "day {day} part1");
+    }
+    if expected.part2.is_some() {
+        assert_eq!(report.part2, expected.part2, "day {day} part2");
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::Serialize;
+
+use crate::{report::Report, stats};
+
+const HISTORY_DIR: &str = "results";
+
+/// Where every recorded run is appended, one JSON record per line.
+pub fn path() -> PathBuf {
+    PathBuf::from(HISTORY_DIR).join("history.jsonl")
+}
+
+/// One day's run, as recorded in the history.
+#[derive(Serialize)]
+struct Record<'a> {
+    /// Seconds since the unix epoch.
+    timestamp: u64,
+
+    /// The commit which was checked out, if this is a git checkout.
+    git_hash: Option<&'a str>,
+
+    /// Answers are only comparable across runs with the same input.
+    input: &'a Path,
+
+    #[serde(flatten)]
+    report: &'a Report,
+}
+
+/// Appends a record of each report to the history, so changed answers can be traced back to
+/// the commit which changed them.
+pub fn append(reports: &[(&Report, &Path)]) -> Result<(), String> {
+    let timestamp = stats::now();
+    let git_hash = git_hash();
+
+    let mut lines = String::new();
+    for (report, input) in reports {
+        let record = Record {
+            timestamp,
+            git_hash: git_hash.as_deref(),
+            input,
+            report,
+        };
+        lines += &serde_json::to_string(&record).expect("history records should serialize");
+        lines += "\n";
+    }
+
+    let path = path();
+    std::fs::create_dir_all(HISTORY_DIR)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("could not append to {}: {e}", path.display()))
+}
+
+/// The short hash of the checked out commit, or None outside of a git checkout.
+fn git_hash() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc::solver::{AnswerValue, PartialAnswer};
+
+    use super::*;
+
+    #[test]
+    fn records_are_flat() {
+        let answer = PartialAnswer {
+            part1: Some(AnswerValue::Int(3)),
+            part2: None,
+        };
+        let report = Report::new(1, answer, Duration::from_millis(2));
+        let record = Record {
+            timestamp: 1764547200,
+            git_hash: Some("46a5544"),
+            input: Path::new("inputs/1.txt"),
+            report: &report,
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(
+            json.starts_with(
+                r#"{"timestamp":1764547200,"git_hash":"46a5544","input":"inputs/1.txt","day":1,"part1":3,"part2":null,"elapsed_ms":2.0,"#
+            ),
+            "{json}"
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! The solvers, and what they need to run. Everything here sticks to computing on the input it's
+//! handed, without touching the filesystem or terminal, so it also builds for the browser. The
+//! runner around it lives in the binary.
+
+pub mod problems {
+    pub mod problem1;
+    pub mod problem10;
+    pub mod problem11;
+    pub mod problem12;
+    pub mod problem2;
+    pub mod problem3;
+    pub mod problem4;
+    pub mod problem5;
+    pub mod problem6;
+    pub mod problem7;
+    pub mod problem8;
+    pub mod problem9;
+}
+
+pub mod ctx;
+pub mod error;
+pub mod examples;
+pub mod fuzz;
+pub mod generate;
+pub mod oracle;
+pub mod shared;
+pub mod solver;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
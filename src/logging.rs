@@ -0,0 +1,19 @@
+use tracing_subscriber::EnvFilter;
+
+/// The environment variable holding the log filter, like: `AOC_LOG=aoc::problems::problem4=trace`
+pub const LOG_ENV: &str = "AOC_LOG";
+
+/// Only warnings are shown unless asked for more, so normal runs stay clean.
+const DEFAULT_FILTER: &str = "warn";
+
+/// Sends tracing output to stderr, filtered by `LOG_ENV`. Filters use the same syntax as
+/// `RUST_LOG`, so debug output can be enabled per module without recompiling.
+pub fn init() {
+    let filter =
+        EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
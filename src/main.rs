@@ -1,54 +1,355 @@
-use std::{process::exit, time::Instant};
-
-use crate::problems::*;
-
-pub mod problems {
-    pub mod problem1;
-    pub mod problem10;
-    pub mod problem11;
-    pub mod problem12;
-    pub mod problem2;
-    pub mod problem3;
-    pub mod problem4;
-    pub mod problem5;
-    pub mod problem6;
-    pub mod problem7;
-    pub mod problem8;
-    pub mod problem9;
-}
+use std::{
+    collections::HashMap,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Instant,
+};
+
+use aoc::{
+    error::AocError,
+    generate, oracle,
+    solver::{self, AnswerValue, Metadata, Solver},
+};
+
+use crate::{
+    cli::{Args, Command},
+    expected::Expected,
+    report::{Format, Report, Style},
+};
+
+pub mod baseline;
+pub mod bench;
+pub mod cli;
+pub mod daemon;
+pub mod expected;
+#[cfg(test)]
+mod golden;
+pub mod history;
+pub mod logging;
+pub mod profile;
+pub mod progress_bar;
+pub mod recording;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod serve;
+pub mod stats;
+pub mod submit;
+pub mod watch;
+pub mod watchdog;
 
-pub mod shared;
+/// How many generated inputs `--verify` checks each day on.
+const VERIFY_CASES: u64 = 100;
+
+fn main() -> ExitCode {
+    logging::init();
+
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ERROR: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
 
-fn main() {
+fn run() -> Result<(), AocError> {
     let start = Instant::now();
-    let first_arg: String = std::env::args().nth(1).unwrap_or_else(|| {
-        eprintln!("ERROR: problem number is required");
-        exit(1);
-    });
-
-    let filename = format!("inputs/{}.txt", first_arg);
-    let input = std::fs::read_to_string(&filename).unwrap_or_else(|_| {
-        eprintln!("ERROR: file does not exist: {filename}");
-        exit(1);
-    });
-
-    match first_arg.as_str() {
-        "1" => println!("{:?}", problem1::solve(&input)),
-        "2" => println!("{:?}", problem2::solve(&input)),
-        "3" => println!("{:?}", problem3::solve(&input)),
-        "4" => println!("{:?}", problem4::solve(&input)),
-        "5" => println!("{:?}", problem5::solve(&input)),
-        "6" => println!("{:?}", problem6::solve(&input)),
-        "7" => println!("{:?}", problem7::solve(&input)),
-        "8" => println!("{:?}", problem8::solve(&input, 1000)),
-        "9" => println!("{:?}", problem9::solve(&input)),
-        "10" => println!("{:?}", problem10::solve(&input)),
-        "11" => println!("{:?}", problem11::solve(&input)),
-        "12" => println!("{:?}", problem12::solve(&input)),
-        _ => {
-            eprintln!("ERROR: {first_arg} is not yet implemented");
-            exit(1);
+
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match Args::parse(raw_args.clone()) {
+        Ok(args) => args,
+        Err(e) if !e.use_stderr() => {
+            // --help and --version aren't errors, they just print
+            let _ = e.print();
+            return Ok(());
+        }
+        Err(e) => return Err(cli::usage_error(e)),
+    };
+    let answers = expected::load().map_err(AocError::Other)?;
+
+    // color is only for people, so leave it out when piping or when asked not to
+    let style = Style {
+        color: args.color
+            && std::io::stdout().is_terminal()
+            && std::env::var_os("NO_COLOR").is_none(),
+    };
+
+    if args.command == Command::List {
+        // days without a solver aren't in the catalog, so they're left out
+        let days = (args.days.clone()).unwrap_or_else(|| args.day.parse().into_iter().collect());
+        let catalog: Vec<Metadata> = days
+            .iter()
+            .filter_map(|day| solver::find(&day.to_string()))
+            .map(|s| s.metadata())
+            .collect();
+        println!("{}", report::render_catalog(&catalog, args.format));
+        return Ok(());
+    }
+
+    match args.command {
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Args::command(), "aoc", &mut std::io::stdout());
+            return Ok(());
+        }
+        Command::Serve { ref addr } => {
+            return serve::serve(addr, args.clone()).map_err(AocError::Other);
+        }
+        Command::Track => return track(&args, &answers),
+        Command::Daemon => return daemon::daemon(&answers).map_err(AocError::Other),
+        Command::Verify => return verify(&args),
+        Command::Gen { size, seed } => {
+            let day = args.day.parse().expect("gen's day is parsed as a number");
+            let input = match seed {
+                Some(seed) => generate::generate(day, size, seed)?,
+                None => {
+                    let seed = rand::random();
+                    let input = generate::generate(day, size, seed)?;
+                    eprintln!("seed: {seed}");
+                    input
+                }
+            };
+            print!("{input}");
+            return Ok(());
+        }
+        Command::New => {
+            let day = args.day.parse().expect("new's day is parsed as a number");
+            for path in scaffold::scaffold(day).map_err(AocError::Other)? {
+                println!("wrote {}", path.display());
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    if let Some(days) = &args.days {
+        if args.command != Command::Run || args.input.is_some() {
+            return Err(AocError::Usage(
+                "several days can only be run, using each day's own input or example".to_string(),
+            ));
+        }
+        if args.quiet {
+            return Err(AocError::Usage(
+                "--quiet only prints one day's answers".to_string(),
+            ));
+        }
+
+        let solvers: Vec<&dyn Solver> = days
+            .iter()
+            .map(|&day| find_solver(&day.to_string()))
+            .collect::<Result<_, _>>()?;
+
+        let results = runner::run_all(&solvers, &args, &answers);
+        println!("{}", report::render_all(&results, args.format, style));
+
+        if args.history {
+            let inputs: Vec<PathBuf> = days.iter().map(|&day| args.input_path(day)).collect();
+            let solved: Vec<(&Report, &Path)> = results
+                .iter()
+                .zip(&inputs)
+                .filter_map(|((_, result), input)| Some((result.as_ref().ok()?, input.as_path())))
+                .collect();
+            history::append(&solved).map_err(AocError::Other)?;
+        }
+        if args.format == Format::Text {
+            println!("Took: {:?}", start.elapsed());
+        }
+
+        // the first failure, in day order, decides the exit status
+        let failure = results.into_iter().find_map(|(_, result)| match result {
+            Ok(report) => report.failure(),
+            Err(failure) => Some(failure),
+        });
+        return failure.map_or(Ok(()), Err);
+    }
+
+    let solver = find_solver(&args.day)?;
+
+    if let Command::Submit { part, answer } = &args.command {
+        return submit(solver, &args, *part, answer.clone());
+    }
+
+    let filename = args.input_path(solver.day());
+    if args.command == Command::Watch {
+        watch::watch(solver.day(), filename, &raw_args);
+    }
+
+    let input =
+        std::fs::read_to_string(&filename).map_err(|_| AocError::MissingInput(filename.clone()))?;
+
+    match args.command {
+        Command::Run => {
+            // the progress bar is decoration, so quiet runs leave it out
+            let show_progress = std::io::stderr().is_terminal() && !args.quiet;
+            let mut report = runner::solve(solver, &input, &args, start, show_progress)?;
+            report.expected = runner::expected_for(solver.day(), &args, &answers);
+            if args.quiet {
+                println!("{}", report.render_quiet());
+            } else {
+                println!("{}", report.render(args.format, style));
+            }
+
+            if args.history {
+                history::append(&[(&report, &filename)]).map_err(AocError::Other)?;
+            }
+
+            if let Some(failure) = report.failure() {
+                return Err(failure);
+            }
+        }
+        Command::Bench {
+            warmup,
+            iterations,
+            ref save_baseline,
+            ref compare,
+        } => {
+            let measurements = bench::bench(solver, &input, args.part, warmup, iterations)?;
+            println!("{}", bench::render(&measurements));
+
+            if let Some(name) = compare {
+                let baseline = baseline::load(name).map_err(AocError::Other)?;
+                let for_day = baseline.get(&solver.day()).cloned().unwrap_or_default();
+                println!();
+                println!(
+                    "{}",
+                    baseline::render_comparison(name, &for_day, &measurements)
+                );
+            }
+
+            if let Some(name) = save_baseline {
+                baseline::save(name, solver.day(), &measurements).map_err(AocError::Other)?;
+                println!("saved baseline: {}", baseline::path(name).display());
+            }
+        }
+        Command::Profile { duration } => {
+            let profiled = profile::profile(solver, &input, args.part, duration)?;
+            println!("profiled {} runs", profiled.runs);
+            println!("pprof: {}", profiled.pprof_path.display());
+            println!("flamegraph: {}", profiled.flamegraph_path.display());
         }
+        Command::Watch => unreachable!("watch never returns"),
+        Command::List
+        | Command::New
+        | Command::Submit { .. }
+        | Command::Completions { .. }
+        | Command::Serve { .. }
+        | Command::Track
+        | Command::Gen { .. }
+        | Command::Verify
+        | Command::Daemon => {
+            unreachable!("handled before any input is read")
+        }
+    }
+
+    Ok(())
+}
+
+/// Solves the selected days' real inputs, recording when each part was first verified correct,
+/// then prints how long after release each part was solved.
+fn track(args: &Args, answers: &HashMap<u32, Expected>) -> Result<(), AocError> {
+    let days = (args.days.clone()).unwrap_or_else(|| args.day.parse().into_iter().collect());
+    let solvers: Vec<&dyn Solver> = days
+        .iter()
+        .filter_map(|day| solver::find(&day.to_string()))
+        .collect();
+
+    let mut times = stats::load().map_err(AocError::Other)?;
+    let now = stats::now();
+    let mut recorded = false;
+    for (day, result) in runner::run_all(&solvers, args, answers) {
+        match result {
+            Ok(report) => recorded |= stats::record(&mut times, &report, now),
+            Err(e) => eprintln!("day {day}: {e}"),
+        }
+    }
+
+    if recorded {
+        stats::save(&times).map_err(AocError::Other)?;
+    }
+    println!("{}", stats::render(&times, &days));
+    Ok(())
+}
+
+/// Cross-checks each selected day's solver against its brute force, on small generated inputs.
+/// When several days are selected, days without a brute force are skipped.
+fn verify(args: &Args) -> Result<(), AocError> {
+    let days = match &args.days {
+        Some(days) => days
+            .iter()
+            .copied()
+            .filter(|day| oracle::days().contains(day))
+            .collect(),
+        None => vec![find_solver(&args.day)?.day()],
     };
-    println!("Took: {:?}", start.elapsed());
+
+    for day in days {
+        for seed in 0..VERIFY_CASES {
+            let regenerate = || {
+                let size = oracle::size(day).unwrap_or_default();
+                format!("cargo run gen {day} --size {size} --seed {seed}")
+            };
+            let check = runner::catch_crash(|| oracle::check(day, seed)).inspect_err(|e| {
+                if !matches!(e, AocError::Usage(_)) {
+                    eprintln!("day {day}: failed on the input from `{}`", regenerate());
+                }
+            })?;
+
+            if !check.matches() {
+                eprintln!(
+                    "day {day}: solved {:#}, but brute force says {:#} for the input from `{}`",
+                    check.solved,
+                    check.expected,
+                    regenerate()
+                );
+                return Err(if check.solved.part1 != check.expected.part1 {
+                    AocError::WrongPart1
+                } else {
+                    AocError::WrongPart2
+                });
+            }
+        }
+
+        println!("day {day}: {VERIFY_CASES} generated inputs match the brute force");
+    }
+
+    Ok(())
+}
+
+/// Submits an answer for the real input, solving the part first if no answer was given.
+fn submit(
+    solver: &'static dyn Solver,
+    args: &Args,
+    part: u8,
+    answer: Option<AnswerValue>,
+) -> Result<(), AocError> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let filename = args.input_path(solver.day());
+            let input =
+                std::fs::read_to_string(&filename).map_err(|_| AocError::MissingInput(filename))?;
+            let report = runner::solve(solver, &input, args, Instant::now(), false)?;
+
+            report
+                .part1
+                .or(report.part2)
+                .expect("the submitted part should be solved")
+        }
+    };
+
+    println!("submitting day {} part {part}: {answer}", solver.day());
+    let verdict = submit::submit(solver.day(), part, &answer).map_err(AocError::Other)?;
+    println!("{verdict}");
+
+    match verdict {
+        submit::Verdict::Correct => Ok(()),
+        submit::Verdict::Incorrect { .. } if part == 1 => Err(AocError::WrongPart1),
+        submit::Verdict::Incorrect { .. } => Err(AocError::WrongPart2),
+        _ => Err(AocError::Other("the answer wasn't accepted".to_string())),
+    }
+}
+
+fn find_solver(day: &str) -> Result<&'static dyn Solver, AocError> {
+    solver::find(day).ok_or_else(|| AocError::UnknownDay(day.to_string()))
 }
@@ -0,0 +1,110 @@
+//! Brute-force solutions for the days with clever algorithms, for cross-checking the solvers on
+//! small generated inputs. They're far too slow for the real input, but simple enough to trust.
+
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    generate,
+    problems::*,
+    solver::{self, Part, PartialAnswer},
+};
+
+/// Solves an input by brute force.
+type BruteForce = fn(&str) -> Result<PartialAnswer, AocError>;
+
+/// Brute forces answer in whichever type the day's solver does, so they're compared as the values
+/// the runner would print.
+fn oracle(day: u32) -> Option<BruteForce> {
+    match day {
+        2 => Some(|input| problem2::brute_force(input).map(Into::into)),
+        5 => Some(|input| problem5::brute_force(input).map(Into::into)),
+        9 => Some(|input| problem9::brute_force(input).map(Into::into)),
+        10 => Some(|input| problem10::brute_force(input).map(Into::into)),
+        _ => None,
+    }
+}
+
+/// The size of input to generate for checking a day, which its brute force stays fast for. Days
+/// without a brute force have no size.
+pub fn size(day: u32) -> Option<usize> {
+    match day {
+        2 => Some(10),
+        5 => Some(20),
+        9 => Some(8),
+        10 => Some(4),
+        _ => None,
+    }
+}
+
+/// The days with a brute-force solution to check against.
+pub fn days() -> Vec<u32> {
+    (1..=25).filter(|&day| oracle(day).is_some()).collect()
+}
+
+/// How a solver's answers compared to the brute force's, on one generated input.
+#[derive(Debug, PartialEq)]
+pub struct Check {
+    pub solved: PartialAnswer,
+    pub expected: PartialAnswer,
+}
+
+impl Check {
+    pub fn matches(&self) -> bool {
+        self.solved == self.expected
+    }
+}
+
+/// Generates a small input for the day from the seed, then solves it both with the day's solver
+/// and by brute force.
+pub fn check(day: u32, seed: u64) -> Result<Check, AocError> {
+    let (Some(brute_force), Some(size)) = (oracle(day), size(day)) else {
+        return Err(AocError::Usage(format!(
+            "day {day} has no brute force to verify against"
+        )));
+    };
+    let solver =
+        solver::find(&day.to_string()).ok_or_else(|| AocError::UnknownDay(day.to_string()))?;
+
+    let input = generate::generate(day, size, seed)?;
+    Ok(Check {
+        solved: solver.solve_parts(&input, Part::Both, &SolveCtx::default())?,
+        expected: brute_force(&input)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn oracles_solve_the_examples() {
+        for day in days() {
+            let input = examples::read(&day.to_string());
+            let solver = solver::find(&day.to_string()).unwrap();
+
+            assert_eq!(
+                oracle(day).unwrap()(&input).unwrap(),
+                solver
+                    .solve_parts(&input, Part::Both, &SolveCtx::default())
+                    .unwrap(),
+                "day {day}"
+            );
+        }
+    }
+
+    #[test]
+    fn solvers_match_oracles() {
+        for day in days() {
+            for seed in 0..10 {
+                let check = check(day, seed).unwrap();
+                assert!(check.matches(), "day {day}, seed {seed}: {check:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn days_without_an_oracle() {
+        assert!(matches!(check(1, 0), Err(AocError::Usage(_))));
+    }
+}
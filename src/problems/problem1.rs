@@ -1,35 +1,64 @@
-use crate::shared::Answer;
+use std::io::BufRead;
 
-struct Safe {
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::Answer,
+    solver::{AnswerValue, Metadata, Solver},
+};
+
+/// A dial numbered `0..size`, which wraps around in either direction.
+pub struct Safe {
     position: usize,
+    size: usize,
 }
 
+/// The puzzle's dial, numbered 0 to 99 and starting at 50.
 impl Default for Safe {
     fn default() -> Self {
-        Safe { position: 50 }
+        Safe {
+            position: 50,
+            size: 100,
+        }
     }
 }
 
 impl Safe {
+    /// A dial numbered `0..size`, starting on `start`, which has to be one of its numbers.
+    pub fn new(size: usize, start: usize) -> Result<Self, AocError> {
+        if start >= size {
+            return Err(AocError::Usage(format!(
+                "the dial should start on one of its {size} numbers, not {start}"
+            )));
+        }
+
+        Ok(Safe {
+            position: start,
+            size,
+        })
+    }
+
     // Moves the dial, returning how many times zero was passed.
     fn turn(&mut self, amount: i32) -> usize {
-        let start = self.position as i32;
-        let end = start + amount;
+        // widened, so a turn of any i32 from anywhere on the dial can't overflow
+        let start = self.position as i64;
+        let end = start + i64::from(amount);
 
         // using % will return negatives, rem_euclid is always non-negative
         // ex: -1 % 100 => -1, -1.rem_euclid(100) => 99
-        self.position = end.rem_euclid(100) as usize;
+        self.position = end.rem_euclid(self.size as i64) as usize;
 
-        Safe::count_zero_clicks(start, end)
+        self.count_zero_clicks(start, end)
     }
 
     /// Counts how many times zero was passed.
-    fn count_zero_clicks(start: i32, end: i32) -> usize {
+    fn count_zero_clicks(&self, start: i64, end: i64) -> usize {
+        let size = self.size as i64;
         if end > 0 {
-            // for positive, count how many times we passed 100
-            (end / 100) as usize
+            // for positive, count how many times we passed the size
+            (end / size) as usize
         } else {
-            let zero_clicks = (-end / 100) as usize;
+            let zero_clicks = (-end / size) as usize;
 
             // for negative, 1 -> -1 counts, but 0 -> -1 does not
             // so add 1, but not when starting at zero
@@ -45,27 +74,104 @@ impl Safe {
     }
 }
 
-fn parse_movement(movement: &str) -> i32 {
-    let (direction, amount) = movement
-        .split_at_checked(1)
-        .expect("movement should be letter then digits");
+/// Parses every movement, as signed amounts to turn the dial, with one list per safe. Safes are
+/// separated by blank lines, and an input without any is a single safe. Errors say which line was
+/// wrong, since the inputs run to thousands of lines.
+pub(crate) fn parse(input: &str) -> Result<Vec<Vec<i32>>, AocError> {
+    let mut safes = vec![Vec::new()];
+    for (idx, movement) in input.lines().enumerate() {
+        // however many blank lines there are in a row, they only start one new safe
+        if movement.is_empty() {
+            if safes.last().is_some_and(|safe| !safe.is_empty()) {
+                safes.push(Vec::new());
+            }
+            continue;
+        }
+
+        let amount = parse_line(idx, movement)?;
+        safes.last_mut().expect("starts with one safe").push(amount);
+    }
 
-    let amount: i32 = amount.parse().expect("digits should parse to int");
+    // a blank line at the end doesn't start a safe with no movements
+    if safes.len() > 1 && safes.last().is_some_and(Vec::is_empty) {
+        safes.pop();
+    }
+
+    Ok(safes)
+}
+
+/// Parses the movement on the line at `idx`, counting from 0.
+fn parse_line(idx: usize, movement: &str) -> Result<i32, AocError> {
+    parse_movement(movement).ok_or_else(|| {
+        AocError::Parse(format!(
+            "movement on line {} should be L or R then digits: {movement}",
+            idx + 1
+        ))
+    })
+}
+
+fn parse_movement(movement: &str) -> Option<i32> {
+    let (direction, amount) = movement.split_at_checked(1)?;
+
+    // the direction gives the sign, so the amount can't have one of its own, which parse allows
+    if !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: i32 = amount.parse().ok()?;
 
     match direction {
-        "L" => -amount,
-        "R" => amount,
-        _ => panic!("direction should be L or R"),
+        "L" => Some(-amount),
+        "R" => Some(amount),
+        _ => None,
     }
 }
 
-pub fn solve(input: &str) -> Answer {
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    Ok(solve_each(input)?.into_iter().sum())
+}
+
+/// The answer for each safe in the input on its own, where `solve` adds them all up.
+pub fn solve_each(input: &str) -> Result<Vec<Answer>, AocError> {
+    Ok(parse(input)?
+        .iter()
+        .map(|movements| solve_safe(movements))
+        .collect())
+}
+
+/// Solves the input one line at a time as it's read, rather than holding all of it, for generated
+/// inputs too big to fit in memory. Gives the same answer as `solve`.
+pub fn solve_reader(reader: impl BufRead) -> Result<Answer, AocError> {
     let mut safe = Safe::default();
 
     let mut zeroes = 0;
     let mut zero_clicks = 0;
-    for one_movement in input.lines() {
-        let amount = parse_movement(one_movement);
+    for (idx, line) in reader.lines().enumerate() {
+        let movement = line.map_err(|e| AocError::Other(format!("could not read input: {e}")))?;
+
+        // each safe starts from a fresh dial
+        if movement.is_empty() {
+            safe = Safe::default();
+            continue;
+        }
+
+        zero_clicks += safe.turn(parse_line(idx, &movement)?);
+        if safe.is_zeroed() {
+            zeroes += 1;
+        }
+    }
+
+    Ok(Answer {
+        part1: zeroes,
+        part2: Some(zero_clicks),
+    })
+}
+
+fn solve_safe(movements: &[i32]) -> Answer {
+    let mut safe = Safe::default();
+
+    let mut zeroes = 0;
+    let mut zero_clicks = 0;
+    for &amount in movements {
         zero_clicks += safe.turn(amount);
 
         if safe.is_zeroed() {
@@ -75,38 +181,138 @@ pub fn solve(input: &str) -> Answer {
 
     Answer {
         part1: zeroes,
-        part2: zero_clicks,
+        part2: Some(zero_clicks),
+    }
+}
+
+pub struct Problem1;
+
+// Both parts are counted in the same pass over the movements, so each part reuses solve.
+impl Solver for Problem1 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 1,
+            title: "Secret Entrance",
+            tags: &["simulation"],
+            has_part2: true,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part1.into())
+    }
+
+    fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part2.expect("part 2 is always solved").into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::{collection::vec, prelude::*};
+
     use super::*;
+    use crate::examples;
 
     #[test]
     fn test_solve_basic_input() {
-        let input = r#"L68
-L30
-R48
-L5
-R60
-L55
-L1
-L99
-R14
-L82"#;
-
-        let result = solve(input);
+        let input = examples::read("1");
+
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 3);
-        assert_eq!(result.part2, 6);
+        assert_eq!(result.part2, Some(6));
+    }
+
+    #[test]
+    fn solve_several_safes() {
+        let example = examples::read("1");
+        let input = format!("{example}\n\n\nR50\nL5\nR5\n\n{example}\n");
+
+        let each: Vec<(usize, Option<usize>)> = solve_each(&input)
+            .unwrap()
+            .into_iter()
+            .map(|answer| (answer.part1, answer.part2))
+            .collect();
+        assert_eq!(each, [(3, Some(6)), (2, Some(2)), (3, Some(6))]);
+
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, 8);
+        assert_eq!(result.part2, Some(14));
+        assert_eq!(parse("").unwrap(), [Vec::<i32>::new()]);
     }
 
     #[test]
-    fn test_solve_full_input() {
-        let input = std::fs::read_to_string("inputs/1.txt").unwrap();
-        let result = solve(&input);
-        assert_eq!(result.part1, 1076);
-        assert_eq!(result.part2, 6379);
+    fn solve_while_reading() {
+        let example = examples::read("1");
+        let several = format!("{example}\n\n\nR50\nL5\nR5\n\n{example}\n");
+
+        for input in [example.as_str(), &several, ""] {
+            let streamed = solve_reader(input.as_bytes()).unwrap();
+            let expected = solve(input).unwrap();
+            assert_eq!(streamed.part1, expected.part1);
+            assert_eq!(streamed.part2, expected.part2);
+        }
+
+        assert_eq!(
+            solve_reader("L68\r\nR4x\r\n".as_bytes()).map(|answer| answer.part1),
+            Err(AocError::Parse(
+                "movement on line 2 should be L or R then digits: R4x".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn malformed_movements_are_errors() {
+        let error = |input: &str| match parse(input) {
+            Err(AocError::Parse(message)) => message,
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+
+        assert_eq!(
+            error("L68\nX30\nR48"),
+            "movement on line 2 should be L or R then digits: X30"
+        );
+        assert_eq!(
+            error("L68\n \nR48"),
+            "movement on line 2 should be L or R then digits:  "
+        );
+        assert_eq!(
+            error("L68\nR48\nRabc"),
+            "movement on line 3 should be L or R then digits: Rabc"
+        );
+        assert_eq!(
+            error("L68\nL-5"),
+            "movement on line 2 should be L or R then digits: L-5"
+        );
+        assert_eq!(
+            error("R+5"),
+            "movement on line 1 should be L or R then digits: R+5"
+        );
+        assert!(parse("L").is_err());
+    }
+
+    #[test]
+    fn malformed_input_fails_to_solve() {
+        assert_eq!(
+            solve("L68\nR4x").map(|answer| answer.part1),
+            Err(AocError::Parse(
+                "movement on line 2 should be L or R then digits: R4x".to_string()
+            ))
+        );
+        assert!(solve_each("L68\n\nL-5").is_err());
+    }
+
+    #[test]
+    fn biggest_turns_do_not_overflow() {
+        let mut safe = Safe::default();
+        assert_eq!(parse_movement("R2147483647"), Some(i32::MAX));
+
+        // 50 + 2147483647 goes all the way around 21474836 times, landing on 97
+        assert_eq!(safe.turn(i32::MAX), 21_474_836);
+        assert_eq!(safe.position, 97);
+
+        assert_eq!(safe.turn(-i32::MAX), 21_474_836);
+        assert_eq!(safe.position, 50);
     }
 
     #[test]
@@ -142,4 +348,58 @@ L82"#;
         assert!(safe.is_zeroed());
         assert_eq!(zero_clicks, 3);
     }
+
+    #[test]
+    fn test_safe_with_smaller_dial() {
+        let mut safe = Safe::new(10, 0).unwrap();
+
+        // starting on zero doesn't count as passing it
+        let zero_clicks = safe.turn(-3);
+        assert_eq!(safe.position, 7);
+        assert_eq!(zero_clicks, 0);
+
+        let zero_clicks = safe.turn(25);
+        assert_eq!(safe.position, 2);
+        assert_eq!(zero_clicks, 3);
+
+        let zero_clicks = safe.turn(-12);
+        assert!(safe.is_zeroed());
+        assert_eq!(zero_clicks, 2);
+    }
+
+    #[test]
+    fn safe_starts_on_one_of_its_numbers() {
+        assert!(Safe::new(1, 0).is_ok());
+        assert!(matches!(Safe::new(10, 10), Err(AocError::Usage(_))));
+        assert!(matches!(Safe::new(0, 0), Err(AocError::Usage(_))));
+    }
+
+    /// A movement as written in the input, like "L68".
+    fn movement() -> impl Strategy<Value = String> {
+        (prop_oneof!["L", "R"], 0..1000_u32)
+            .prop_map(|(direction, amount)| format!("{direction}{amount}"))
+    }
+
+    proptest! {
+        #[test]
+        fn zero_clicks_match_clicking_one_at_a_time(movements in vec(movement(), 0..50)) {
+            let mut safe = Safe::default();
+            let mut position: i32 = 50;
+
+            for movement in movements {
+                let amount = parse_movement(&movement).unwrap();
+
+                let mut clicks = 0;
+                for _ in 0..amount.abs() {
+                    position = (position + amount.signum()).rem_euclid(100);
+                    if position == 0 {
+                        clicks += 1;
+                    }
+                }
+
+                prop_assert_eq!(safe.turn(amount), clicks, "turning {}", movement);
+                prop_assert_eq!(safe.position as i32, position);
+            }
+        }
+    }
 }
@@ -1,9 +1,19 @@
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 
 use itertools::Itertools;
-use peroxide::fuga::{Matrix, MatrixTrait, MutMatrix, Scalable, Shape::Row, Vector, zeros};
-
-use crate::shared::Answer;
+use rand::Rng;
+use tracing::{Level, debug};
+
+use crate::{
+    ctx::{Progress, SolveCtx},
+    error::AocError,
+    shared::{
+        Answer, BitSet, Rational, gf2,
+        linalg::{self, Solution},
+        parse,
+    },
+    solver::{AnswerValue, Metadata, Solver},
+};
 
 #[derive(Clone, Debug)]
 struct Button {
@@ -14,28 +24,7 @@ struct Button {
     position: usize,
 }
 
-#[derive(Clone, Debug)]
-struct ConfigurationConstraints {
-    // how many times to push each button. None implies no constraint on this button.
-    button_presses: Vec<Option<u8>>,
-}
-
-impl ConfigurationConstraints {
-    fn new(presses_per_button: Vec<Option<u8>>) -> Self {
-        Self {
-            button_presses: presses_per_button,
-        }
-    }
-
-    fn total_button_presses(&self) -> u8 {
-        self.button_presses
-            .iter()
-            .filter_map(|bp| bp.as_ref())
-            .sum()
-    }
-}
-
-struct Machine {
+pub(crate) struct Machine {
     // the pattern of lights which must be activated
     indicator_light_diagram: Vec<bool>,
 
@@ -46,64 +35,46 @@ struct Machine {
 }
 
 impl Machine {
-    fn calculate_minimal_configuration_instructions(&self) -> ConfigurationConstraints {
-        let light_to_buttons: Vec<Vec<&Button>> = self.map_lights_to_buttons();
-
-        // For each light, this walks through each possible valid configuration. The solution ends
-        // up constrained by the solution to the lights which came before it.
-        //
-        // For example (X = no constraint, # = must be this many button presses):
-        // light 0     | off | EF  | XXXX00
-        //   light 1   | on  | BF  | X1XX00 must press B to turn light on
-        //   light 2   | on  | CDE | X11000
-        //     light 3 | off | AD  | 011000 can't press A
-        //   light 2   | on  | CDE | X10100
-        //     light 3 | off | AD  | 110100 must press A
-
-        // Start with one candidate that has no constraints
-        let mut current_candidates = vec![ConfigurationConstraints::new(
-            vec![None; self.button_wiring_schematics.len()],
-        )];
-        let mut next_candidates: Vec<ConfigurationConstraints> = vec![];
-
-        for (light_idx, buttons_for_light) in light_to_buttons.iter().enumerate() {
-            while let Some(candidate) = current_candidates.pop() {
-                let new_candidates = generate_candidates_for_constraints(
-                    candidate,
-                    buttons_for_light,
-                    self.indicator_light_diagram[light_idx],
-                );
-
-                next_candidates.extend(new_candidates);
-            }
-
-            // next candidates become current, reset next
-            current_candidates = next_candidates;
-            next_candidates = vec![];
-        }
+    /// Each light ends up on when an odd number of its buttons are pressed, and pressing a button
+    /// twice does nothing, so this is a system of equations over GF(2) with one variable per
+    /// button. Any free buttons are what's left to choose between, which is rarely more than a few.
+    fn calculate_fewest_presses_for_lights(&self) -> Result<usize, AocError> {
+        let lights: Vec<BitSet> = self
+            .map_lights_to_buttons()
+            .iter()
+            .map(|buttons| buttons.iter().map(|b| b.position).collect())
+            .collect();
 
-        current_candidates
-            .into_iter()
-            .min_by_key(|c| c.total_button_presses())
-            .expect("all machines should be solvable")
+        let presses = gf2::solve(
+            &lights,
+            self.button_wiring_schematics.len(),
+            &self.indicator_light_diagram,
+        )
+        .ok_or_else(|| self.unsolvable())?
+        .solutions()
+        .map(|presses| presses.count_ones())
+        .min()
+        .expect("a solvable machine has at least one solution");
+
+        Ok(presses)
     }
 
-    fn calculate_fewest_presses_for_joltage_requirements(&self) -> usize {
+    fn calculate_fewest_presses_for_joltage_requirements(&self) -> Result<usize, AocError> {
         // calculates which buttons to press by first reducing the number of variables through the
         // magic of linear algebra, calculating rough ranges for the remaining variables, then
         // looping over all possible values.
 
-        let joltage_matrix = JoltageMatrix::new(self);
+        let joltage_matrix = JoltageMatrix::new(self).ok_or_else(|| self.unsolvable())?;
 
         // if this machine has no free buttons (generally, more buttons than joltages), then we can
         // simply compute the answer and be done.
-        let free_button_indices = &joltage_matrix.free_button_indices;
+        let free_button_indices = joltage_matrix.free_button_indices();
         if free_button_indices.is_empty() {
-            return joltage_matrix
+            return Ok(joltage_matrix
                 .calculate_button_presses(&[])
-                .expect("should always calculate result if no free buttons")
+                .ok_or_else(|| self.unsolvable())?
                 .iter()
-                .sum();
+                .sum());
         }
 
         // Limits can be calculated through the free buttons. Look at the free button's connections, and
@@ -116,7 +87,7 @@ impl Machine {
                 .max()
                 .unwrap();
 
-            0..max
+            0..=max
         });
 
         let mut min_presses = usize::MAX;
@@ -130,9 +101,11 @@ impl Machine {
 
             let all_button_presses = all_button_presses.unwrap();
 
-            // if !self.validate_joltage_requirements(&all_button_presses) {
-            //     continue;
-            // }
+            // presses calculated from the reduced matrix should always meet the requirements, but
+            // it's handy to double check when debugging
+            if tracing::enabled!(Level::DEBUG) {
+                self.validate_joltage_requirements(&all_button_presses);
+            }
 
             let total_presses = all_button_presses.iter().sum();
             if total_presses < min_presses {
@@ -141,10 +114,18 @@ impl Machine {
         }
 
         if min_presses == usize::MAX {
-            panic!("no solution found?? {:?}", self.indicator_light_diagram)
+            return Err(self.unsolvable());
         }
 
-        min_presses
+        Ok(min_presses)
+    }
+
+    /// The error for a machine which no combination of button presses can configure.
+    fn unsolvable(&self) -> AocError {
+        AocError::Unsolvable(format!(
+            "no button presses configure machine with lights {:?} and joltages {:?}",
+            self.indicator_light_diagram, self.joltage_requirements
+        ))
     }
 
     /// Flips buttons -> lights into lights -> buttons
@@ -159,7 +140,7 @@ impl Machine {
             .collect()
     }
 
-    #[allow(dead_code)]
+    /// Checks that the presses produce the required joltages, logging when they don't.
     fn validate_joltage_requirements(&self, button_presses: &[usize]) -> bool {
         let mut joltages = vec![0; self.joltage_requirements.len()];
 
@@ -170,9 +151,7 @@ impl Machine {
         }
 
         if joltages != self.joltage_requirements {
-            println!("Joltages don't match!");
-            println!("expected: {:?}", self.joltage_requirements);
-            println!("actual:   {:?}", joltages);
+            debug!(expected = ?self.joltage_requirements, actual = ?joltages, "joltages don't match");
             false
         } else {
             true
@@ -184,280 +163,350 @@ impl Machine {
 /// Reduces number of variables to only the "free" buttons (which is often 1-2) and calculates
 /// other button presses based on those.
 struct JoltageMatrix {
-    matrix: Matrix,
-    joltage_requirements: Vec<f64>,
-
-    basic_button_indices: Vec<usize>,
-    free_button_indices: Vec<usize>,
+    system: Solution,
 }
 
 impl JoltageMatrix {
-    fn new(machine: &Machine) -> Self {
+    /// None if no amount of presses, even fractional or negative ones, meets the requirements.
+    fn new(machine: &Machine) -> Option<Self> {
         // columns are buttons, and rows are connections
-        let mut matrix = zeros(
-            machine.joltage_requirements.len(),
-            machine.button_wiring_schematics.len(),
-        );
+        let mut matrix = vec![
+            vec![Rational::ZERO; machine.button_wiring_schematics.len()];
+            machine.joltage_requirements.len()
+        ];
 
         // populate the buttons/connections on the matrix
         for (b_idx, button) in machine.button_wiring_schematics.iter().enumerate() {
-            for connection in button.connections.iter() {
-                matrix[(*connection, b_idx)] = 1.0;
+            for &connection in button.connections.iter() {
+                matrix[connection][b_idx] = Rational::ONE;
             }
         }
 
-        // convert joltages to float, so they can be used in the matrix
-        let joltage_requirements = machine
+        let joltage_requirements: Vec<Rational> = machine
             .joltage_requirements
             .iter()
-            .map(|&j| j as f64)
+            .map(|&j| Rational::from(j))
             .collect();
 
-        // compute the row reduced echelon form, to identify the basic vs free variables
-        // joltage requirements are also adjusted as rows are swapped/subtracted
-        let reduced_matrix = rref(&matrix);
-        let matrix_with_joltages = matrix.add_col(&joltage_requirements);
-        let reduced_matrix_with_joltages = rref(&matrix_with_joltages);
-        let joltage_requirements =
-            reduced_matrix_with_joltages.col(reduced_matrix_with_joltages.col - 1);
-
-        // basic variables are well defined, and can be calculate in terms of the free variables
-        // they are the columns which contain the first 1 in a row.
-        let basic_button_indices: Vec<usize> = (0..matrix.row)
-            .filter_map(|row| (0..matrix.col).position(|col| reduced_matrix[(row, col)] == 1.0))
-            .collect();
+        // row reducing identifies the basic vs free variables. basic variables are well defined,
+        // and can be calculated in terms of the free variables, which can have a range of values.
+        let system = linalg::solve(&matrix, &joltage_requirements)?;
 
-        // free variables can have a range of values, and the solution will be defined in terms of
-        // those
-        let free_button_indices: Vec<usize> = (0..matrix.col)
-            .filter(|i| !basic_button_indices.contains(i))
-            .collect();
+        Some(Self { system })
+    }
 
-        Self {
-            matrix: reduced_matrix,
-            basic_button_indices,
-            free_button_indices,
-            joltage_requirements,
-        }
+    fn free_button_indices(&self) -> &[usize] {
+        &self.system.free
     }
 
     /// Given values for the "free" buttons, calcualte the values for all buttons.
     fn calculate_button_presses(&self, free_button_presses: &[usize]) -> Option<Vec<usize>> {
-        let mut all_button_presses = vec![0; self.matrix.col];
+        let free_button_presses: Vec<i128> = free_button_presses
+            .iter()
+            .map(|&presses| presses as i128)
+            .collect();
 
-        // add the free variables into the answer, so others can be computed from them
-        for (&idx, &presses) in self.free_button_indices.iter().zip(free_button_presses) {
-            all_button_presses[idx] = presses;
-        }
+        // Fractional or negative presses are no good.
+        self.system
+            .evaluate_integers(&free_button_presses)?
+            .into_iter()
+            .map(|presses| usize::try_from(presses).ok())
+            .collect()
+    }
+}
 
-        for (row_idx, &button_idx) in self.basic_button_indices.iter().enumerate() {
-            // for row_idx in 0..self.basic_button_indices.len() {
-            let row = self.matrix.row(row_idx);
+impl FromStr for Machine {
+    type Err = AocError;
 
-            // start with the joltage requirement, then subject any presses by the 'free' buttons
-            let mut button_presses = self.joltage_requirements[row_idx];
-            for &col_idx in self.free_button_indices.iter() {
-                let presses = all_button_presses[col_idx] as f64;
-                button_presses -= row[col_idx] * (presses as f64);
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AocError::Parse(format!("machine should be like [.#] (1) {{3,5}}: {s}"));
 
-            // Fractional values are no good.
-            // TODO: Would it be better to sort this on the matrix itself?
-            let rounded_button_presses = button_presses.round();
-            if (button_presses - rounded_button_presses).abs() > 0.000001 {
-                return None;
-            }
+        let (light_str, rest) = s.split_once(" ").ok_or_else(invalid)?;
+        let (button_str, joltage_str) = rest.rsplit_once(" ").ok_or_else(invalid)?;
 
-            if rounded_button_presses < 0.0 {
-                return None;
-            }
+        let lights: Vec<bool> = parse::bracketed(light_str, '[', ']')
+            .ok_or_else(invalid)?
+            .chars()
+            .map(|c| match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<_, _>>()?;
+
+        // each like: (1,2)
+        let buttons: Vec<Button> = parse::list(button_str, " ", |s| {
+            parse::ints(parse::bracketed(s, '(', ')')?, ",")
+        })
+        .ok_or_else(invalid)?
+        .into_iter()
+        .enumerate()
+        .map(|(position, connections)| Button {
+            position,
+            connections,
+        })
+        .collect();
+
+        let joltages: Vec<usize> = parse::bracketed(joltage_str, '{', '}')
+            .and_then(|s| parse::ints(s, ","))
+            .ok_or_else(invalid)?;
 
-            all_button_presses[button_idx] = rounded_button_presses as usize;
+        // every button has to connect to a light, and every light has a joltage
+        let connected_to_lights = buttons
+            .iter()
+            .flat_map(|b| &b.connections)
+            .all(|&light| light < lights.len());
+        if !connected_to_lights || joltages.len() != lights.len() {
+            return Err(invalid());
         }
 
-        Some(all_button_presses)
+        Ok(Self {
+            indicator_light_diagram: lights,
+            button_wiring_schematics: buttons,
+            joltage_requirements: joltages,
+        })
     }
 }
 
-// I can't believe I had to do this... but this is a more numerically stable (for my purposes, at
-// least) version of peroxide's rref algorithm. It's a copy paste, with the two changes noted
-// below.
-fn rref(matrix: &Matrix) -> Matrix {
-    let mut lead = 0usize;
-    let mut result = matrix.clone();
-    'outer: for r in 0..matrix.row {
-        if matrix.col <= lead {
-            break;
-        }
-        let mut i = r;
-
-        // check based on epsislon, rather than == 0.0
-        while result[(i, lead)].abs() < 0.000000001 {
-            i += 1;
-            if matrix.row == i {
-                i = r;
-                lead += 1;
-                if matrix.col == lead {
-                    break 'outer;
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    let machines = parse(input)?;
+
+    Ok(Answer {
+        part1: total_button_presses_for_lights(&machines)?,
+        part2: Some(total_button_presses_for_joltage_requirements(
+            &machines,
+            &SolveCtx::default(),
+        )?),
+    })
+}
+
+pub(crate) fn parse(input: &str) -> Result<Vec<Machine>, AocError> {
+    input.lines().map(|line| line.parse()).collect()
+}
+
+/// Generates `size` random machines. Each machine's lights and joltages come from pressing its
+/// buttons a random number of times, so every machine can be configured.
+///
+/// Machines grow with the size too, up to about as big as the real input's, so small inputs stay
+/// small enough to solve by brute force.
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let max_presses = size.min(20);
+
+    (0..size)
+        .map(|_| {
+            let lights = rng.random_range(3..=size.clamp(3, 10));
+            let mut buttons: Vec<Vec<usize>> = vec![];
+            for _ in 0..rng.random_range(2..=lights + 3) {
+                let button: Vec<usize> = (0..lights).filter(|_| rng.random_bool(0.4)).collect();
+                if !button.is_empty() && !buttons.contains(&button) {
+                    buttons.push(button);
                 }
             }
-        }
-        unsafe {
-            result.swap(i, r, Row);
-        }
-        let tmp = result[(r, lead)];
-        // check based on epsislon, rather than == 0.0
-        if tmp.abs() >= 0.000000001 {
-            unsafe {
-                result.row_mut(r).iter_mut().for_each(|t| *(*t) /= tmp);
+            // every light needs a button, or its joltage could never change
+            for light in 0..lights {
+                if !buttons.iter().flatten().any(|&l| l == light) {
+                    buttons.push(vec![light]);
+                }
             }
-        }
-        for j in 0..result.row {
-            if j != r {
-                let tmp1 = result.row(r).mul_scalar(result[(j, lead)]);
-                let tmp2 = result.row(j).sub_vec(&tmp1);
-                result.subs_row(j, &tmp2);
+
+            let mut toggled = vec![false; lights];
+            let mut joltages = vec![0; lights];
+            for button in &buttons {
+                let toggles = rng.random_bool(0.5);
+                let presses = rng.random_range(0..=max_presses);
+                for &light in button {
+                    toggled[light] ^= toggles;
+                    joltages[light] += presses;
+                }
             }
-        }
-        lead += 1;
-    }
-    result
+
+            let diagram: String = toggled
+                .iter()
+                .map(|&on| if on { '#' } else { '.' })
+                .collect();
+            let buttons = buttons
+                .iter()
+                .map(|button| format!("({})", button.iter().join(",")))
+                .join(" ");
+            format!("[{diagram}] {buttons} {{{}}}\n", joltages.iter().join(","))
+        })
+        .collect()
 }
 
-/// Given a set of constraints and the buttons for a specific light, determines which constraints
-/// should be checked next. This takes into consideration if the light should be on, and how many
-/// related lights are already on.
-fn generate_candidates_for_constraints(
-    constraints: ConfigurationConstraints,
-    buttons_for_light: &Vec<&Button>,
-    is_on: bool,
-) -> Vec<ConfigurationConstraints> {
-    let constraints = constraints.button_presses;
-
-    // filter to buttons that are not constrained
-    let unconstrained_buttons = buttons_for_light
-        .iter()
-        .filter(|b| constraints[b.position].is_none())
-        .collect::<Vec<_>>();
+/// Solves every machine by trying every combination of button presses. Only fast enough for small
+/// machines, but simple enough to check `solve` against.
+pub(crate) fn brute_force(input: &str) -> Result<Answer, AocError> {
+    let machines = parse(input)?;
 
-    // based on constraints, how many lights are already on?
-    let current_button_count_for_light = buttons_for_light
-        .iter()
-        .map(|b| b.position)
-        .map(|p| constraints[p].unwrap_or(0))
-        .sum::<u8>() as usize;
-
-    // determine if the number of buttons pressed should be even or odd
-    // taking into consideration the number of lights already on
-    let mod_target = if is_on { 1 } else { 0 };
-    let mod_target = (mod_target + current_button_count_for_light) % 2;
-
-    let mut candidates = vec![];
-
-    // starting at 0 or 1, count by twos up to the number of unconstrained buttons
-    // then permute over possible indices for buttons to press
-    for indices_to_choose in (mod_target..=unconstrained_buttons.len()).step_by(2) {
-        for buttons_to_press in (0..unconstrained_buttons.len()).combinations(indices_to_choose) {
-            // create a candidate to suggest, based on the starting candidate
-            let mut candidate_constraints = constraints.clone();
-
-            // PUSH THE BUTTONS!
-            for (idx, unconstrained_button) in unconstrained_buttons.iter().enumerate() {
-                let times_to_push_button = if buttons_to_press.contains(&idx) {
-                    1
-                } else {
-                    0
-                };
-
-                candidate_constraints[unconstrained_button.position] = Some(times_to_push_button);
+    Ok(Answer {
+        part1: machines
+            .iter()
+            .map(fewest_presses_for_lights)
+            .sum::<Result<_, _>>()?,
+        part2: Some(
+            machines
+                .iter()
+                .map(fewest_presses_for_joltages)
+                .sum::<Result<_, _>>()?,
+        ),
+    })
+}
+
+/// Tries pressing every subset of the buttons once, since pressing a button twice undoes it.
+fn fewest_presses_for_lights(machine: &Machine) -> Result<usize, AocError> {
+    let buttons = &machine.button_wiring_schematics;
+
+    (0..1_usize << buttons.len())
+        .filter(|pressed| {
+            let mut lights = vec![false; machine.indicator_light_diagram.len()];
+            for (idx, button) in buttons.iter().enumerate() {
+                if pressed & (1 << idx) != 0 {
+                    for &light in &button.connections {
+                        lights[light] = !lights[light];
+                    }
+                }
             }
 
-            candidates.push(ConfigurationConstraints::new(candidate_constraints));
+            lights == machine.indicator_light_diagram
+        })
+        .map(|pressed| pressed.count_ones() as usize)
+        .min()
+        .ok_or_else(|| machine.unsolvable())
+}
+
+/// Searches outward from no presses, one press at a time, until the joltages are reached.
+fn fewest_presses_for_joltages(machine: &Machine) -> Result<usize, AocError> {
+    let target = &machine.joltage_requirements;
+    let start = vec![0; target.len()];
+    let mut seen = HashSet::from([start.clone()]);
+    let mut frontier = vec![start];
+
+    for presses in 0.. {
+        if frontier.contains(target) {
+            return Ok(presses);
         }
-    }
 
-    candidates
-}
+        let mut next = vec![];
+        for joltages in &frontier {
+            for button in &machine.button_wiring_schematics {
+                let mut pressed = joltages.clone();
+                for &light in &button.connections {
+                    pressed[light] += 1;
+                }
 
-#[derive(Debug)]
-struct ParseError;
+                let overshot = pressed.iter().zip(target).any(|(j, t)| j > t);
+                if !overshot && seen.insert(pressed.clone()) {
+                    next.push(pressed);
+                }
+            }
+        }
 
-impl FromStr for Machine {
-    type Err = ParseError;
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (light_str, rest) = s.split_once(" ").ok_or(ParseError)?;
-        let (button_str, joltage_str) = rest.rsplit_once(" ").ok_or(ParseError)?;
+    Err(machine.unsolvable())
+}
 
-        let lights: Vec<bool> = light_str[1..(light_str.len() - 1)]
-            .chars()
-            .map(|c| c == '#')
-            .collect();
+fn total_button_presses_for_lights(machines: &[Machine]) -> Result<usize, AocError> {
+    machines
+        .iter()
+        .map(|m| m.calculate_fewest_presses_for_lights())
+        .sum()
+}
 
-        let buttons: Vec<Button> = button_str
-            .split(" ")
-            // each like: (1,2)
-            .map(|s| &s[1..(s.len() - 1)])
-            // each like 1,2 (str)
-            .map(|s| s.split(",").map(|s| s.parse::<usize>().unwrap()).collect())
-            .enumerate()
-            .map(|(position, connections)| Button {
-                position,
-                connections,
-            })
-            .collect();
+/// Searching each machine's free button presses is the slow part, so progress is reported per
+/// machine.
+fn total_button_presses_for_joltage_requirements(
+    machines: &[Machine],
+    progress: &dyn Progress,
+) -> Result<usize, AocError> {
+    machines
+        .iter()
+        .enumerate()
+        .inspect(|(idx, _)| progress.report(*idx, machines.len()))
+        .map(|(_, m)| m.calculate_fewest_presses_for_joltage_requirements())
+        .sum()
+}
 
-        let joltages: Vec<usize> = joltage_str[1..(joltage_str.len() - 1)]
-            .split(",")
-            .map(|s| s.parse().unwrap())
-            .collect();
+pub struct Problem10;
 
-        Ok(Self {
-            indicator_light_diagram: lights,
-            button_wiring_schematics: buttons,
-            joltage_requirements: joltages,
-        })
+impl Solver for Problem10 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 10,
+            title: "Factory",
+            tags: &["linear algebra", "search"],
+            has_part2: true,
+        }
     }
-}
 
-pub fn solve(input: &str) -> Answer {
-    let machines: Vec<Machine> = input.lines().map(|line| line.parse().unwrap()).collect();
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let machines = parse(input)?;
 
-    let total_button_presses_for_lights: usize = machines
-        .iter()
-        .map(|m| m.calculate_minimal_configuration_instructions())
-        .map(|ci: ConfigurationConstraints| ci.total_button_presses())
-        .map(|count| count as usize)
-        .sum();
+        ctx.phase("configure lights");
+        Ok(total_button_presses_for_lights(&machines)?.into())
+    }
 
-    let total_button_presses_for_joltage_requirements: usize = machines
-        .iter()
-        .map(|m| m.calculate_fewest_presses_for_joltage_requirements())
-        .sum();
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let machines = parse(input)?;
 
-    Answer {
-        part1: total_button_presses_for_lights,
-        part2: total_button_presses_for_joltage_requirements,
+        ctx.phase("search presses");
+        Ok(total_button_presses_for_joltage_requirements(&machines, ctx)?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use peroxide::fuga::MatrixTrait;
-
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
-[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
-[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"#;
+        let input = examples::read("10");
 
-        let result = solve(input.trim());
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 7);
-        assert_eq!(result.part2, 33);
+        assert_eq!(result.part2, Some(33));
+    }
+
+    #[test]
+    fn reports_progress_per_machine() {
+        let machines = parse(&examples::read("10")).unwrap();
+        let reports = std::cell::RefCell::new(vec![]);
+        let progress = |done, total| reports.borrow_mut().push((done, total));
+
+        total_button_presses_for_joltage_requirements(&machines, &progress).unwrap();
+        assert_eq!(reports.into_inner(), [(0, 3), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn unsolvable_machines_are_errors() {
+        // the second button has to be pressed 3 times, which is already too many for the first
+        let machine: Machine = "[#.] (0) (0,1) {1,3}".parse().unwrap();
+
+        assert!(matches!(
+            machine.calculate_fewest_presses_for_joltage_requirements(),
+            Err(AocError::Unsolvable(_))
+        ));
+    }
+
+    #[test]
+    fn free_buttons_can_be_pressed_up_to_their_joltage() {
+        // pressing the last button 4 times is best, which is as often as it can be pressed
+        let machine: Machine = "[####] (0,2) (1,3) (0,1,2,3) {4,4,4,4}".parse().unwrap();
+
+        assert_eq!(
+            machine
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
+            4
+        );
     }
 
     #[test]
@@ -538,69 +587,34 @@ mod tests {
     }
 
     #[test]
-    fn check_generate_candidates_for_constraints() {
+    fn can_create_joltage_matrix() {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
         let parsed: Machine = input.parse().unwrap();
-        let lights_to_buttons = parsed.map_lights_to_buttons();
-
-        let constraints = ConfigurationConstraints::new(vec![None; 6]);
-
-        // get candidates for the first light, with no incoming constraints
-        let first_candidates =
-            generate_candidates_for_constraints(constraints, &lights_to_buttons[0], false);
-        assert_eq!(first_candidates.len(), 2);
-        assert_eq!(
-            first_candidates[0].button_presses,
-            vec![None, None, None, None, Some(0), Some(0)]
-        );
-        assert_eq!(
-            first_candidates[1].button_presses,
-            vec![None, None, None, None, Some(1), Some(1)]
-        );
+        let matrix = JoltageMatrix::new(&parsed).unwrap();
 
-        // get candidates for the second light, based on the first candidate from light one
-        let second_candidates = generate_candidates_for_constraints(
-            first_candidates[0].clone(),
-            &lights_to_buttons[1],
-            true,
-        );
-        assert_eq!(second_candidates.len(), 1);
-        assert_eq!(
-            second_candidates[0].button_presses,
-            vec![None, Some(1), None, None, Some(0), Some(0)]
-        );
-
-        // get candidates for the second light, based on the second candidate from light one
-        let second_candidates = generate_candidates_for_constraints(
-            first_candidates[1].clone(),
-            &lights_to_buttons[1],
-            true,
-        );
-        assert_eq!(second_candidates.len(), 1);
+        let rows: Vec<Vec<i128>> = matrix
+            .system
+            .reduced
+            .iter()
+            .map(|row| row[..6].iter().map(|r| r.to_integer().unwrap()).collect())
+            .collect();
         assert_eq!(
-            second_candidates[0].button_presses,
-            vec![None, Some(0), None, None, Some(1), Some(1)]
+            rows,
+            [
+                [1, 0, 0, 1, 0, -1],
+                [0, 1, 0, 0, 0, 1],
+                [0, 0, 1, 1, 0, -1],
+                [0, 0, 0, 0, 1, 1]
+            ]
         );
-    }
-
-    #[test]
-    fn can_create_joltage_matrix() {
-        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
-        let parsed: Machine = input.parse().unwrap();
-        let matrix = JoltageMatrix::new(&parsed);
-
-        assert_eq!(matrix.matrix.row(0), vec![1.0, 0.0, 0.0, 1.0, 0.0, -1.0]);
-        assert_eq!(matrix.matrix.row(1), vec![0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
-        assert_eq!(matrix.matrix.row(2), vec![0.0, 0.0, 1.0, 1.0, 0.0, -1.0]);
-        assert_eq!(matrix.matrix.row(3), vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
 
-        assert_eq!(matrix.free_button_indices, vec![3, 5]);
+        assert_eq!(matrix.free_button_indices(), [3, 5]);
     }
     #[test]
     fn can_calculate_button_presses() {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
         let parsed: Machine = input.parse().unwrap();
-        let matrix = JoltageMatrix::new(&parsed);
+        let matrix = JoltageMatrix::new(&parsed).unwrap();
 
         // these are exampled I worked through by hand ahead of time
         assert_eq!(
@@ -648,7 +662,9 @@ mod tests {
         let parsed: Machine = input.parse().unwrap();
 
         assert_eq!(
-            parsed.calculate_fewest_presses_for_joltage_requirements(),
+            parsed
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
             195 //TODO: Be skeptical of this value, I don't know for sure that it's right
         );
     }
@@ -659,7 +675,9 @@ mod tests {
         let parsed: Machine = input.parse().unwrap();
 
         assert_eq!(
-            parsed.calculate_fewest_presses_for_joltage_requirements(),
+            parsed
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
             10
         );
     }
@@ -671,7 +689,9 @@ mod tests {
         let parsed: Machine = input.parse().unwrap();
 
         assert_eq!(
-            parsed.calculate_fewest_presses_for_joltage_requirements(),
+            parsed
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
             186
         );
     }
@@ -683,7 +703,9 @@ mod tests {
         let parsed: Machine = input.parse().unwrap();
 
         assert_eq!(
-            parsed.calculate_fewest_presses_for_joltage_requirements(),
+            parsed
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
             195
         );
     }
@@ -695,7 +717,9 @@ mod tests {
         let parsed: Machine = input.parse().unwrap();
 
         assert_eq!(
-            parsed.calculate_fewest_presses_for_joltage_requirements(),
+            parsed
+                .calculate_fewest_presses_for_joltage_requirements()
+                .unwrap(),
             101
         );
     }
@@ -1,36 +1,13 @@
-/// Counts paths through a graph by recurisvely searching the graph, and tallying up how many times
-/// specific nodes are passed through.
-use std::{cell::OnceCell, collections::HashMap, ops::AddAssign, rc::Rc};
+/// Counts paths through a graph by tallying devices in reverse topological order, and tallying up
+/// how many times specific nodes are passed through.
+use std::ops::AddAssign;
 
-use crate::shared::Answer;
-
-#[derive(Debug)]
-struct Device {
-    label: String,
-
-    // reference counting makes the references to the other devices play nice with the compiler
-    outputs: OnceCell<Vec<Rc<Device>>>,
-}
-
-impl Device {
-    fn new(label: String) -> Self {
-        Self {
-            label,
-            outputs: OnceCell::new(),
-        }
-    }
-
-    // more ergonomic way to get the outputs
-    fn outputs(&self) -> &Vec<Rc<Device>> {
-        self.outputs.get_or_init(Vec::new)
-    }
-
-    fn set_outputs(&self, outputs: Vec<Rc<Device>>) {
-        self.outputs
-            .set(outputs)
-            .expect("set_outputs should only be called once");
-    }
-}
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, BitSet, Graph, topo_sort},
+    solver::{AnswerValue, Metadata, Solver},
+};
 
 #[derive(Debug, Clone, Default)]
 struct PathTally {
@@ -50,7 +27,7 @@ struct PathTally {
 impl PathTally {
     fn update_for_specific_devices(&mut self, device_label: &str) {
         match device_label {
-            // start counting 'out'. it was accumulate as the stack unwinds.
+            // start counting 'out'. it accumulates as the devices leading here are tallied.
             "out" => self.out = 1,
 
             // every path from here to out has gone through dac, so dac = out.
@@ -83,128 +60,139 @@ impl AddAssign<PathTally> for PathTally {
     }
 }
 
-fn find_paths_to_out<'d>(
-    device: &'d Device,
-    all_tallies: &mut HashMap<&'d str, PathTally>,
-) -> PathTally {
-    // is there already an answer for this device? use it rather than exploring it again.
-    if all_tallies.contains_key(device.label.as_str()) {
-        return all_tallies.get(&device.label.as_str()).unwrap().clone();
+/// Tallies the paths from `start` to out. Every device's outputs come after it in topological
+/// order, so going through that order backwards tallies each device's outputs before the device.
+fn find_paths_to_out(devices: &Graph, start: usize) -> Result<PathTally, AocError> {
+    // gather every device reachable from start, and the connections between them
+    let mut reachable = vec![start];
+    let mut seen: BitSet = [start].into_iter().collect();
+    let mut connections = Vec::new();
+    let mut to_visit = vec![start];
+    while let Some(device) = to_visit.pop() {
+        for &output in devices.neighbors(device) {
+            connections.push((device, output));
+            if seen.set(output) {
+                reachable.push(output);
+                to_visit.push(output);
+            }
+        }
     }
 
-    // tally up the results from this node + its children
-    let mut tallies = PathTally::default();
-    for output in device.outputs().iter() {
-        tallies += find_paths_to_out(output, all_tallies)
-    }
+    let order =
+        topo_sort(reachable, connections).map_err(|e| AocError::Unsolvable(e.to_string()))?;
 
-    tallies.update_for_specific_devices(&device.label);
+    let mut all_tallies = vec![PathTally::default(); devices.len()];
+    for device in order.into_iter().rev() {
+        // tally up the results from this node's children
+        let mut tallies = PathTally::default();
+        for &output in devices.neighbors(device) {
+            tallies += all_tallies[output].clone();
+        }
 
-    // remember the answer in case we find ourselves here again
-    all_tallies.insert(&device.label, tallies.clone());
+        tallies.update_for_specific_devices(devices.label(device));
+        all_tallies[device] = tallies;
+    }
 
-    tallies
+    Ok(std::mem::take(&mut all_tallies[start]))
 }
 
-pub fn solve(input: &str) -> Answer {
-    let (you, svr) = parse(input);
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    let devices = parse(input)?;
 
-    let part1 = you.map(|you| {
-        // how many paths exist from you to out?
-        let tallies = find_paths_to_out(&you, &mut HashMap::new());
+    Ok(Answer {
+        part1: count_paths_from_you(&devices)?,
+        part2: Some(count_paths_from_svr(&devices)?),
+    })
+}
 
-        tallies.out
-    });
+/// How many paths exist from you to out? None exist when there's no you, since the examples for
+/// each part only have the device they need.
+fn count_paths_from_you(devices: &Graph) -> Result<usize, AocError> {
+    let tally = devices
+        .id("you")
+        .map(|you| find_paths_to_out(devices, you))
+        .transpose()?;
 
-    let part2 = svr.map(|svr| {
-        // how many paths exist from svr, through dac/fft, to out?
-        let tallies = find_paths_to_out(&svr, &mut HashMap::new());
+    Ok(tally.map(|tally| tally.out).unwrap_or_default())
+}
 
-        tallies.dac_and_fft
-    });
+/// How many paths exist from svr, through dac/fft, to out?
+fn count_paths_from_svr(devices: &Graph) -> Result<usize, AocError> {
+    let tally = devices
+        .id("svr")
+        .map(|svr| find_paths_to_out(devices, svr))
+        .transpose()?;
 
-    Answer {
-        part1: part1.unwrap_or_default(),
-        part2: part2.unwrap_or_default(),
-    }
+    Ok(tally.map(|tally| tally.dac_and_fft).unwrap_or_default())
 }
 
-/// Loads all devices, then returns references to the 'you' and 'svr' devices.
-fn parse(input: &str) -> (Option<Rc<Device>>, Option<Rc<Device>>) {
-    let mut devices: Vec<Rc<Device>> = Vec::new();
-    let mut connections: Vec<Vec<&str>> = Vec::new();
-    let mut label_to_device: HashMap<&str, Rc<Device>> = HashMap::new();
+pub struct Problem11;
+
+impl Solver for Problem11 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 11,
+            title: "Reactor",
+            tags: &["graph", "dynamic programming"],
+            has_part2: true,
+        }
+    }
 
-    // In the first pass create all devices, parse their connections, and map labels to devices
-    for line in input.lines() {
-        let (label, connections_str) = line.split_once(": ").unwrap();
-        let device = Rc::new(Device::new(label.to_string()));
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let devices = parse(input)?;
 
-        label_to_device.insert(label, Rc::clone(&device));
-        devices.push(device);
-        connections.push(connections_str.split(" ").collect());
+        ctx.phase("count paths");
+        Ok(count_paths_from_you(&devices)?.into())
     }
 
-    // The implicit "out" device doesn't exist in the input explicitly, so add it.
-    let out_device = Rc::new(Device::new("out".to_string()));
-    label_to_device.insert("out", Rc::clone(&out_device));
-    devices.push(out_device);
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let devices = parse(input)?;
 
-    // In the second pass, add the outputs to each device
-    for (device, connections) in devices.iter().zip(connections) {
-        let outputs = connections
-            .iter()
-            .map(|&c| Rc::clone(&label_to_device[c]))
-            .collect();
+        ctx.phase("count paths");
+        Ok(count_paths_from_svr(&devices)?.into())
+    }
+}
+
+/// Loads every device, with a connection to each of its outputs.
+pub(crate) fn parse(input: &str) -> Result<Graph, AocError> {
+    let devices = Graph::parse_directed(input)?;
 
-        device.set_outputs(outputs);
+    // every device is listed with its outputs, except for out, which is implied
+    let unknown = (0..devices.len())
+        .find(|&device| devices.neighbors(device).is_empty() && devices.label(device) != "out");
+    if let Some(device) = unknown {
+        let label = devices.label(device);
+        return Err(AocError::Parse(format!("unknown device: {label}")));
     }
 
-    (
-        label_to_device.get("you").map(Rc::clone),
-        label_to_device.get("svr").map(Rc::clone),
-    )
+    Ok(devices)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-aaa: you hhh
-you: bbb ccc
-bbb: ddd eee
-ccc: ddd eee fff
-ddd: ggg
-eee: out
-fff: out
-ggg: out
-hhh: ccc fff iii
-iii: out"#;
-        let result = solve(input.trim());
+        let input = examples::read("11");
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 5);
 
-        let input = r#"
-you: aaa
-svr: aaa bbb
-aaa: fft
-fft: ccc
-bbb: tty
-tty: ccc
-ccc: ddd eee
-ddd: hub
-hub: fff
-eee: dac
-dac: fff
-fff: ggg hhh
-ggg: out
-hhh: out"#;
+        let input = examples::read("11-2");
 
-        let result = solve(input.trim());
-        assert_eq!(result.part2, 2);
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part2, Some(2));
+    }
+
+    #[test]
+    fn loops_are_unsolvable() {
+        // there'd be infinitely many paths, going round the loop any number of times
+        let result = solve("you: aaa\naaa: bbb out\nbbb: aaa");
+        assert!(matches!(result, Err(AocError::Unsolvable(_))));
     }
 
     #[test]
@@ -222,16 +210,18 @@ ggg: out
 hhh: ccc fff iii
 iii: out"#;
 
-        let (you, svr) = parse(input.trim());
-
-        let you = you.unwrap();
-        assert_eq!(you.label, "you");
-        assert_eq!(you.outputs()[0].label, "bbb");
-        assert_eq!(you.outputs()[1].label, "ccc");
-
-        let svr = svr.unwrap();
-        assert_eq!(svr.label, "svr");
-        assert_eq!(svr.outputs()[0].label, "hhh");
-        assert_eq!(svr.outputs()[1].label, "aaa");
+        let devices = parse(input.trim()).unwrap();
+        let outputs = |label| -> Vec<&str> {
+            let device = devices.id(label).unwrap();
+            devices
+                .neighbors(device)
+                .iter()
+                .map(|&output| devices.label(output))
+                .collect()
+        };
+
+        assert_eq!(outputs("you"), ["bbb", "ccc"]);
+        assert_eq!(outputs("svr"), ["hhh", "aaa"]);
+        assert_eq!(outputs("out"), Vec::<&str>::new());
     }
 }
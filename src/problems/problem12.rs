@@ -1,15 +1,22 @@
 // Surprisingly... The solution was as simple as counting the volume taken up by the presents and
 // comparing it to the size of the region. Perhaps I just got a lucky input, but I'm going with it.
 
-use crate::shared::Answer;
+use tracing::{debug, trace};
+
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, Grid, parse},
+    solver::{AnswerValue, Metadata, Solver},
+};
 
 #[derive(Debug)]
-struct Shape {
-    map: Vec<Vec<bool>>,
+pub(crate) struct Shape {
+    map: Grid<bool>,
 }
 
 #[derive(Debug)]
-struct Region {
+pub(crate) struct Region {
     width: usize,
     height: usize,
 
@@ -28,16 +35,21 @@ impl Region {
     }
 }
 
-pub fn solve(input: &str) -> Answer {
-    let (shapes, regions) = parse(input);
+pub fn solve(input: &str, ctx: &SolveCtx) -> Result<Answer, AocError> {
+    ctx.phase("parse");
+    let (shapes, regions) = parse(input)?;
 
     let volume_per_shape: Vec<usize> = shapes
         .iter()
-        .map(|s| s.map.iter().flatten().filter(|&&b| b).count())
+        .map(|s| s.map.cells().filter(|(_, filled)| **filled).count())
         .collect();
+    debug!(shapes = shapes.len(), regions = regions.len(), "parsed");
 
+    ctx.phase("check regions");
     let mut does_not_fit = 0;
-    for region in regions.iter() {
+    for (idx, region) in regions.iter().enumerate() {
+        ctx.progress(idx, regions.len());
+
         let area = region.width * region.height;
 
         let gift_volume: usize = region
@@ -48,17 +60,37 @@ pub fn solve(input: &str) -> Answer {
             .sum();
 
         if area < gift_volume {
+            trace!(idx, area, gift_volume, "region can't fit its presents");
             does_not_fit += 1;
         }
     }
 
-    Answer {
-        part1: regions.len() - does_not_fit,
-        part2: 0,
+    Ok(Answer::part1_only(regions.len() - does_not_fit))
+}
+
+pub struct Problem12;
+
+impl Solver for Problem12 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 12,
+            title: "Christmas Tree Farm",
+            tags: &["heuristic"],
+            has_part2: false,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input, ctx)?.part1.into())
+    }
+
+    // There is no part 2 puzzle for the final day, and the metadata says so, so this is never run.
+    fn solve_part2(&self, _input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        unreachable!("the final day has no part 2")
     }
 }
 
-fn parse(input: &str) -> (Vec<Shape>, Vec<Region>) {
+pub(crate) fn parse(input: &str) -> Result<(Vec<Shape>, Vec<Region>), AocError> {
     let mut lines = input.lines();
 
     // this assumes there's six 3x3 shapes, which is true for both inputs :shrug:
@@ -67,94 +99,63 @@ fn parse(input: &str) -> (Vec<Shape>, Vec<Region>) {
             // skip the header line "0:", etc
             lines.next();
 
-            let map: Vec<Vec<bool>> = (0..3)
-                .map(|_| lines.next().unwrap().chars().map(|c| c == '#').collect())
-                .rev() // flip upside down, for rendering
-                .collect();
+            let invalid = || AocError::Parse("there should be six 3x3 shapes".to_string());
+            let rows: Vec<&str> = (0..3)
+                .map(|_| lines.next().ok_or_else(invalid))
+                .collect::<Result<_, _>>()?;
+            let map = Grid::from_str(&rows.join("\n"), |c| Some(c == '#'))?;
+            if map.dimensions() != (3, 3) {
+                return Err(invalid());
+            }
 
             // skip the blank line between shapes
             lines.next();
 
-            Shape { map }
+            Ok(Shape { map })
         })
-        .collect();
+        .collect::<Result<Vec<_>, AocError>>()?;
 
     let regions = lines
         .map(|line| {
-            let (size_str, presents_str) = line.split_once(": ").unwrap();
+            let invalid =
+                || AocError::Parse(format!("region should be like 4x4: 0 0 0 0 2 0: {line}"));
+            let (size_str, presents_str) = parse::labelled(line).ok_or_else(invalid)?;
 
-            let (width, height) = size_str.split_once("x").unwrap();
+            let (width, height) = size_str.split_once("x").ok_or_else(invalid)?;
             let (width, height) = (
-                width.parse::<usize>().unwrap(),
-                height.parse::<usize>().unwrap(),
+                parse::int(width).ok_or_else(invalid)?,
+                parse::int(height).ok_or_else(invalid)?,
             );
 
-            let present_counts = presents_str
-                .split(" ")
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect();
+            let present_counts: Vec<usize> = parse::ints(presents_str, " ").ok_or_else(invalid)?;
+            if present_counts.len() != shapes.len() {
+                return Err(invalid());
+            }
 
-            Region::new(width, height, present_counts)
+            Ok(Region::new(width, height, present_counts))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (shapes, regions)
+    Ok((shapes, regions))
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::examples;
 
     #[test]
     fn can_parse_input() {
-        let input = r#"
-0:
-###
-##.
-##.
-
-1:
-###
-##.
-.##
-
-2:
-.##
-###
-##.
-
-3:
-##.
-###
-##.
-
-4:
-###
-#..
-###
-
-5:
-###
-.#.
-###
-
-4x4: 0 0 0 0 2 0
-12x5: 1 0 1 0 2 2
-12x5: 1 0 1 0 3 2
-"#;
-        let (shapes, regions) = parse(input.trim());
+        let input = examples::read("12");
+        let (shapes, regions) = parse(&input).unwrap();
 
         assert_eq!(shapes.len(), 6);
         assert_eq!(regions.len(), 3);
 
         assert_eq!(
-            shapes[0].map,
-            vec![
-                vec![true, true, true],
-                vec![true, true, false],
-                vec![true, true, false]
-            ]
+            shapes[0].map.rows().collect::<Vec<_>>(),
+            [[true, true, true], [true, true, false], [true, true, false]]
         );
 
         assert_eq!(regions[1].width, 12);
@@ -1,155 +1,302 @@
-use crate::shared::{Answer, NumericPartitionIterator};
-use rayon::prelude::*;
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{
+        Answer, BigUint,
+        digits::{self, RepdigitPattern},
+    },
+    solver::{AnswerValue, Metadata, Solver},
+};
+use rand::Rng;
 
-/// A product id, which implements validity checks.
-pub struct ProductId {
-    id: usize,
+/// The sums can outgrow even the ids, so they're added up as BigUints.
+pub fn solve(input: &str) -> Result<Answer<BigUint>, AocError> {
+    input.split(",").map(solve_one_range).sum()
 }
 
-impl ProductId {
-    fn new(id: usize) -> Self {
-        ProductId { id }
-    }
+/// Parses every range of ids, as inclusive (start, end) pairs. Ids can be as long as 39 digits,
+/// the most a u128 holds.
+pub(crate) fn parse(input: &str) -> Result<Vec<(u128, u128)>, AocError> {
+    input.split(",").map(parse_range).collect()
+}
+
+fn parse_range(range: &str) -> Result<(u128, u128), AocError> {
+    let invalid = || AocError::Parse(format!("range should be two integers like 11-22: {range}"));
+
+    let (start, end) = range.trim().split_once("-").ok_or_else(invalid)?;
+    let start: u128 = start.parse().map_err(|_| invalid())?;
+    let end: u128 = end.parse().map_err(|_| invalid())?;
+
+    Ok((start, end))
+}
 
-    fn len(&self) -> u32 {
-        self.id.ilog10() + 1
+fn solve_one_range(range: &str) -> Result<Answer<BigUint>, AocError> {
+    let (start, end) = parse_range(range)?;
+    Ok(sum_invalid_ids(start, end))
+}
+
+/// Sums the ids in the range which repeat a block twice for part 1, and at least twice for part 2.
+/// Rather than checking every id, this works out which ids repeat a block from the blocks
+/// themselves, so ranges billions of ids long take no longer than short ones.
+fn sum_invalid_ids(start: u128, end: u128) -> Answer<BigUint> {
+    if start > end {
+        return Answer {
+            part1: BigUint::default(),
+            part2: Some(BigUint::default()),
+        };
     }
+    let lens = digits::digit_count(start)..=digits::digit_count(end);
 
-    fn has_two_matching_partitions(&self) -> bool {
-        if !self.len().is_multiple_of(2) {
-            return false; // odd length strings can't match
-        }
+    let doubled = lens
+        .clone()
+        .filter(|len| len.is_multiple_of(2))
+        .map(|len| sum_repeating(start, end, len, len / 2))
+        .sum();
+    let repeated = lens.map(|len| sum_repeated(start, end, len)).sum();
 
-        self.has_matching_partitions_of_size(self.len() / 2)
+    Answer {
+        part1: doubled,
+        part2: Some(repeated),
     }
+}
 
-    fn has_n_matching_partitions(&self) -> bool {
-        // try split sizes up to half the length, past that it can't divide evenly
-        let mut split_sizes_to_try = 1..=(self.len() / 2);
+/// The sum of the ids in `start..=end` which are `len` digits long, and which are a block of
+/// `block_len` digits repeated, like 123123 with a `len` of 6 and a `block_len` of 3.
+fn sum_repeating(start: u128, end: u128, len: u32, block_len: u32) -> BigUint {
+    // each of those ids is its block times 1001, or 10101, and so on, so the blocks which land in
+    // the range are consecutive. Building that up a block at a time keeps it from overflowing for
+    // the longest ids, where 10^len alone wouldn't fit.
+    let shift = 10_u128.pow(block_len);
+    let multiplier = (1..len / block_len).fold(1, |ones: u128, _| ones * shift + 1);
 
-        split_sizes_to_try.any(|split_size| self.has_matching_partitions_of_size(split_size))
+    let first = start.div_ceil(multiplier).max(shift / 10);
+    let last = (end / multiplier).min(shift - 1);
+    if first > last {
+        return BigUint::default();
     }
 
-    fn has_matching_partitions_of_size(&self, split_size: u32) -> bool {
-        // if s can't be split evenly, it won't have matching partitions
-        if !self.len().is_multiple_of(split_size) {
-            return false;
-        }
+    // blocks are at most 19 digits, so the sum of them fits
+    let blocks = (first + last) * (last - first + 1) / 2;
+    &BigUint::from(multiplier) * &BigUint::from(blocks)
+}
 
-        // split off the first partition, all others must match this
-        let mut partitions = self.partitions(split_size);
-        let first_partition = partitions.next().unwrap();
+/// The sum of the ids in `start..=end` which are `len` digits long, and which are any block
+/// repeated at least twice. An id like 222222 repeats blocks of 1, 2, and 3 digits, so adding up
+/// each block length would count it more than once.
+///
+/// Any id that repeats its block also repeats that block doubled, when that still fits, so
+/// every such id repeats a block of `len / p` for some prime `p` dividing `len`. Those overlap
+/// exactly where the blocks of both lengths repeat, which inclusion-exclusion takes back out.
+fn sum_repeated(start: u128, end: u128, len: u32) -> BigUint {
+    // ids have at most 39 digits, which these are all the primes up to
+    let primes: Vec<u32> = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]
+        .into_iter()
+        .filter(|p| len.is_multiple_of(*p))
+        .collect();
 
-        partitions.all(|this_partition| this_partition == first_partition)
-    }
+    let (mut added, mut taken) = (BigUint::default(), BigUint::default());
+    for chosen in 1..1_u32 << primes.len() {
+        let product: u32 = primes
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| chosen & (1 << idx) != 0)
+            .map(|(_, p)| p)
+            .product();
+        let block_sum = sum_repeating(start, end, len, len / product);
 
-    fn partitions(&self, split_size: u32) -> NumericPartitionIterator {
-        NumericPartitionIterator::new(self.id, split_size)
+        if chosen.count_ones() % 2 == 1 {
+            added += block_sum;
+        } else {
+            taken += block_sum;
+        }
     }
-}
 
-pub fn solve(input: &str) -> Answer {
-    input
-        .split(",")
-        .collect::<Vec<&str>>()
-        .par_iter()
-        .map(|s| solve_one_range(s))
-        .sum()
+    added
+        .checked_sub(&taken)
+        .expect("the overlaps are all among what was added")
 }
 
-fn solve_one_range(range: &str) -> Answer {
-    let split: Vec<&str> = range.trim().split("-").collect();
-    let start: usize = split[0].parse().expect("start of range should be integer");
-    let end: usize = split[1].parse().expect("end of range should be integer");
-
-    (start..=end)
-        .map(|num| {
-            let id = ProductId::new(num);
-            let two_matches = id.has_two_matching_partitions();
-            let n_matches = id.has_n_matching_partitions();
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            // few enough digits that repeated ids turn up often
+            let len = rng.random_range(1..=7);
+            let start = rng.random_range(10_u128.pow(len - 1)..10_u128.pow(len));
+            format!("{start}-{}", start + rng.random_range(0..2000))
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+        + "\n"
+}
 
+/// Solves by checking every id in every range. Only fast enough for short ranges, but simple enough
+/// to check `solve` against.
+pub(crate) fn brute_force(input: &str) -> Result<Answer<BigUint>, AocError> {
+    Ok(parse(input)?
+        .into_iter()
+        .flat_map(|(start, end)| start..=end)
+        .map(|id| {
+            let sum_if =
+                |pattern: RepdigitPattern| BigUint::from(if pattern.matches(id) { id } else { 0 });
             Answer {
-                part1: if two_matches { id.id } else { 0 },
-                part2: if n_matches { id.id } else { 0 },
+                part1: sum_if(RepdigitPattern::Twice),
+                part2: Some(sum_if(RepdigitPattern::AtLeastTwice)),
             }
         })
-        .sum()
+        .sum())
+}
+
+pub struct Problem2;
+
+// Both parts are counted from the same blocks, so each part reuses solve.
+impl Solver for Problem2 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 2,
+            title: "Gift Shop",
+            tags: &["math"],
+            has_part2: true,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part1.into())
+    }
+
+    fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part2.expect("part 2 is always solved").into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::examples;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
 
     #[test]
     fn solve_basic_input() {
-        let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+        let input = examples::read("2");
 
-        let result = solve(input);
-        assert_eq!(result.part1, 1227775554);
-        assert_eq!(result.part2, 4174379265);
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, big(1227775554));
+        assert_eq!(result.part2, Some(big(4174379265)));
+    }
+
+    #[test]
+    fn malformed_range_is_an_error() {
+        assert!(solve("11-22,95").is_err());
     }
 
     #[test]
     fn solve_one_range_11_22() {
         let input = "11-22";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 33);
-        assert_eq!(result.part2, 33);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(33));
+        assert_eq!(result.part2, Some(big(33)));
     }
 
     #[test]
     fn solve_one_range_95_115() {
         let input = "95-115";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 99);
-        assert_eq!(result.part2, 99 + 111);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(99));
+        assert_eq!(result.part2, Some(big(99 + 111)));
     }
 
     #[test]
     fn solve_one_range_998_1012() {
         let input = "998-1012";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 1010);
-        assert_eq!(result.part2, 999 + 1010);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(1010));
+        assert_eq!(result.part2, Some(big(999 + 1010)));
     }
 
     #[test]
     fn solve_one_range_1188511880_1188511890() {
         let input = "1188511880-1188511890";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 1188511885);
-        assert_eq!(result.part2, 1188511885);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(1188511885));
+        assert_eq!(result.part2, Some(big(1188511885)));
     }
 
     #[test]
     fn solve_one_range_222220_222224() {
         let input = "222220-222224";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 222222);
-        assert_eq!(result.part2, 222222);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(222222));
+        assert_eq!(result.part2, Some(big(222222)));
     }
 
     #[test]
     fn solve_one_range_1698522_1698528() {
         let input = "1698522-1698528";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 0);
-        assert_eq!(result.part2, 0);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(0));
+        assert_eq!(result.part2, Some(big(0)));
     }
 
     #[test]
     fn solve_one_range_446443_446449() {
         let input = "446443-446449";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 446446);
-        assert_eq!(result.part2, 446446);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(446446));
+        assert_eq!(result.part2, Some(big(446446)));
+    }
+
+    #[test]
+    fn solve_ranges_of_billions() {
+        // every id in ranges that long would take minutes to check, but the blocks don't
+        let result = solve_one_range("1-9999999999").unwrap();
+        let doubled = (1..=5_u32)
+            .map(|len| {
+                (10_usize.pow(len - 1)..10_usize.pow(len)).sum::<usize>() * (10_usize.pow(len) + 1)
+            })
+            .sum::<usize>();
+        assert_eq!(result.part1, BigUint::from(doubled));
+
+        let result = solve_one_range("1000000000-1999999999").unwrap();
+        assert_eq!(
+            result.part1,
+            BigUint::from((10000..20000).map(|block| block * 100001).sum::<usize>())
+        );
+    }
+
+    #[test]
+    fn solve_ids_past_u64() {
+        // a 38 digit id, made of a 19 digit block twice
+        let doubled = 1_234_567_890_123_456_789 * (10_u128.pow(19) + 1);
+        let range = format!("{}-{}", doubled - 1000, doubled + 1000);
+        let result = solve(&range).unwrap();
+        assert_eq!(result.part1, BigUint::from(doubled));
+        assert_eq!(result.part2, brute_force(&range).unwrap().part2);
+
+        // every doubled 38 digit id adds up to far more than a u128 holds
+        let result = solve(&format!("{}-{}", 10_u128.pow(37), u128::MAX)).unwrap();
+        assert_eq!(result.part1.to_u128(), None);
+        assert!(parse("1-340282366920938463463374607431768211456").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn counting_matches_checking_every_id(start in 0..2_000_000_usize, len in 0..3000_usize) {
+            let range = format!("{start}-{}", start + len);
+            let (counted, checked) = (solve(&range).unwrap(), brute_force(&range).unwrap());
+            prop_assert_eq!(counted.part1, checked.part1);
+            prop_assert_eq!(counted.part2, checked.part2);
+        }
     }
 
     #[test]
     fn solve_one_range_38593856_38593862() {
         let input = "38593856-38593862";
-        let result = solve_one_range(input);
-        assert_eq!(result.part1, 38593859);
-        assert_eq!(result.part2, 38593859);
+        let result = solve_one_range(input).unwrap();
+        assert_eq!(result.part1, big(38593859));
+        assert_eq!(result.part2, Some(big(38593859)));
     }
 }
@@ -1,4 +1,10 @@
-use crate::shared::Answer;
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, BigUint},
+    solver::{AnswerValue, Metadata, Param, Solver},
+};
+use rand::Rng;
 
 /// A BatteryBank has many batteries and can calculate its own maximum joltage for a given number
 /// of batteries.
@@ -6,21 +12,59 @@ use crate::shared::Answer;
 /// Maximum joltage is calculated by starting with the N right-most batteries. The remaining
 /// batteries are processed right to left, with a new battery being offered to the first slot. If
 /// the a battery is replaced, its own battery is made available to the next slot.
-struct BatteryBank<'a> {
+pub(crate) struct BatteryBank<'a> {
     joltages: &'a str,
 }
 
 impl<'a> BatteryBank<'a> {
-    fn new(joltages: &'a str) -> Self {
-        BatteryBank { joltages }
+    fn new(joltages: &'a str) -> Result<Self, AocError> {
+        if !joltages.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AocError::Parse(format!(
+                "battery bank should only have digits: {joltages}"
+            )));
+        }
+
+        Ok(BatteryBank { joltages })
+    }
+
+    /// The maximum joltage, or None when it's too big for a usize, which it can be from 20
+    /// batteries up.
+    fn maximum_joltage(&self, max_batteries: usize) -> Option<usize> {
+        self.maximum_big_joltage(max_batteries).to_usize()
+    }
+
+    /// The maximum joltage, however many batteries that takes.
+    fn maximum_big_joltage(&self, max_batteries: usize) -> BigUint {
+        let ten = BigUint::from(10_u64);
+        self.select_batteries(max_batteries)
+            .into_iter()
+            .fold(BigUint::default(), |joltage, battery| {
+                &joltage * &ten + BigUint::from(u64::from(battery))
+            })
     }
 
-    fn maximum_joltage(&self, max_batteries: usize) -> usize {
-        // CALCULATE INDIVIDUAL JOLTAGES (iterates right to left)
-        let mut battery_iter = self.joltages.chars().rev().map(|c| c.to_digit(10).unwrap());
+    /// The joltage of each battery which makes up the maximum joltage, in order.
+    fn select_batteries(&self, max_batteries: usize) -> Vec<u32> {
+        self.select_positions(max_batteries)
+            .into_iter()
+            .map(|idx| u32::from(self.joltages.as_bytes()[idx] - b'0'))
+            .collect()
+    }
+
+    /// Where each battery which makes up the maximum joltage is in the bank, in order.
+    fn select_positions(&self, max_batteries: usize) -> Vec<usize> {
+        // CALCULATE INDIVIDUAL JOLTAGES (iterates right to left), keeping where each one is. Banks
+        // are checked to be all digits, so each byte is a battery, which is much quicker to go
+        // through than decoding chars when banks are millions of batteries long.
+        let mut battery_iter = self
+            .joltages
+            .bytes()
+            .enumerate()
+            .rev()
+            .map(|(idx, b)| (idx, u32::from(b - b'0')));
 
         // INITIALIZE JOLTAGES
-        let mut selected: Vec<u32> = battery_iter.by_ref().take(max_batteries).collect();
+        let mut selected: Vec<(usize, u32)> = battery_iter.by_ref().take(max_batteries).collect();
         selected.reverse();
 
         // DETERMINE MAXIMIZED JOLTAGE ARRAY
@@ -29,7 +73,7 @@ impl<'a> BatteryBank<'a> {
             let mut available_battery = this_battery;
 
             for selected_battery in selected.iter_mut() {
-                if available_battery >= *selected_battery {
+                if available_battery.1 >= selected_battery.1 {
                     // swap out a selected battery for a better one
                     // making the old battery available
                     std::mem::swap(&mut *selected_battery, &mut available_battery);
@@ -39,84 +83,235 @@ impl<'a> BatteryBank<'a> {
             }
         }
 
-        // CALCULATE MAXIMUM JOLTAGE
-        selected
-            .iter()
-            .map(|i| i.to_string())
-            .collect::<String>()
-            .parse()
-            .unwrap()
+        selected.into_iter().map(|(idx, _)| idx).collect()
     }
 }
 
-pub fn solve(input: &str) -> Answer {
+const PART1_BATTERIES: Param = Param {
+    name: "part1_batteries",
+    about: "how many batteries part 1 turns on in each bank (2)",
+};
+
+const PART2_BATTERIES: Param = Param {
+    name: "part2_batteries",
+    about: "how many batteries part 2 turns on in each bank (12)",
+};
+
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    parse(input)?.into_iter().map(solve_one).sum()
+}
+
+/// Parses every battery bank, checking they're all digits.
+pub(crate) fn parse(input: &str) -> Result<Vec<BatteryBank<'_>>, AocError> {
     input
         .split("\n")
         .filter(|s| !s.trim().is_empty())
         .map(BatteryBank::new)
-        .map(solve_one)
-        .sum()
+        .collect()
 }
 
-fn solve_one(battery_bank: BatteryBank) -> Answer {
-    Answer {
-        part1: battery_bank.maximum_joltage(2),
-        part2: battery_bank.maximum_joltage(12),
+/// Generates a few banks which are each `size` batteries long, since it's long banks rather than
+/// many of them which stress the solver, like `cargo run gen 3 --size 1000000`.
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    const BANKS: usize = 10;
+
+    (0..BANKS)
+        .map(|_| {
+            let mut bank: String = (0..size.max(1))
+                .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                .collect();
+            bank.push('\n');
+            bank
+        })
+        .collect()
+}
+
+/// Sums the maximum joltage of every battery bank, using `max_batteries` from each.
+fn total_maximum_joltage(input: &str, max_batteries: usize) -> Result<BigUint, AocError> {
+    Ok(parse(input)?
+        .iter()
+        .map(|bank| bank.maximum_big_joltage(max_batteries))
+        .sum())
+}
+
+fn solve_one(battery_bank: BatteryBank) -> Result<Answer, AocError> {
+    let joltage = |max_batteries| {
+        battery_bank.maximum_joltage(max_batteries).ok_or_else(|| {
+            AocError::Unsolvable(format!(
+                "the joltage of {max_batteries} batteries is too big for a usize"
+            ))
+        })
+    };
+
+    Ok(Answer {
+        part1: joltage(2)?,
+        part2: Some(joltage(12)?),
+    })
+}
+
+pub struct Problem3;
+
+impl Solver for Problem3 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 3,
+            title: "Lobby",
+            tags: &["greedy"],
+            has_part2: true,
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[PART1_BATTERIES, PART2_BATTERIES]
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let batteries = ctx.param(PART1_BATTERIES.name, 2)?;
+        Ok(total_maximum_joltage(input, batteries)?.into())
+    }
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let batteries = ctx.param(PART2_BATTERIES.name, 12)?;
+        Ok(total_maximum_joltage(input, batteries)?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::{examples, shared::combinatorics};
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"987654321111111
-811111111111119
-234234234234278
-818181911112111"#;
+        let input = examples::read("3");
 
-        let result = solve(input);
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 357);
-        assert_eq!(result.part2, 3121910778619);
+        assert_eq!(result.part2, Some(3121910778619));
     }
 
     #[test]
     fn solve_example_one() {
-        let result = solve_one(BatteryBank::new("987654321111111"));
+        let result = solve_one(BatteryBank::new("987654321111111").unwrap()).unwrap();
 
         assert_eq!(result.part1, 98);
-        assert_eq!(result.part2, 987654321111);
+        assert_eq!(result.part2, Some(987654321111));
     }
 
     #[test]
     fn solve_example_two() {
-        let result = solve_one(BatteryBank::new("811111111111119"));
+        let result = solve_one(BatteryBank::new("811111111111119").unwrap()).unwrap();
 
         assert_eq!(result.part1, 89);
-        assert_eq!(result.part2, 811111111119);
+        assert_eq!(result.part2, Some(811111111119));
     }
 
     #[test]
     fn solve_example_three() {
-        let result = solve_one(BatteryBank::new("234234234234278"));
+        let result = solve_one(BatteryBank::new("234234234234278").unwrap()).unwrap();
 
         assert_eq!(result.part1, 78);
-        assert_eq!(result.part2, 434234234278);
+        assert_eq!(result.part2, Some(434234234278));
     }
 
     #[test]
     fn solve_example_four() {
-        let result = solve_one(BatteryBank::new("818181911112111"));
+        let result = solve_one(BatteryBank::new("818181911112111").unwrap()).unwrap();
 
         assert_eq!(result.part1, 92);
-        assert_eq!(result.part2, 888911112111);
+        assert_eq!(result.part2, Some(888911112111));
     }
 
     #[test]
     fn solve_example_five_mine() {
-        let result = solve_one(BatteryBank::new("818191911112111"));
+        let result = solve_one(BatteryBank::new("818191911112111").unwrap()).unwrap();
 
         assert_eq!(result.part1, 99);
     }
+
+    #[test]
+    fn select_more_batteries_than_fit_in_a_usize() {
+        let bank = format!("{}{}", "1".repeat(10), "9".repeat(30));
+        let bank = BatteryBank::new(&bank).unwrap();
+        assert_eq!(bank.select_batteries(30), vec![9; 30]);
+        assert_eq!(bank.maximum_big_joltage(30).to_string(), "9".repeat(30));
+
+        // only ten batteries can be left out, so not enough of the 1s to reach the 7 sooner
+        let bank = format!("5{}7{}", "1".repeat(20), "2".repeat(20));
+        let bank = BatteryBank::new(&bank).unwrap();
+        assert_eq!(
+            bank.maximum_big_joltage(32).to_string(),
+            format!("5{}7{}", "1".repeat(10), "2".repeat(20))
+        );
+
+        // the smaller counts give the same joltage either way
+        assert_eq!(
+            bank.maximum_joltage(12),
+            bank.maximum_big_joltage(12).to_usize()
+        );
+        assert_eq!(bank.maximum_joltage(32), None);
+    }
+
+    #[test]
+    fn battery_counts_from_params() {
+        let input = examples::read("3");
+        let params = [
+            (PART1_BATTERIES.name.to_string(), "3".to_string()),
+            (PART2_BATTERIES.name.to_string(), "30".to_string()),
+        ];
+        let ctx = SolveCtx::default().with_params(params.into());
+
+        assert_eq!(
+            Problem3.solve_part1(&input, &ctx),
+            Ok(AnswerValue::Int(987 + 819 + 478 + 921))
+        );
+
+        // each bank only has 15 batteries, so asking for 30 turns them all on
+        assert_eq!(
+            Problem3.solve_part2(&input, &ctx),
+            Ok(AnswerValue::Int(
+                987654321111111 + 811111111111119 + 234234234234278 + 818181911112111
+            ))
+        );
+
+        // without params, they're the puzzle's own counts
+        let ctx = SolveCtx::default();
+        assert_eq!(
+            Problem3.solve_part1(&input, &ctx),
+            Ok(AnswerValue::Int(357))
+        );
+        assert_eq!(
+            Problem3.solve_part2(&input, &ctx),
+            Ok(AnswerValue::Int(3121910778619))
+        );
+    }
+
+    #[test]
+    fn select_positions_of_example_one() {
+        let bank = BatteryBank::new("811111111111119").unwrap();
+        assert_eq!(bank.select_positions(2), [0, 14]);
+
+        // any three of the 1s would do, and the leftmost ones win
+        assert_eq!(bank.select_positions(4), [0, 1, 2, 14]);
+    }
+
+    proptest! {
+        #[test]
+        fn positions_match_trying_every_choice(joltages in "[1-9]{1,10}", count in 1..5_usize) {
+            let bank = BatteryBank::new(&joltages).unwrap();
+            let positions = bank.select_positions(count);
+            prop_assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+
+            let digits: Vec<char> = joltages.chars().collect();
+            let joltage = |chosen: Vec<&char>| chosen.into_iter().collect::<String>();
+            let best = combinatorics::combinations(&digits, count.min(digits.len()))
+                .map(joltage)
+                .max()
+                .unwrap();
+            let selected = joltage(positions.iter().map(|&idx| &digits[idx]).collect());
+            prop_assert_eq!(selected, best);
+        }
+    }
 }
@@ -1,174 +1,395 @@
-use crate::shared::{Alternator, Answer, Neighborator};
-
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Alternator, Answer, Grid, Neighborator},
+    solver::{AnswerValue, Metadata, Param, Solver},
+};
+
+/// Rolls with this many neighbors can't be removed.
 const TOO_MANY_NEIGHBORS: usize = 4;
 
+/// Chooses a different threshold than `TOO_MANY_NEIGHBORS`, like `--param neighbors=5`.
+const NEIGHBORS: Param = Param {
+    name: "neighbors",
+    about: "rolls with this many neighbors can't be removed (4)",
+};
+
 /// A helpful diagram showing where rolls of paper are, and how many neighbors each one has. When
-/// a roll is removed, the neighbor counts are updated and other rolls will be removed recursively.
-struct HelpfulDiagram {
-    rolls: Vec<Vec<bool>>,
-    neighbor_counts: Vec<Vec<usize>>,
+/// a roll is removed, the neighbor counts are updated and other rolls will be removed in turn.
+pub(crate) struct HelpfulDiagram {
+    rolls: Grid<bool>,
+    neighbor_counts: Grid<usize>,
 
-    width: usize,
-    height: usize,
+    /// Rolls with at least this many neighbors can't be removed.
+    too_many_neighbors: usize,
 }
 
 impl HelpfulDiagram {
-    pub fn parse(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let width = lines[0].len();
-        let height = lines.len();
-
-        // create empty rolls/neighbor_counts 2d vectors, populated below
-        let rolls = vec![vec![false; height]; width];
-        let neighbor_counts = vec![vec![0_usize; height]; width];
-
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let parsed = Grid::from_str(input, |c| match c {
+            '@' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let (width, height) = parsed.dimensions();
+
+        // start empty, then add the paper rolls, which updates rolls & neighbor_counts
         let mut diagram = Self {
-            width,
-            height,
-            rolls,
-            neighbor_counts,
+            rolls: Grid::new(width, height, false),
+            neighbor_counts: Grid::new(width, height, 0),
+            too_many_neighbors: TOO_MANY_NEIGHBORS,
         };
-
-        // add the paper rolls to the diagram, which updates rolls & neighbor_counts
-        for (y, line) in lines.iter().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                if c == '@' {
-                    diagram.add_roll(x, y);
-                }
-            }
+        for ((x, y), _) in parsed.cells().filter(|(_, roll)| **roll) {
+            diagram.add_roll(x, y);
         }
 
-        diagram
+        Ok(diagram)
     }
 
     /// Adds a roll to the diagram, updating neighbor counts.
     pub fn add_roll(&mut self, x: usize, y: usize) {
-        self.rolls[x][y] = true;
+        self.rolls[(x, y)] = true;
 
-        for (neighbor_x, neighbor_y) in self.neighborator(x, y) {
-            self.neighbor_counts[neighbor_x][neighbor_y] += 1;
+        for neighbor in self.neighborator(x, y) {
+            self.neighbor_counts[neighbor] += 1;
         }
     }
 
     /// Removes a roll, updating all neighbors and removing those as well if possible. Returns how
-    /// many rolls were removed in total.
-    pub fn remove_roll_recursive(&mut self, x: usize, y: usize) -> usize {
-        self.rolls[x][y] = false;
+    /// many rolls were removed in total. `on_removed` sees the diagram after each removal, for
+    /// drawing the cascade as it happens.
+    ///
+    /// Each neighbor which can be removed is removed straight away, with its own neighbors dealt
+    /// with before moving on to the next. That goes as deep as the cascade does, so the rolls
+    /// still to be dealt with are kept on a stack, rather than recursing, which would overflow on
+    /// big dense diagrams.
+    pub fn remove_roll_cascade(
+        &mut self,
+        x: usize,
+        y: usize,
+        on_removed: &dyn Fn(&HelpfulDiagram),
+    ) -> usize {
+        // each roll being removed, as the neighbors it has left to update
+        let mut removing = vec![self.remove_roll(x, y)];
+        on_removed(self);
         let mut removed_count = 1;
 
-        // self.draw();
-        // sleep(Duration::from_millis(5));
-
-        for (neighbor_x, neighbor_y) in self.neighborator(x, y) {
-            self.neighbor_counts[neighbor_x][neighbor_y] -= 1;
-
-            let has_roll = self.has_roll_at(neighbor_x, neighbor_y);
-            if has_roll && self.neighbor_counts[neighbor_x][neighbor_y] < TOO_MANY_NEIGHBORS {
-                removed_count += self.remove_roll_recursive(neighbor_x, neighbor_y);
+        while let Some(neighbors) = removing.last_mut() {
+            let Some((neighbor_x, neighbor_y)) = neighbors.next() else {
+                removing.pop();
+                continue;
+            };
+            self.neighbor_counts[(neighbor_x, neighbor_y)] -= 1;
+
+            if self.is_removable(neighbor_x, neighbor_y) {
+                removing.push(self.remove_roll(neighbor_x, neighbor_y));
+                removed_count += 1;
+                on_removed(self);
             }
         }
 
         removed_count
     }
 
-    #[allow(dead_code)]
-    fn draw(&self) {
-        let mut buffer = String::new();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.has_roll_at(x, y) {
-                    buffer.push('@');
-                } else {
-                    buffer.push(' ');
+    /// Removes rolls a wave at a time, where each wave is every roll which could be removed when it
+    /// started, and rolls only freed up by the wave wait for the next one. Returns every wave that
+    /// removed something, in order. However the waves fall, the same rolls are removed in the end
+    /// as by cascading.
+    pub fn remove_in_waves(&mut self) -> Vec<Wave> {
+        let mut remaining = self.rolls.cells().filter(|(_, roll)| **roll).count();
+        let mut removable: Vec<(usize, usize)> = self
+            .rolls
+            .cells()
+            .map(|(pos, _)| pos)
+            .filter(|&(x, y)| self.is_removable(x, y))
+            .collect();
+
+        let mut waves = Vec::new();
+        while !removable.is_empty() {
+            // only the neighbors of rolls just removed can have become removable
+            let mut candidates = Vec::new();
+            for &(x, y) in &removable {
+                for neighbor in self.remove_roll(x, y) {
+                    self.neighbor_counts[neighbor] -= 1;
+                    candidates.push(neighbor);
                 }
             }
-            buffer.push('\n');
+
+            remaining -= removable.len();
+            waves.push(Wave {
+                removed: removable.len(),
+                remaining,
+            });
+
+            candidates.sort_unstable();
+            candidates.dedup();
+            removable = candidates
+                .into_iter()
+                .filter(|&(x, y)| self.is_removable(x, y))
+                .collect();
         }
-        print!("\x1B[2J"); // clear screen
-        println!("{buffer}");
+
+        waves
+    }
+
+    /// Takes a roll away, without updating anything else. Returns its neighbors, which still need
+    /// updating.
+    fn remove_roll(&mut self, x: usize, y: usize) -> Neighborator {
+        self.rolls[(x, y)] = false;
+        self.neighborator(x, y)
     }
 
-    /// Checks if a roll is present. Returns false if out of bounds.
+    /// Draws the rolls, like they appear in the input.
+    fn render(&self) -> String {
+        self.rolls
+            .map(|&roll| if roll { '@' } else { ' ' })
+            .to_string()
+    }
+
+    /// Checks if a roll is present.
     fn has_roll_at(&self, x: usize, y: usize) -> bool {
-        self.rolls[x][y]
+        self.rolls[(x, y)]
+    }
+
+    fn count_adjacent_rolls(&self, x: usize, y: usize) -> usize {
+        self.neighbor_counts[(x, y)]
     }
 
-    fn count_adjacent_rolls(&self, x: i32, y: i32) -> usize {
-        self.neighbor_counts[x as usize][y as usize]
+    /// Whether there's a roll here with few enough neighbors to take it away.
+    fn is_removable(&self, x: usize, y: usize) -> bool {
+        self.has_roll_at(x, y) && self.count_adjacent_rolls(x, y) < self.too_many_neighbors
     }
 
     /// Iterates over in bounds neighbors
     fn neighborator(&self, x: usize, y: usize) -> Neighborator {
-        Neighborator::new((x, y), (self.width, self.height))
+        self.rolls.neighbors(x, y)
     }
 }
 
-pub fn solve(input: &str) -> Answer {
-    let mut diagram = HelpfulDiagram::parse(input);
+/// One wave of removals, from `HelpfulDiagram::remove_in_waves`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Wave {
+    /// How many rolls this wave removed.
+    pub removed: usize,
 
-    // Check which rolls can initially be removed (for part 1).
-    let mut can_initially_remove = 0;
-    for y in 0..diagram.height {
-        for x in 0..diagram.width {
-            let adjacent_rolls = diagram.count_adjacent_rolls(x as i32, y as i32);
+    /// How many rolls were left afterwards.
+    pub remaining: usize,
+}
 
-            if diagram.has_roll_at(x, y) && adjacent_rolls < TOO_MANY_NEIGHBORS {
-                can_initially_remove += 1;
-            }
-        }
+/// Every wave of removals from the input's diagram, for seeing how the cascade plays out rather
+/// than only where it ends.
+pub fn removal_waves(input: &str) -> Result<Vec<Wave>, AocError> {
+    let mut diagram = HelpfulDiagram::parse(input)?;
+    Ok(diagram.remove_in_waves())
+}
+
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    let diagram = HelpfulDiagram::parse(input)?;
+    Ok(solve_diagram(diagram))
+}
+
+/// Solves with rolls that have `too_many_neighbors` neighbors staying put, for trying out
+/// different thresholds on the same diagram.
+pub fn solve_with(input: &str, too_many_neighbors: usize) -> Result<Answer, AocError> {
+    let mut diagram = HelpfulDiagram::parse(input)?;
+    diagram.too_many_neighbors = too_many_neighbors;
+    Ok(solve_diagram(diagram))
+}
+
+/// Parses the diagram, with the threshold from the `neighbors` param if one was given.
+fn parse_with_param(input: &str, ctx: &SolveCtx) -> Result<HelpfulDiagram, AocError> {
+    let too_many_neighbors = ctx.param(NEIGHBORS.name, TOO_MANY_NEIGHBORS)?;
+
+    ctx.phase("parse");
+    let mut diagram = HelpfulDiagram::parse(input)?;
+    diagram.too_many_neighbors = too_many_neighbors;
+    Ok(diagram)
+}
+
+fn solve_diagram(mut diagram: HelpfulDiagram) -> Answer {
+    Answer {
+        part1: count_initially_removable(&diagram),
+        part2: Some(remove_eventually(&mut diagram, &SolveCtx::default())),
     }
+}
+
+/// Counts which rolls can be removed before any others are removed.
+fn count_initially_removable(diagram: &HelpfulDiagram) -> usize {
+    diagram
+        .rolls
+        .cells()
+        .filter(|&((x, y), _)| diagram.is_removable(x, y))
+        .count()
+}
 
-    // Recursively removes rolls, as it becomes possible to remove them.
+/// Removes rolls, as it becomes possible to remove them. Returns how many were
+/// removed in total. Each removal is drawn as a frame, when visualizing.
+fn remove_eventually(diagram: &mut HelpfulDiagram, ctx: &SolveCtx) -> usize {
     let mut can_eventually_remove = 0;
+    let draw = |diagram: &HelpfulDiagram| ctx.frame(|| diagram.render());
 
+    let (width, height) = diagram.rolls.dimensions();
     for _ in 0..3500 {
-        let x = rand::random_range(0..diagram.width);
-        let y = rand::random_range(0..diagram.height);
+        let x = rand::random_range(0..width);
+        let y = rand::random_range(0..height);
 
-        let adjacent_rolls = diagram.count_adjacent_rolls(x as i32, y as i32);
+        if diagram.is_removable(x, y) {
+            can_eventually_remove += diagram.remove_roll_cascade(x, y, &draw);
+        }
+    }
 
-        if diagram.has_roll_at(x, y) && adjacent_rolls < TOO_MANY_NEIGHBORS {
-            can_eventually_remove += diagram.remove_roll_recursive(x, y);
+    for y in Alternator::new(0..height) {
+        for x in Alternator::new(0..width) {
+            if diagram.is_removable(x, y) {
+                can_eventually_remove += diagram.remove_roll_cascade(x, y, &draw);
+            }
         }
     }
 
-    for y in Alternator::new(0..diagram.height) {
-        for x in Alternator::new(0..diagram.width) {
-            let adjacent_rolls = diagram.count_adjacent_rolls(x as i32, y as i32);
+    can_eventually_remove
+}
 
-            if diagram.has_roll_at(x, y) && adjacent_rolls < TOO_MANY_NEIGHBORS {
-                can_eventually_remove += diagram.remove_roll_recursive(x, y);
-            }
+pub struct Problem4;
+
+impl Solver for Problem4 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 4,
+            title: "Printing Department",
+            tags: &["grid"],
+            has_part2: true,
         }
     }
 
-    Answer {
-        part1: can_initially_remove,
-        part2: can_eventually_remove,
+    fn params(&self) -> &'static [Param] {
+        &[NEIGHBORS]
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let diagram = parse_with_param(input, ctx)?;
+
+        ctx.phase("count removable");
+        Ok(count_initially_removable(&diagram).into())
+    }
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let mut diagram = parse_with_param(input, ctx)?;
+
+        ctx.phase("remove");
+        Ok(remove_eventually(&mut diagram, ctx).into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ctx::SolveEvent, examples};
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-..@@.@@@@.
-@@@.@.@.@@
-@@@@@.@.@@
-@.@@@@..@.
-@@.@@@@.@@
-.@@@@@@@.@
-.@.@.@.@@@
-@.@@@.@@@@
-.@@@@@@@@.
-@.@.@@@.@."#;
-
-        let result = solve(input.trim());
+        let input = examples::read("4");
+
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 13);
-        assert_eq!(result.part2, 43);
+        assert_eq!(result.part2, Some(43));
+    }
+
+    #[test]
+    fn dense_diagrams_dont_overflow_the_stack() {
+        // only the corners can be removed at first, but each roll removed from the end of the band
+        // frees up the next, so one removal cascades all the way along
+        let row = "@".repeat(50_000);
+        let input = format!("{row}\n{row}");
+
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, 4);
+        assert_eq!(result.part2, Some(2 * 50_000));
+    }
+
+    #[test]
+    fn visualizing_draws_each_removal() {
+        let input = examples::read("4");
+        let (ctx, events) = SolveCtx::new();
+        let ctx = ctx.visualized(true);
+
+        Problem4.solve_part2(&input, &ctx).unwrap();
+        drop(ctx);
+
+        let frames: Vec<String> = events
+            .iter()
+            .filter_map(|event| match event {
+                SolveEvent::Frame(frame) => Some(frame),
+                _ => None,
+            })
+            .collect();
+        let rolls = |s: &str| s.chars().filter(|&c| c == '@').count();
+        assert_eq!(frames.len(), 43);
+        assert_eq!(rolls(&input) - rolls(frames.last().unwrap()), 43);
+    }
+
+    #[test]
+    fn removing_in_waves() {
+        let input = examples::read("4");
+        let waves = removal_waves(&input).unwrap();
+
+        let removed: Vec<usize> = waves.iter().map(|wave| wave.removed).collect();
+        assert_eq!(removed, [13, 12, 7, 5, 2, 1, 1, 1, 1]);
+
+        // each wave leaves what the last one did, less what it removed
+        let rolls = input.chars().filter(|&c| c == '@').count();
+        let mut remaining = rolls;
+        for wave in &waves {
+            remaining -= wave.removed;
+            assert_eq!(wave.remaining, remaining);
+        }
+        assert_eq!(rolls - remaining, 43);
+
+        // a square loses its corners, then its edges, then its middle
+        let removed: Vec<usize> = removal_waves("@@@\n@@@\n@@@")
+            .unwrap()
+            .iter()
+            .map(|wave| wave.removed)
+            .collect();
+        assert_eq!(removed, [4, 4, 1]);
+
+        // nothing to remove means no waves at all
+        assert!(removal_waves("...\n...").unwrap().is_empty());
+    }
+
+    #[test]
+    fn neighbor_threshold_can_be_chosen() {
+        let example = examples::read("4");
+        let rolls = example.chars().filter(|&c| c == '@').count();
+
+        // with room for every neighbor, everything can go straight away
+        let result = solve_with(&example, 9).unwrap();
+        assert_eq!(result.part1, rolls);
+        assert_eq!(result.part2, Some(rolls));
+
+        // and with none, nothing can
+        let result = solve_with(&example, 0).unwrap();
+        assert_eq!(result.part1, 0);
+        assert_eq!(result.part2, Some(0));
+    }
+
+    #[test]
+    fn neighbor_threshold_from_a_param() {
+        let example = examples::read("4");
+        let with = |value: &str| {
+            let params = [(NEIGHBORS.name.to_string(), value.to_string())];
+            SolveCtx::default().with_params(params.into())
+        };
+
+        assert_eq!(Problem4.solve_part1(&example, &with("0")), Ok(0.into()));
+        assert_eq!(Problem4.solve_part2(&example, &with("0")), Ok(0.into()));
+        assert_eq!(
+            Problem4.solve_part1(&example, &SolveCtx::default()),
+            Ok(13.into())
+        );
+        assert!(matches!(
+            Problem4.solve_part1(&example, &with("four")),
+            Err(AocError::Usage(_))
+        ));
     }
 
     #[test]
@@ -177,7 +398,7 @@ mod tests {
 ..@@
 @@@."#;
 
-        let diagram = HelpfulDiagram::parse(input.trim());
+        let diagram = HelpfulDiagram::parse(input.trim()).unwrap();
         assert!(!diagram.has_roll_at(0, 0));
         assert!(!diagram.has_roll_at(1, 0));
         assert!(diagram.has_roll_at(2, 0));
@@ -195,7 +416,7 @@ mod tests {
 .@@
 @.@"#;
 
-        let diagram = HelpfulDiagram::parse(input.trim());
+        let diagram = HelpfulDiagram::parse(input.trim()).unwrap();
         assert_eq!(diagram.count_adjacent_rolls(0, 0), 1);
         assert_eq!(diagram.count_adjacent_rolls(2, 0), 2);
         assert_eq!(diagram.count_adjacent_rolls(1, 1), 5);
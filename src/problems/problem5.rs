@@ -1,78 +1,64 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{io::BufRead, ops::RangeInclusive, str::FromStr};
 
-use crate::shared::Answer;
+use rand::Rng;
+
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, RangeSet},
+    solver::{AnswerValue, Metadata, Solver},
+};
 
 /// A complicated inventory management system which tracks fresh ingredients by ranges of ids.
 ///
-/// Builds a vector of ranges, merging overlapping ranges in the process. Once this structure
-/// exists, it can be queried in two ways:
+/// Merges overlapping ranges into a `RangeSet`. Once this structure exists, it can be queried in
+/// two ways:
 ///
 /// 1. Check freshness of an ingredient: Find the closest range by using binary search on the
-///    vector of ranges. Check this one range to see if the ingredient is fresh.
+///    merged ranges. Check this one range to see if the ingredient is fresh.
 /// 2. Count all fresh ingredients: Add up the size of all ranges.
-struct ComplicatedInventoryManagmentSystem {
-    fresh_ingredients: Vec<IngredientRange>,
+pub(crate) struct ComplicatedInventoryManagmentSystem {
+    fresh_ingredients: RangeSet,
 }
 
 impl ComplicatedInventoryManagmentSystem {
-    fn load(fresh_ingredients: &str) -> Self {
+    fn load(fresh_ingredients: &str) -> Result<Self, AocError> {
         let fresh_ingredients: Vec<IngredientRange> = fresh_ingredients
             .lines()
-            .map(|s| s.parse().unwrap())
-            .collect();
+            .enumerate()
+            .map(|(idx, line)| parse_range(idx, line))
+            .collect::<Result<_, _>>()?;
 
-        Self {
-            fresh_ingredients: Self::build_non_overlapping_ingredient_ranges(fresh_ingredients),
-        }
+        Self::new(fresh_ingredients.iter().map(IngredientRange::ids).collect())
     }
 
-    fn build_non_overlapping_ingredient_ranges(
-        mut fresh_ingredients: Vec<IngredientRange>,
-    ) -> Vec<IngredientRange> {
-        // Sorting simplifies building out the non-overlapping list.
-        //
-        // Without sorting, edge cases emerge around:
-        // 1. ranges which overlap multiple ranges
-        // 2. ranges which are entirely contained within others
-        fresh_ingredients.sort_unstable();
-
-        // Pull out one range before looping, so the previous entry always exists.
-        let mut iter = fresh_ingredients.into_iter();
-        let mut fresh_ingredients_merged = vec![iter.next().unwrap()];
-
-        for range in iter {
-            let previous = fresh_ingredients_merged.last_mut().unwrap();
-
-            // When a new range starts inside the previous, extend previous
-            // Otherwise, add a new range
-            if range.start <= previous.end {
-                if range.end > previous.end {
-                    previous.end = range.end;
-                }
-            } else {
-                fresh_ingredients_merged.push(range)
-            }
+    fn new(fresh_ingredients: RangeSet) -> Result<Self, AocError> {
+        if fresh_ingredients.is_empty() {
+            return Err(AocError::Parse(
+                "there should be fresh ingredients".to_string(),
+            ));
         }
 
-        fresh_ingredients_merged
+        Ok(Self { fresh_ingredients })
     }
 
     fn is_ingredient_fresh(&self, id: IngredientId) -> bool {
-        match self
-            .fresh_ingredients
-            .binary_search_by_key(&id, |r| r.start)
-        {
-            // If Ok (found), the start of a range was hit directly.
-            Ok(_) => true,
-
-            // If Err (not found), pos is either within the range or after it, so check the prior range.
-            Err(0) => false,
-            Err(pos) => self.fresh_ingredients[pos - 1].contains(id),
-        }
+        self.fresh_ingredients.contains(id)
     }
 
     fn count_all_fresh_ingredients(&self) -> usize {
-        self.fresh_ingredients.iter().map(|r| r.len()).sum()
+        self.count_fresh_in(0, IngredientId::MAX)
+    }
+
+    /// Counts the fresh ingredients with ids from `start` to `end`, inclusive.
+    pub fn count_fresh_in(&self, start: IngredientId, end: IngredientId) -> usize {
+        self.fresh_ingredients.count_in(start..=end)
+    }
+
+    /// The `k`th fresh ingredient, counting from 0 at the lowest id. None when there are `k` fresh
+    /// ingredients or fewer.
+    pub fn nth_fresh(&self, k: usize) -> Option<IngredientId> {
+        self.fresh_ingredients.nth(k)
     }
 
     fn count_requested_fresh_ingredients(&self, requested_ingredients: Vec<IngredientId>) -> usize {
@@ -96,95 +82,324 @@ impl IngredientRange {
         id >= self.start && id <= self.end
     }
 
-    fn len(&self) -> usize {
-        self.end - self.start + 1
+    fn ids(&self) -> RangeInclusive<IngredientId> {
+        self.start..=self.end
     }
 }
 
-impl PartialOrd for IngredientRange {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl FromStr for IngredientRange {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AocError::Parse(format!("range should be like 3-5: {s}"));
+
+        let (start, end) = s.split_once("-").ok_or_else(invalid)?;
+        let start: IngredientId = start.parse().map_err(|_| invalid())?;
+        let end: IngredientId = end.parse().map_err(|_| invalid())?;
+        if start > end {
+            return Err(invalid());
+        }
+
+        Ok(IngredientRange { start, end })
     }
 }
 
-impl Ord for IngredientRange {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.start.cmp(&other.start)
-    }
+/// The `k`th fresh ingredient in the input's ranges, counting from 0 at the lowest id.
+pub fn nth_fresh(input: &str, k: usize) -> Result<Option<IngredientId>, AocError> {
+    let (cims, _) = parse(input)?;
+    Ok(cims.nth_fresh(k))
 }
 
-impl FromStr for IngredientRange {
-    type Err = ParseIntError;
+pub fn solve(input: &str) -> Result<Answer, AocError> {
+    let (cims, requested_ingredients) = parse(input)?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once("-").expect("all ranges should have a hyphen");
-        let start: IngredientId = start.parse()?;
-        let end: IngredientId = end.parse()?;
+    Ok(Answer {
+        part1: cims.count_requested_fresh_ingredients(requested_ingredients),
+        part2: Some(cims.count_all_fresh_ingredients()),
+    })
+}
 
-        Ok(IngredientRange { start, end })
+/// Solves the input one line at a time as it's read, rather than holding all of it, for generated
+/// inputs too big to fit in memory. Only the merged ranges are kept, and each range is quick to
+/// merge in when they come sorted. Gives the same answer as `solve`.
+pub fn solve_reader(reader: impl BufRead) -> Result<Answer, AocError> {
+    let mut lines = reader.lines().enumerate().map(|(idx, line)| {
+        line.map(|line| (idx, line))
+            .map_err(|e| AocError::Other(format!("could not read input: {e}")))
+    });
+
+    let mut fresh_ingredients = RangeSet::new();
+    let mut split = false;
+    for line in lines.by_ref() {
+        let (idx, line) = line?;
+        if line.is_empty() {
+            split = true;
+            break;
+        }
+        fresh_ingredients.insert(parse_range(idx, &line)?.ids());
     }
+    if !split {
+        return Err(missing_split());
+    }
+    let cims = ComplicatedInventoryManagmentSystem::new(fresh_ingredients)?;
+
+    // every range has been read by now, so each ingredient can be checked as it comes
+    let mut fresh_requested = 0;
+    for line in lines {
+        let (idx, line) = line?;
+        if cims.is_ingredient_fresh(parse_ingredient(idx, &line)?) {
+            fresh_requested += 1;
+        }
+    }
+
+    Ok(Answer {
+        part1: fresh_requested,
+        part2: Some(cims.count_all_fresh_ingredients()),
+    })
 }
 
-pub fn solve(input: &str) -> Answer {
-    let (fresh_ingredients, ingredients_to_check) = input.split_once("\n\n").unwrap();
+pub(crate) fn parse(
+    input: &str,
+) -> Result<(ComplicatedInventoryManagmentSystem, Vec<IngredientId>), AocError> {
+    let (fresh_ingredients, ingredients_to_check) =
+        input.split_once("\n\n").ok_or_else(missing_split)?;
 
+    // the ingredients start after the ranges and the blank line
+    let first_idx = fresh_ingredients.lines().count() + 1;
     let requested_ingredients = ingredients_to_check
         .lines()
-        .map(|line| line.parse::<IngredientId>().unwrap())
+        .enumerate()
+        .map(|(idx, line)| parse_ingredient(first_idx + idx, line))
+        .collect::<Result<_, _>>()?;
+
+    let cims = ComplicatedInventoryManagmentSystem::load(fresh_ingredients)?;
+    Ok((cims, requested_ingredients))
+}
+
+/// Parses the range on the 0-based line `idx`, saying which line it was when it's malformed.
+fn parse_range(idx: usize, line: &str) -> Result<IngredientRange, AocError> {
+    line.parse().map_err(|_| {
+        AocError::Parse(format!(
+            "range on line {} should be like 3-5: {line}",
+            idx + 1
+        ))
+    })
+}
+
+/// Parses the ingredient on the 0-based line `idx`, saying which line it was when it's malformed.
+fn parse_ingredient(idx: usize, line: &str) -> Result<IngredientId, AocError> {
+    line.parse().map_err(|_| {
+        AocError::Parse(format!(
+            "ingredient on line {} should be an id: {line}",
+            idx + 1
+        ))
+    })
+}
+
+fn missing_split() -> AocError {
+    AocError::Parse("fresh ranges and ingredients should be split by a blank line".to_string())
+}
+
+/// Generates `size` fresh ranges and `size` ingredients to check. Ids spread out as the size
+/// grows, so plenty of ranges overlap at any size.
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let spread = size.max(1) * 100;
+
+    let ranges: String = (0..size.max(1))
+        .map(|_| {
+            let start = rng.random_range(0..spread);
+            format!("{start}-{}\n", start + rng.random_range(0..spread / 5))
+        })
+        .collect();
+    let ingredients: String = (0..size)
+        .map(|_| format!("{}\n", rng.random_range(0..spread + spread / 5)))
         .collect();
 
-    let cims = ComplicatedInventoryManagmentSystem::load(fresh_ingredients);
-    Answer {
-        part1: cims.count_requested_fresh_ingredients(requested_ingredients),
-        part2: cims.count_all_fresh_ingredients(),
+    format!("{ranges}\n{ingredients}")
+}
+
+/// Solves by checking every ingredient against every range, and every id against every range,
+/// without merging anything. Only fast enough for small inputs, but simple enough to check `solve`
+/// against.
+pub(crate) fn brute_force(input: &str) -> Result<Answer, AocError> {
+    let (_, requested_ingredients) = parse(input)?;
+    let (fresh_ingredients, _) = input.split_once("\n\n").ok_or_else(missing_split)?;
+    let ranges: Vec<IngredientRange> = fresh_ingredients
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_range(idx, line))
+        .collect::<Result<_, _>>()?;
+
+    let is_fresh = |id| ranges.iter().any(|r| r.contains(id));
+    let last_id = ranges.iter().map(|r| r.end).max().unwrap_or_default();
+
+    Ok(Answer {
+        part1: requested_ingredients
+            .into_iter()
+            .filter(|&id| is_fresh(id))
+            .count(),
+        part2: Some((0..=last_id).filter(|&id| is_fresh(id)).count()),
+    })
+}
+
+pub struct Problem5;
+
+impl Solver for Problem5 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 5,
+            title: "Cafeteria",
+            tags: &["ranges"],
+            has_part2: true,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let (cims, requested_ingredients) = parse(input)?;
+
+        ctx.phase("count fresh");
+        Ok(cims
+            .count_requested_fresh_ingredients(requested_ingredients)
+            .into())
+    }
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        ctx.phase("parse");
+        let (cims, _) = parse(input)?;
+
+        ctx.phase("count fresh");
+        Ok(cims.count_all_fresh_ingredients().into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use proptest::{collection::vec, prelude::*};
+
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-3-5
-10-14
-16-20
-12-18
-
-1
-5
-8
-11
-17
-32"#;
-
-        let result = solve(input.trim());
+        let input = examples::read("5");
+
+        let result = solve(&input).unwrap();
         assert_eq!(result.part1, 3);
-        assert_eq!(result.part2, 14);
+        assert_eq!(result.part2, Some(14));
     }
 
     #[test]
-    fn overlapping_ranges_inner_first() {
-        let cims = ComplicatedInventoryManagmentSystem::load("3-4\n2-5");
+    fn solve_while_reading() {
+        let example = examples::read("5");
+        let generated = crate::generate::generate(5, 200, 0).unwrap();
+
+        for input in [example.as_str(), &generated] {
+            let streamed = solve_reader(input.as_bytes()).unwrap();
+            let expected = solve(input).unwrap();
+            assert_eq!(streamed.part1, expected.part1);
+            assert_eq!(streamed.part2, expected.part2);
+        }
 
-        assert!(!cims.is_ingredient_fresh(1));
-        assert!(cims.is_ingredient_fresh(2));
-        assert!(cims.is_ingredient_fresh(3));
-        assert!(cims.is_ingredient_fresh(4));
-        assert!(cims.is_ingredient_fresh(5));
-        assert!(!cims.is_ingredient_fresh(6));
+        assert!(solve_reader("3-5\n10-14\n".as_bytes()).is_err());
+        assert!(solve_reader("\n5\n".as_bytes()).is_err());
+        assert_eq!(
+            solve_reader("3-5\n\nfive\n".as_bytes()).map(|answer| answer.part1),
+            Err(AocError::Parse(
+                "ingredient on line 3 should be an id: five".to_string()
+            ))
+        );
     }
 
     #[test]
-    fn overlapping_ranges_outer_first() {
-        let cims = ComplicatedInventoryManagmentSystem::load("2-5\n3-4");
-
-        assert!(!cims.is_ingredient_fresh(1));
-        assert!(cims.is_ingredient_fresh(2));
-        assert!(cims.is_ingredient_fresh(3));
-        assert!(cims.is_ingredient_fresh(4));
-        assert!(cims.is_ingredient_fresh(5));
-        assert!(!cims.is_ingredient_fresh(6));
+    fn malformed_input_is_an_error() {
+        let error = |input: &str| match solve(input) {
+            Err(AocError::Parse(message)) => message,
+            Err(other) => panic!("should be a parse error: {other:?}"),
+            Ok(answer) => panic!("should not solve: {:?}", answer.part1),
+        };
+
+        assert_eq!(
+            error("3-5\n10-14\n"),
+            "fresh ranges and ingredients should be split by a blank line"
+        );
+        assert_eq!(
+            error("3-5\n5\n\n1"),
+            "range on line 2 should be like 3-5: 5"
+        );
+        assert_eq!(
+            error("3-5\n7-2\n\n1"),
+            "range on line 2 should be like 3-5: 7-2"
+        );
+        assert_eq!(
+            error("3-5\nx-7\n\n1"),
+            "range on line 2 should be like 3-5: x-7"
+        );
+        assert_eq!(error("\n\n1"), "there should be fresh ingredients");
+        assert_eq!(
+            error("3-5\n\n1\nx"),
+            "ingredient on line 4 should be an id: x"
+        );
+
+        assert_eq!(
+            nth_fresh("3-5\n7-2\n\n1", 0),
+            Err(AocError::Parse(
+                "range on line 2 should be like 3-5: 7-2".to_string()
+            ))
+        );
+        assert!(brute_force("3-5\n10-14\n").is_err());
+
+        // reading line by line finds the same lines
+        assert_eq!(
+            solve_reader("3-5\n7-2\n\n1\n".as_bytes()).map(|answer| answer.part1),
+            Err(AocError::Parse(
+                "range on line 2 should be like 3-5: 7-2".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn count_fresh_within_a_span() {
+        let (cims, _) = parse(&examples::read("5")).unwrap();
+
+        // the example's fresh ids are 3 to 5 and 10 to 20
+        assert_eq!(cims.count_fresh_in(0, 100), 14);
+        assert_eq!(cims.count_fresh_in(4, 12), 5);
+        assert_eq!(cims.count_fresh_in(6, 9), 0);
+        assert_eq!(cims.count_fresh_in(20, 20), 1);
+        assert_eq!(cims.count_fresh_in(9, 3), 0);
+    }
+
+    #[test]
+    fn nth_fresh_ingredient() {
+        let (cims, _) = parse(&examples::read("5")).unwrap();
+
+        // the example's fresh ids are 3 to 5 and 10 to 20
+        assert_eq!(cims.nth_fresh(0), Some(3));
+        assert_eq!(cims.nth_fresh(2), Some(5));
+        assert_eq!(cims.nth_fresh(3), Some(10));
+        assert_eq!(cims.nth_fresh(13), Some(20));
+        assert_eq!(cims.nth_fresh(14), None);
+        assert_eq!(nth_fresh(&examples::read("5"), 4), Ok(Some(11)));
+    }
+
+    proptest! {
+        #[test]
+        fn merging_keeps_the_same_ids(ranges in vec((0..200_usize, 0..20_usize), 1..20)) {
+            let fresh: HashSet<IngredientId> =
+                ranges.iter().flat_map(|&(start, len)| start..=start + len).collect();
+            let ranges: Vec<String> = ranges
+                .iter()
+                .map(|(start, len)| format!("{start}-{}", start + len))
+                .collect();
+            let cims = ComplicatedInventoryManagmentSystem::load(&ranges.join("\n")).unwrap();
+
+            prop_assert_eq!(cims.count_all_fresh_ingredients(), fresh.len());
+            for id in 0..250 {
+                prop_assert_eq!(cims.is_ingredient_fresh(id), fresh.contains(&id), "id {}", id);
+            }
+        }
     }
 }
@@ -1,206 +1,312 @@
-use crate::shared::Answer;
-
-#[derive(Debug)]
-struct CephalopodMathProblem {
-    lines: Vec<Vec<char>>,
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, BigUint, Grid, parse},
+    solver::{AnswerValue, Metadata, Solver},
+};
+
+/// What a problem does with its numbers, written at the start of its bottom row.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operation {
+    Add,
+    Multiply,
+    Subtract,
+    Divide,
+    Min,
+    Max,
 }
 
-impl CephalopodMathProblem {
-    fn solve(&self) -> usize {
-        let mut numbers: Vec<usize> = vec![];
-        for x in 0..self.lines[0].len() {
-            // concatenate these to get a number
-            let mut number_string = String::new();
-            for y in 0..(self.lines.len() - 1) {
-                number_string.push(self.lines[y][x])
-            }
-
-            numbers.push(number_string.trim().parse().unwrap())
+impl Operation {
+    /// Reads an operation: `+`, `*`, `-`, `/`, `<` for the smallest number, or `>` for the
+    /// biggest.
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Operation::Add),
+            '*' => Some(Operation::Multiply),
+            '-' => Some(Operation::Subtract),
+            '/' => Some(Operation::Divide),
+            '<' => Some(Operation::Min),
+            '>' => Some(Operation::Max),
+            _ => None,
         }
+    }
 
-        let operation = self.lines.last().unwrap().first().unwrap();
-        match operation {
-            '+' => numbers.iter().sum(),
-            '*' => numbers.iter().product(),
-            _ => panic!("unexpected operation: {operation}"),
+    /// Works through the numbers from the first, so subtracting takes each later number away from
+    /// it, and dividing divides by each in turn, truncating like integer division does. Neither
+    /// can end up bigger than the first number, so only adding and multiplying need big numbers.
+    fn apply(self, numbers: &[usize]) -> Result<BigUint, AocError> {
+        let (&first, rest) = numbers.split_first().expect("problems should have numbers");
+        let big = numbers.iter().map(|&n| BigUint::from(n));
+
+        match self {
+            Operation::Add => Ok(big.sum()),
+            Operation::Multiply => Ok(big.product()),
+            Operation::Subtract => rest
+                .iter()
+                .try_fold(first, |left, &n| left.checked_sub(n))
+                .map(BigUint::from)
+                .ok_or_else(|| {
+                    AocError::Unsolvable(format!("subtracting goes below zero: {numbers:?}"))
+                }),
+            Operation::Divide => rest
+                .iter()
+                .try_fold(first, |left, &n| left.checked_div(n))
+                .map(BigUint::from)
+                .ok_or_else(|| AocError::Unsolvable(format!("dividing by zero: {numbers:?}"))),
+            Operation::Min => Ok(BigUint::from(
+                rest.iter().fold(first, |left, &n| left.min(n)),
+            )),
+            Operation::Max => Ok(BigUint::from(
+                rest.iter().fold(first, |left, &n| left.max(n)),
+            )),
         }
     }
 }
 
-struct CephalopodMathScroll {
-    all_problem_chars: Vec<Vec<char>>,
+/// Which way the digits of each number run.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum DigitOrder {
+    /// Most significant digit first, so left to right across a row, and top to bottom down a
+    /// column.
+    #[default]
+    Forward,
+
+    /// Most significant digit last, so right to left across a row, and bottom to top up a column.
+    Reversed,
 }
 
-impl CephalopodMathScroll {
-    fn new(all_problem_text: &str) -> Self {
-        Self {
-            all_problem_chars: all_problem_text
-                .trim()
-                .lines()
-                .map(|l| l.chars().collect())
-                .collect(),
+impl DigitOrder {
+    /// Reads the digits as a number, in this order.
+    fn read(self, digits: impl DoubleEndedIterator<Item = char>) -> String {
+        match self {
+            DigitOrder::Forward => digits.collect(),
+            DigitOrder::Reversed => digits.rev().collect(),
         }
     }
-
-    fn problems(self) -> ProblemIterator {
-        ProblemIterator::new(self)
-    }
 }
 
-struct ProblemIterator {
-    scroll: CephalopodMathScroll,
-    cur_problem_index: Option<usize>,
+/// One problem from the scroll: the block of columns it takes up, with its operation in the
+/// bottom row. Part 1 reads its numbers a row at a time, and part 2 a column at a time.
+#[derive(Debug)]
+pub(crate) struct CephalopodMathProblem {
+    block: Grid<char>,
+    digit_order: DigitOrder,
 }
 
-impl ProblemIterator {
-    fn new(scroll: CephalopodMathScroll) -> Self {
-        Self {
-            scroll,
-            cur_problem_index: Some(0),
-        }
+impl CephalopodMathProblem {
+    /// Reads the operation, and the numbers both ways, checking each of them can be read.
+    fn read(&self) -> Result<ReadProblem, AocError> {
+        Ok(ReadProblem {
+            operation: self.operation()?,
+            row_numbers: self.row_numbers()?,
+            column_numbers: self.column_numbers()?,
+        })
     }
 
-    fn find_next_problem_index(&self) -> Option<usize> {
-        let cur_problem_index = self.cur_problem_index?;
-
-        // finds the next problem, by looking for the math operation in the last line
-        self.scroll
-            .all_problem_chars
-            .last()
-            .unwrap()
-            .iter()
-            .skip(cur_problem_index + 1)
-            .position(char::is_ascii_punctuation)
-            .map(|i| i + cur_problem_index + 1)
+    /// The one operation in the bottom row, wherever it's written under the numbers.
+    fn operation(&self) -> Result<Operation, AocError> {
+        let bottom = self.block.rows().last().unwrap();
+        let mut operations = bottom.iter().filter(|&&c| c != ' ');
+
+        match (operations.next(), operations.next()) {
+            (Some(&c), None) => Operation::from_char(c)
+                .ok_or_else(|| AocError::Parse(format!("unexpected operation: {c}"))),
+            _ => Err(AocError::Parse(format!(
+                "each problem should have one operation: {}",
+                bottom.iter().collect::<String>()
+            ))),
+        }
     }
-}
-
-impl Iterator for ProblemIterator {
-    type Item = CephalopodMathProblem;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let curr_idx = self.cur_problem_index?;
-        let next_idx = self.find_next_problem_index();
+    /// How many rows of numbers there are, above the operation.
+    fn number_height(&self) -> usize {
+        self.block.height() - 1
+    }
 
-        // pulls out the text of a single problem from the scroll
-        let problem_text = self
-            .scroll
-            .all_problem_chars
-            .iter()
-            .map(|line| {
-                if let Some(next_idx) = next_idx {
-                    &line[curr_idx..(next_idx - 1)]
-                } else {
-                    &line[curr_idx..]
-                }
+    /// Reads the numbers left to right, one per row.
+    fn row_numbers(&self) -> Result<Vec<usize>, AocError> {
+        self.block
+            .rows()
+            .take(self.number_height())
+            .map(|row| {
+                let number_string = self.digit_order.read(row.iter().copied());
+                number_string.trim().parse().map_err(|_| {
+                    AocError::Parse(format!("row should be a number: {number_string}"))
+                })
             })
-            //TODO: Can I drop this copy?
-            .map(|slice| slice.to_vec())
-            .collect();
-
-        self.cur_problem_index = next_idx;
+            .collect()
+    }
 
-        Some(CephalopodMathProblem {
-            lines: problem_text,
-        })
+    /// Reads the numbers top to bottom, one per column.
+    fn column_numbers(&self) -> Result<Vec<usize>, AocError> {
+        (0..self.block.width())
+            .map(|x| {
+                // concatenate these to get a number
+                let number_string = self
+                    .digit_order
+                    .read((0..self.number_height()).map(|y| self.block[(x, y)]));
+
+                number_string.trim().parse().map_err(|_| {
+                    AocError::Parse(format!("column should be a number: {number_string}"))
+                })
+            })
+            .collect()
     }
 }
 
-struct MathProblem {
-    values: Vec<usize>,
+/// A problem with its numbers already read both ways, so each part only has to work them out.
+pub(crate) struct ReadProblem {
+    operation: Operation,
+    row_numbers: Vec<usize>,
+    column_numbers: Vec<usize>,
 }
 
-impl MathProblem {
-    fn new(value: usize) -> Self {
-        Self {
-            values: vec![value],
-        }
+impl ReadProblem {
+    /// Solves reading the numbers across, the way people write them. Big numbers, since a long
+    /// enough column of products can outgrow `usize`.
+    fn solve_rows(&self) -> Result<BigUint, AocError> {
+        self.operation.apply(&self.row_numbers)
     }
 
-    fn add_value(&mut self, value: usize) {
-        self.values.push(value);
+    /// Solves reading the numbers down, the way cephalopods write them.
+    fn solve_columns(&self) -> Result<BigUint, AocError> {
+        self.operation.apply(&self.column_numbers)
     }
+}
+
+/// The whole scroll, split into its problems wherever a column is blank all the way down.
+pub(crate) struct CephalopodMathScroll {
+    problems: Vec<CephalopodMathProblem>,
+}
+
+impl CephalopodMathScroll {
+    /// Reads the scroll, checking it's rows of digits with a row of operations at the bottom, and
+    /// that each problem has one operation. Each number's digits run in `digit_order`.
+    fn new(all_problem_text: &str, digit_order: DigitOrder) -> Result<Self, AocError> {
+        let problems: Vec<CephalopodMathProblem> = parse::column_blocks(all_problem_text)
+            .into_iter()
+            .map(|block| CephalopodMathProblem { block, digit_order })
+            .collect();
+
+        let Some(first) = problems.first() else {
+            return Err(AocError::Parse("scroll should not be empty".to_string()));
+        };
+        if first.number_height() == 0 {
+            return Err(AocError::Parse(
+                "scroll should have rows of numbers".to_string(),
+            ));
+        }
 
-    fn calculate(&self, operation: &str) -> usize {
-        match operation {
-            "+" => self.values.iter().sum(),
-            "*" => self.values.iter().product(),
-            _ => panic!("unexpected operation: {operation}"),
+        for problem in &problems {
+            let numbers = problem.block.rows().take(problem.number_height());
+            if numbers.flatten().any(|c| !c.is_ascii_digit() && *c != ' ') {
+                return Err(AocError::Parse("rows should only have digits".to_string()));
+            }
+            problem.operation()?;
         }
+
+        Ok(Self { problems })
     }
 }
 
-pub fn part1(input: &str) -> usize {
-    let lines: Vec<_> = input.lines().collect();
+/// Parses the scroll into its problems, reading each of them both ways.
+pub(crate) fn parse(input: &str) -> Result<Vec<ReadProblem>, AocError> {
+    parse_with(input, DigitOrder::default())
+}
 
-    // first line creates the math problems with one value
-    let mut problems: Vec<_> = lines[0]
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .map(MathProblem::new)
-        .collect();
+fn parse_with(input: &str, digit_order: DigitOrder) -> Result<Vec<ReadProblem>, AocError> {
+    CephalopodMathScroll::new(input, digit_order)?
+        .problems
+        .iter()
+        .map(CephalopodMathProblem::read)
+        .collect()
+}
 
-    // middle lines add more values
-    for line in &lines[1..(lines.len() - 1)] {
-        line.split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .enumerate()
-            .for_each(|(idx, value)| {
-                problems[idx].add_value(value);
-            });
-    }
+pub fn part1(input: &str) -> Result<BigUint, AocError> {
+    let problems = parse(input)?;
+    problems.iter().map(ReadProblem::solve_rows).sum()
+}
+
+pub fn part2(input: &str) -> Result<BigUint, AocError> {
+    let problems = parse(input)?;
+    problems.iter().map(ReadProblem::solve_columns).sum()
+}
 
-    // last line performs calculations
-    lines
-        .last()
-        .unwrap()
-        .split_whitespace()
-        .enumerate()
-        .map(|(idx, op)| problems[idx].calculate(op))
-        .sum()
+pub fn solve(input: &str) -> Result<Answer<BigUint>, AocError> {
+    solve_with(input, DigitOrder::default())
 }
 
-fn part2(input: &str) -> usize {
-    // Create a 2D array of chars
-    let scroll = CephalopodMathScroll::new(input);
-    scroll.problems().map(|p| p.solve()).sum()
+/// Solves with the numbers' digits running in `digit_order`, for scrolls written the other way.
+pub fn solve_with(input: &str, digit_order: DigitOrder) -> Result<Answer<BigUint>, AocError> {
+    let problems = parse_with(input, digit_order)?;
+
+    Ok(Answer {
+        part1: problems
+            .iter()
+            .map(ReadProblem::solve_rows)
+            .sum::<Result<_, _>>()?,
+        part2: Some(
+            problems
+                .iter()
+                .map(ReadProblem::solve_columns)
+                .sum::<Result<_, _>>()?,
+        ),
+    })
 }
-pub fn solve(input: &str) -> Answer {
-    //TODO: I think these can merge once I parse them into problems by string, i can have two
-    //different calculate functions, one for each part.
-    Answer {
-        part1: part1(input),
-        part2: part2(input),
+
+pub struct Problem6;
+
+impl Solver for Problem6 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 6,
+            title: "Trash Compactor",
+            tags: &["parsing"],
+            has_part2: true,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(part1(input)?.into())
+    }
+
+    fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(part2(input)?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +"#;
+        let input = examples::read("6");
 
-        let result = solve(input.trim());
-        assert_eq!(result.part1, 4277556);
-        assert_eq!(result.part2, 3263827);
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, BigUint::from(4277556_u64));
+        assert_eq!(result.part2, Some(BigUint::from(3263827_u64)));
+    }
+
+    #[test]
+    fn products_can_outgrow_usize() {
+        let input = "99999999999\n99999999999\n*";
+
+        let result = solve(input).unwrap();
+        assert_eq!(result.part1.to_string(), "9999999999800000000001");
+        assert_eq!(
+            Problem6.solve_part1(input, &SolveCtx::default()),
+            Ok(AnswerValue::Big(result.part1))
+        );
     }
 
     #[test]
     fn find_next_problem_index() {
-        let input = r#"
-123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +"#;
+        let input = examples::read("6");
 
-        let scroll = CephalopodMathScroll::new(input);
-        let mut problems = scroll.problems();
+        let scroll = CephalopodMathScroll::new(&input, DigitOrder::Forward).unwrap();
+        let mut problems = scroll.problems.iter();
 
         assert!(problems.next().is_some());
         assert!(problems.next().is_some());
@@ -211,13 +317,118 @@ mod tests {
 
     #[test]
     fn solve_a_math_problem() {
-        let input = r#"
-123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +"#;
-
-        let problem = CephalopodMathScroll::new(input).problems().next().unwrap();
-        assert_eq!(problem.solve(), 8544);
+        let input = examples::read("6");
+
+        let problem = &parse(&input).unwrap()[0];
+        assert_eq!(problem.row_numbers, [123, 45, 6]);
+        assert_eq!(problem.column_numbers, [1, 24, 356]);
+        assert_eq!(problem.solve_rows(), Ok(BigUint::from(123_u64 * 45 * 6)));
+        assert_eq!(problem.solve_columns(), Ok(BigUint::from(8544_u64)));
+    }
+
+    #[test]
+    fn problems_are_split_by_blank_columns() {
+        // wide numbers, several blank columns between problems, a first row starting with spaces,
+        // and rows with their trailing spaces stripped
+        let input = " 1234    7\n  56     89\n +       *\n";
+        let problems = CephalopodMathScroll::new(input, DigitOrder::Forward)
+            .unwrap()
+            .problems;
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].row_numbers(), Ok(vec![1234, 56]));
+        assert_eq!(problems[0].column_numbers(), Ok(vec![1, 25, 36, 4]));
+        assert_eq!(problems[1].row_numbers(), Ok(vec![7, 89]));
+        assert_eq!(problems[1].column_numbers(), Ok(vec![78, 9]));
+
+        let result = solve(input).unwrap();
+        assert_eq!(result.part1, BigUint::from(1234_u64 + 56 + 7 * 89));
+        assert_eq!(
+            result.part2,
+            Some(BigUint::from(1_u64 + 25 + 36 + 4 + 78 * 9))
+        );
+
+        // a problem without an operation under it
+        assert!(CephalopodMathScroll::new("12 34\n+   ", DigitOrder::Forward).is_err());
+        assert!(CephalopodMathScroll::new("+", DigitOrder::Forward).is_err());
+    }
+
+    #[test]
+    fn reading_digits_either_way() {
+        let input = "93 7\n21 80\n+  *";
+        let numbers = |digit_order| {
+            let scroll = CephalopodMathScroll::new(input, digit_order).unwrap();
+            let rows: Vec<_> = scroll.problems.iter().map(|p| p.row_numbers()).collect();
+            let columns: Vec<_> = scroll.problems.iter().map(|p| p.column_numbers()).collect();
+            (rows, columns)
+        };
+
+        // across then down, most significant first
+        assert_eq!(
+            numbers(DigitOrder::Forward),
+            (
+                vec![Ok(vec![93, 21]), Ok(vec![7, 80])],
+                vec![Ok(vec![92, 31]), Ok(vec![78, 0])],
+            )
+        );
+
+        // the same text, read right to left across, and bottom to top down
+        assert_eq!(
+            numbers(DigitOrder::Reversed),
+            (
+                vec![Ok(vec![39, 12]), Ok(vec![7, 8])],
+                vec![Ok(vec![29, 13]), Ok(vec![87, 0])],
+            )
+        );
+
+        let result = solve_with(input, DigitOrder::Reversed).unwrap();
+        assert_eq!(result.part1, BigUint::from(39_u64 + 12 + 7 * 8));
+        assert_eq!(result.part2, Some(BigUint::from(29_u64 + 13)));
+    }
+
+    #[test]
+    fn every_operation() {
+        // read across, the numbers are 93 and 21, and read down, they're 92 and 31
+        let solved = |operation: char| {
+            let result = solve(&format!("93\n21\n{operation} ")).unwrap();
+            (result.part1, result.part2.unwrap())
+        };
+        let big = |n: u64| BigUint::from(n);
+
+        assert_eq!(solved('+'), (big(114), big(123)));
+        assert_eq!(solved('*'), (big(1953), big(2852)));
+        assert_eq!(solved('-'), (big(72), big(61)));
+        assert_eq!(solved('/'), (big(4), big(2)));
+        assert_eq!(solved('<'), (big(21), big(31)));
+        assert_eq!(solved('>'), (big(93), big(92)));
+
+        assert!(CephalopodMathScroll::new("93\n21\n% ", DigitOrder::Forward).is_err());
+        assert!(CephalopodMathScroll::new("93\n21\n+-", DigitOrder::Forward).is_err());
+    }
+
+    #[test]
+    fn subtracting_and_dividing_work_from_the_first_number() {
+        assert_eq!(
+            Operation::Subtract.apply(&[20, 5, 3]),
+            Ok(BigUint::from(12_u64))
+        );
+        assert_eq!(
+            Operation::Divide.apply(&[100, 7, 2]),
+            Ok(BigUint::from(7_u64))
+        );
+        assert_eq!(Operation::Divide.apply(&[5, 9]), Ok(BigUint::default()));
+        assert_eq!(Operation::Min.apply(&[5]), Ok(BigUint::from(5_u64)));
+
+        assert!(matches!(
+            Operation::Subtract.apply(&[5, 3, 3]),
+            Err(AocError::Unsolvable(_))
+        ));
+        assert!(matches!(
+            Operation::Divide.apply(&[5, 0]),
+            Err(AocError::Unsolvable(_))
+        ));
+
+        // the scroll parses fine, but its problem has no answer
+        assert!(matches!(solve("5\n3\n3\n-"), Err(AocError::Unsolvable(_))));
     }
 }
@@ -1,41 +1,127 @@
-use crate::shared::Answer;
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, BigUint, Grid},
+    solver::{AnswerValue, Metadata, Solver},
+};
+
+/// How many timelines reach each column. Counts start as u128s, which are quick, and only move to
+/// big numbers once one would overflow, since each splitter roughly doubles them.
+enum Timelines {
+    Small(Vec<u128>),
+    Big(Vec<BigUint>),
+}
+
+impl Timelines {
+    fn len(&self) -> usize {
+        match self {
+            Timelines::Small(counts) => counts.len(),
+            Timelines::Big(counts) => counts.len(),
+        }
+    }
+
+    /// Starts a single timeline in the column.
+    fn start(&mut self, idx: usize) {
+        match self {
+            Timelines::Small(counts) => counts[idx] = 1,
+            Timelines::Big(counts) => counts[idx] = BigUint::from(1_u64),
+        }
+    }
+
+    /// Splits the timelines in the column between the columns either side, dropping any that
+    /// would leave the manifold. Returns whether there were any timelines to split.
+    fn split(&mut self, idx: usize) -> bool {
+        let sides = [
+            idx.checked_sub(1),
+            Some(idx + 1).filter(|&right| right < self.len()),
+        ];
+
+        match self {
+            Timelines::Small(counts) => {
+                let timelines = counts[idx];
+                if timelines == 0 {
+                    return false;
+                }
+
+                let fits = sides
+                    .iter()
+                    .flatten()
+                    .all(|&side| counts[side].checked_add(timelines).is_some());
+                if !fits {
+                    self.grow();
+                    return self.split(idx);
+                }
+
+                for side in sides.into_iter().flatten() {
+                    counts[side] += timelines;
+                }
+                counts[idx] = 0;
+            }
+            Timelines::Big(counts) => {
+                if counts[idx].is_zero() {
+                    return false;
+                }
+
+                let timelines = std::mem::take(&mut counts[idx]);
+                for side in sides.into_iter().flatten() {
+                    counts[side] += &timelines;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Moves every count to big numbers.
+    fn grow(&mut self) {
+        if let Timelines::Small(counts) = self {
+            let counts = counts.iter().map(|&count| BigUint::from(count)).collect();
+            *self = Timelines::Big(counts);
+        }
+    }
+
+    fn total(&self) -> BigUint {
+        match self {
+            Timelines::Small(counts) => counts.iter().map(|&count| BigUint::from(count)).sum(),
+            Timelines::Big(counts) => counts.iter().cloned().sum(),
+        }
+    }
+}
 
 struct TachyonParticleAnalyzer {
-    possible_timelines: Vec<usize>,
+    possible_timelines: Timelines,
     splits: usize,
 }
 
 impl TachyonParticleAnalyzer {
     fn new() -> Self {
         Self {
-            possible_timelines: vec![],
+            possible_timelines: Timelines::Small(vec![]),
             splits: 0,
         }
     }
 
-    /// Analyzes a single row of tachyon particles, tabulating splits and possible timelines.
-    fn analyze(&mut self, row: &str) {
-        if self.possible_timelines.is_empty() {
-            self.possible_timelines.resize(row.len(), 0);
+    /// Analyzes a single row of tachyon particles, tabulating splits and possible timelines. A
+    /// splitter on the edge of the manifold still splits, but the beam which leaves the manifold
+    /// is discarded, along with its timelines.
+    fn analyze(&mut self, row: &[char]) {
+        if self.possible_timelines.len() == 0 {
+            self.possible_timelines = Timelines::Small(vec![0; row.len()]);
         }
 
-        for (idx, c) in row.chars().enumerate() {
+        for (idx, &c) in row.iter().enumerate() {
             match c {
-                'S' => {
-                    self.possible_timelines[idx] = 1;
-                }
+                'S' => self.possible_timelines.start(idx),
                 '^' => {
-                    if self.possible_timelines[idx] > 0 {
-                        // If a particle comes into this splitter, it's possibilities are applied
-                        // to both split beams.
-                        self.possible_timelines[idx - 1] += self.possible_timelines[idx];
-                        self.possible_timelines[idx + 1] += self.possible_timelines[idx];
-                        self.possible_timelines[idx] = 0;
+                    // If a particle comes into this splitter, it's possibilities are applied to
+                    // both split beams.
+                    if self.possible_timelines.split(idx) {
                         self.splits += 1;
                     }
                 }
                 '.' => {}
-                _ => panic!("unexpected character"),
+                // rows come from parse, which only lets these three characters through
+                _ => unreachable!("parse should have rejected {c:?}"),
             };
         }
     }
@@ -44,48 +130,119 @@ impl TachyonParticleAnalyzer {
         self.splits
     }
 
-    fn possibilities(&self) -> usize {
-        self.possible_timelines.iter().sum()
+    fn possibilities(&self) -> BigUint {
+        self.possible_timelines.total()
     }
 }
 
-pub fn solve(input: &str) -> Answer {
+pub fn solve(input: &str) -> Result<Answer<BigUint>, AocError> {
     let mut analyzer = TachyonParticleAnalyzer::new();
 
-    input.lines().for_each(|l| analyzer.analyze(l));
+    let manifold = parse(input)?;
+    manifold.rows().for_each(|row| analyzer.analyze(row));
 
-    Answer {
-        part1: analyzer.splits(),
-        part2: analyzer.possibilities(),
+    Ok(Answer {
+        part1: BigUint::from(analyzer.splits()),
+        part2: Some(analyzer.possibilities()),
+    })
+}
+
+/// Parses the rows of the manifold, checking they're the same width.
+pub(crate) fn parse(input: &str) -> Result<Grid<char>, AocError> {
+    Grid::from_str(input, |c| matches!(c, 'S' | '^' | '.').then_some(c))
+}
+
+pub struct Problem7;
+
+// Splits and timelines are tallied in the same pass over the rows, so each part reuses solve.
+impl Solver for Problem7 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 7,
+            title: "Laboratories",
+            tags: &["grid", "dynamic programming"],
+            has_part2: true,
+        }
+    }
+
+    fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part1.into())
+    }
+
+    fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(solve(input)?.part2.expect("part 2 is always solved").into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    /// A manifold where every beam hits a splitter on every row, so the timelines double each row,
+    /// and it's wide enough that none leave it.
+    fn doubling_manifold(rows: usize) -> String {
+        let width = 2 * rows + 3;
+        let center = rows + 1;
+
+        let mut manifold = format!("{}S{}\n", ".".repeat(center), ".".repeat(center));
+        for row in 0..rows {
+            let line: String = (0..width)
+                .map(|x| {
+                    if (x + row + center).is_multiple_of(2) {
+                        '^'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            manifold.push_str(&line);
+            manifold.push('\n');
+        }
+
+        manifold
+    }
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-.......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-..............."#;
-
-        let result = solve(input.trim());
-        assert_eq!(result.part1, 21);
-        assert_eq!(result.part2, 40);
+        let input = examples::read("7");
+
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, big(21));
+        assert_eq!(result.part2, Some(big(40)));
+    }
+
+    #[test]
+    fn splitters_on_the_edges() {
+        // each splitter sends one beam out of the manifold, leaving one timeline going on
+        let left = solve("S..\n...\n^..\n...").unwrap();
+        assert_eq!((left.part1, left.part2), (big(1), Some(big(1))));
+        let right = solve("..S\n...\n..^\n...").unwrap();
+        assert_eq!((right.part1, right.part2), (big(1), Some(big(1))));
+
+        // split in two, then each half loses its outside beam at an edge
+        let both = solve(".S.\n.^.\n^.^\n...").unwrap();
+        assert_eq!((both.part1, both.part2), (big(3), Some(big(2))));
+
+        // a one column manifold loses both beams
+        let narrow = solve("S\n^").unwrap();
+        assert_eq!((narrow.part1, narrow.part2), (big(1), Some(big(0))));
+    }
+
+    #[test]
+    fn timelines_can_outgrow_usize() {
+        // 2^90 timelines, past a usize but still in a u128
+        let result = solve(&doubling_manifold(90)).unwrap();
+        assert_eq!(result.part1, big(90 * 91 / 2));
+        assert_eq!(result.part2, Some(BigUint::from(1_u128 << 90)));
+
+        // and 2^140, where the busiest columns outgrow a u128 too
+        let result = solve(&doubling_manifold(140)).unwrap();
+        let expected: BigUint = (0..140).map(|_| big(2)).product();
+        assert_eq!(result.part2, Some(expected));
     }
 }
@@ -1,31 +1,13 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
-};
-
-use crate::shared::Answer;
-
-#[derive(Clone)]
-struct Circuit {
-    id: CircuitId,
-    junctions: Vec<JunctionId>,
-}
-
-impl Circuit {
-    fn new(first_junction_id: JunctionId) -> Self {
-        Self {
-            id: CircuitId(first_junction_id.0),
-            junctions: vec![first_junction_id],
-        }
-    }
+use std::{cmp::Ordering, collections::BinaryHeap};
 
-    fn merge(&mut self, other: Circuit) {
-        self.junctions.extend(other.junctions);
-    }
-}
+use rand::Rng;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct CircuitId(usize);
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, DisjointSet, Point3},
+    solver::{AnswerValue, Metadata, Param, Solver},
+};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct JunctionId(usize);
@@ -33,17 +15,12 @@ struct JunctionId(usize);
 #[derive(Eq, PartialEq, Clone)]
 struct Junction {
     id: JunctionId,
-    circuit_id: CircuitId,
-    location: (usize, usize, usize),
+    location: Point3<usize>,
 }
 
 impl Junction {
-    fn new(location: (usize, usize, usize), id: JunctionId, circuit_id: CircuitId) -> Self {
-        Self {
-            location,
-            id,
-            circuit_id,
-        }
+    fn new(location: Point3<usize>, id: JunctionId) -> Self {
+        Self { location, id }
     }
 }
 
@@ -67,12 +44,10 @@ impl StringOfLights {
         }
     }
 
-    fn calculate_length(start: (usize, usize, usize), end: (usize, usize, usize)) -> f64 {
-        let diff_0 = start.0 as i64 - end.0 as i64;
-        let diff_1 = start.1 as i64 - end.1 as i64;
-        let diff_2 = start.2 as i64 - end.2 as i64;
-
-        f64::sqrt(((diff_0).pow(2) + (diff_1).pow(2) + (diff_2).pow(2)) as f64)
+    fn calculate_length(start: Point3<usize>, end: Point3<usize>) -> f64 {
+        // widened, so squaring can't overflow where usize is 32 bits
+        let widen = |c: usize| c as u64;
+        f64::sqrt(start.map(widen).squared_distance(end.map(widen)) as f64)
     }
 }
 
@@ -105,40 +80,52 @@ impl PartialOrd for Length {
 }
 impl Eq for Length {}
 
-pub fn solve(input: &str, connections_to_make: usize) -> Answer {
-    let mut junctions: Vec<Junction> = input
+/// Parses the location of every junction box.
+pub(crate) fn parse(input: &str) -> Result<Vec<Point3<usize>>, AocError> {
+    input
         .lines()
-        .map(|line| line.splitn(3, ',').collect::<Vec<&str>>())
-        .map(|strs| {
-            (
-                strs[0].parse::<usize>().unwrap(),
-                strs[1].parse::<usize>().unwrap(),
-                strs[2].parse::<usize>().unwrap(),
-            )
+        .map(|line| {
+            let invalid = || AocError::Parse(format!("junction should be like 1,2,3: {line}"));
+            let coords: Vec<usize> = line
+                .split(',')
+                .map(|s| s.parse().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()?;
+
+            match coords[..] {
+                [x, y, z] => Ok(Point3::new(x, y, z)),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+/// Generates `size` junction boxes scattered at random, in the same range as the real input.
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let [x, y, z] = [(); 3].map(|_| rng.random_range(0..100_000));
+            format!("{x},{y},{z}\n")
         })
+        .collect()
+}
+
+pub fn solve(input: &str, connections_to_make: usize) -> Result<Answer, AocError> {
+    let junctions: Vec<Junction> = parse(input)?
+        .into_iter()
         .enumerate()
-        .map(|(id, location)| Junction::new(location, JunctionId(id), CircuitId(id)))
+        .map(|(id, location)| Junction::new(location, JunctionId(id)))
         .collect();
 
-    let circuits: Vec<Circuit> = junctions.iter().map(|j| Circuit::new(j.id)).collect();
-
-    let mut id_to_circuit: HashMap<CircuitId, Circuit> =
-        circuits.iter().map(|c| (c.id, c.clone())).collect();
+    // every junction starts out as a circuit of its own
+    let mut circuits = DisjointSet::new(junctions.len());
 
     let mut heap: BinaryHeap<StringOfLights> = BinaryHeap::new();
-    for (idx_a, circuit_a) in circuits.iter().enumerate() {
-        let junction_a_id = &circuit_a.junctions[0];
-        for (idx_b, circuit_b) in circuits.iter().enumerate() {
-            let junction_b_id = &circuit_b.junctions[0];
-            if idx_b > idx_a && junction_a_id != junction_b_id {
-                let junction_a = junctions[junction_a_id.0].clone();
-                let junction_b = junctions[junction_b_id.0].clone();
-                heap.push(StringOfLights::new(&junction_a, &junction_b));
-            }
+    for (idx_a, junction_a) in junctions.iter().enumerate() {
+        for junction_b in &junctions[idx_a + 1..] {
+            heap.push(StringOfLights::new(junction_a, junction_b));
         }
     }
 
-    let mut circuits_remaining = junctions.len();
     let mut connections_made = 0;
     let mut part1_answer = 0;
     let mut part2_answer = 0;
@@ -147,11 +134,7 @@ pub fn solve(input: &str, connections_to_make: usize) -> Answer {
     while let Some(lights) = heap.pop() {
         // when enough connections are made, calculate the answer to part1 (but keep going)
         if connections_made == connections_to_make {
-            let mut sizes = id_to_circuit
-                .values()
-                .map(|c| c.junctions.len())
-                .collect::<Vec<_>>();
-
+            let mut sizes = circuits.set_sizes().collect::<Vec<_>>();
             sizes.sort();
 
             part1_answer = sizes.iter().rev().take(3).product();
@@ -159,82 +142,103 @@ pub fn solve(input: &str, connections_to_make: usize) -> Answer {
 
         connections_made += 1;
 
-        let new_circuit_id;
-        let junctions_to_update;
-
         // merges two circuits together, if they need to be merged
-        {
-            let junction_start = &junctions[lights.start.0];
-            let junction_end = &junctions[lights.end.0];
-
-            if junction_start.circuit_id == junction_end.circuit_id {
-                continue;
-            }
-            // remove the "other" circuit
-            let other_circuit = id_to_circuit
-                .remove(&junction_end.circuit_id)
-                .expect("junction_end.circuit_id should exist")
-                .clone();
-
-            new_circuit_id = junction_start.circuit_id;
-            junctions_to_update = other_circuit.junctions.clone();
-
-            id_to_circuit
-                .entry(junction_start.circuit_id)
-                .and_modify(|c| c.merge(other_circuit));
-        }
-
-        // assign everything in the other circuit to "this" circuit
-        for junction_id in junctions_to_update {
-            junctions[junction_id.0].circuit_id = new_circuit_id;
+        if !circuits.union(lights.start.0, lights.end.0) {
+            continue;
         }
 
         // when only one circuit remains, calculate the answer to part 2
-        circuits_remaining -= 1;
-        if circuits_remaining == 1 {
+        if circuits.num_sets() == 1 {
             let junction_start = &junctions[lights.start.0];
             let junction_end = &junctions[lights.end.0];
-            part2_answer = junction_start.location.0 * junction_end.location.0;
+            part2_answer = junction_start.location.x * junction_end.location.x;
             break;
         }
     }
 
-    Answer {
+    Ok(Answer {
         part1: part1_answer,
-        part2: part2_answer,
+        part2: Some(part2_answer),
+    })
+}
+
+/// How many connections to make before calculating part 1, for the real input.
+const CONNECTIONS_TO_MAKE: usize = 1000;
+
+/// Chooses a different number of connections than `CONNECTIONS_TO_MAKE`, like
+/// `--param connections=10` for the example.
+const CONNECTIONS: Param = Param {
+    name: "connections",
+    about: "connections to make before counting part 1's circuits (1000, or 10 for the example)",
+};
+
+pub struct Problem8;
+
+// Part 2 continues connecting where part 1 left off, so each part reuses solve.
+impl Solver for Problem8 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 8,
+            title: "Playground",
+            tags: &["graph", "geometry"],
+            has_part2: true,
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[CONNECTIONS]
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let connections = ctx.param(CONNECTIONS.name, CONNECTIONS_TO_MAKE)?;
+        Ok(solve(input, connections)?.part1.into())
+    }
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let connections = ctx.param(CONNECTIONS.name, CONNECTIONS_TO_MAKE)?;
+        Ok(solve(input, connections)?
+            .part2
+            .expect("part 2 is always solved")
+            .into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-162,817,812
-57,618,57
-906,360,560
-592,479,940
-352,342,300
-466,668,158
-542,29,236
-431,825,988
-739,650,466
-52,470,668
-216,146,977
-819,987,18
-117,168,530
-805,96,715
-346,949,466
-970,615,88
-941,993,340
-862,61,35
-984,92,344
-425,690,689"#;
-
-        let result = solve(input.trim(), 10);
+        let input = examples::read("8");
+
+        let result = solve(&input, 10).unwrap();
         assert_eq!(result.part1, 40);
-        assert_eq!(result.part2, 25272);
+        assert_eq!(result.part2, Some(25272));
+    }
+
+    #[test]
+    fn connections_from_a_param() {
+        let example = examples::read("8");
+        let with = |value: &str| {
+            let params = [(CONNECTIONS.name.to_string(), value.to_string())];
+            SolveCtx::default().with_params(params.into())
+        };
+
+        assert_eq!(Problem8.solve_part1(&example, &with("10")), Ok(40.into()));
+        assert_eq!(
+            Problem8.solve_part2(&example, &with("10")),
+            Ok(25272.into())
+        );
+
+        // the example only has 190 pairs, so never makes the real input's 1000 connections
+        assert_eq!(
+            Problem8.solve_part1(&example, &SolveCtx::default()),
+            Ok(0.into())
+        );
+        assert!(matches!(
+            Problem8.solve_part1(&example, &with("ten")),
+            Err(AocError::Usage(_))
+        ));
     }
 }
@@ -10,20 +10,17 @@
 use std::collections::HashSet;
 
 use itertools::Itertools;
+use rand::Rng;
+use tracing::debug;
 
-use crate::shared::Answer;
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::{Answer, Direction, Point2, combinatorics, polygon},
+    solver::{AnswerValue, Metadata, Solver},
+};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
+pub(crate) type Point = Point2<usize>;
 
 #[derive(Debug)]
 struct Rect {
@@ -78,14 +75,6 @@ impl Line {
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
 impl From<&Line> for Direction {
     fn from(line: &Line) -> Self {
         if line.start.x == line.end.x {
@@ -102,14 +91,29 @@ impl From<&Line> for Direction {
     }
 }
 
-struct Polygon {
+pub(crate) struct Polygon {
     vertical_borders: Vec<Line>,
     horizontal_borders: Vec<Line>,
     concave_vertices: HashSet<Point>,
 }
 
 impl Polygon {
-    fn new(points: &[Point]) -> Self {
+    /// Builds the polygon with the points as its vertices, in order. Every border has to be
+    /// horizontal or vertical, and turn at each vertex.
+    fn new(points: &[Point]) -> Result<Self, AocError> {
+        if points.is_empty() {
+            return Err(AocError::Parse("polygon should have points".to_string()));
+        }
+        let looped = points.iter().chain(points.first());
+        if let Some((start, end)) = looped
+            .tuple_windows()
+            .find(|(start, end)| (start.x == end.x) == (start.y == end.y))
+        {
+            return Err(AocError::Parse(format!(
+                "border should be horizontal or vertical: {start:?} -> {end:?}"
+            )));
+        }
+
         let borders = points
             .iter()
             .tuple_windows()
@@ -145,79 +149,234 @@ impl Polygon {
                 let vertex = first.end;
                 (vertex, first.direction, second.direction)
             })
-            .filter_map(
-                |(vertex, first_dir, second_dir)| match (first_dir, second_dir) {
-                    (Direction::Right, Direction::Down) => None,
-                    (Direction::Right, Direction::Up) => Some(vertex),
-                    (Direction::Down, Direction::Left) => None,
-                    (Direction::Down, Direction::Right) => Some(vertex),
-
-                    (Direction::Left, Direction::Down) => Some(vertex),
-                    (Direction::Left, Direction::Up) => None,
-                    (Direction::Up, Direction::Left) => Some(vertex),
-                    (Direction::Up, Direction::Right) => None,
-                    _ => panic!("Impossible turn encountered: {first_dir:?} -> {second_dir:?}"),
-                },
-            )
-            .collect::<HashSet<_>>();
-        Self {
+            .filter_map(|(vertex, first_dir, second_dir)| {
+                // the points go clockwise, so turning right is convex and turning left is concave
+                if second_dir == first_dir.turn_right() {
+                    None
+                } else if second_dir == first_dir.turn_left() {
+                    Some(Ok(vertex))
+                } else {
+                    Some(Err(AocError::Parse(format!(
+                        "Impossible turn encountered: {first_dir:?} -> {second_dir:?}"
+                    ))))
+                }
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+        Ok(Self {
             vertical_borders,
             horizontal_borders,
             concave_vertices,
-        }
+        })
     }
 }
 
-pub fn solve(input: &str) -> Answer {
-    let points = build_points(input);
+pub fn solve(input: &str, ctx: &SolveCtx) -> Result<Answer, AocError> {
+    let all_rects = build_sorted_rects(input, ctx)?;
+    let polygon = Polygon::new(&build_points(input)?)?;
+
+    Ok(Answer {
+        part1: largest_rect_area(&all_rects),
+        part2: Some(largest_in_bounds_rect_area(&all_rects, &polygon, ctx)),
+    })
+}
+
+/// Builds every rectangle with corners on two points, largest first.
+fn build_sorted_rects(input: &str, ctx: &SolveCtx) -> Result<Vec<Rect>, AocError> {
+    ctx.phase("parse");
+    let points = build_points(input)?;
+
+    ctx.phase("build rects");
     let mut all_rects = build_rects(&points);
-    let polygon = Polygon::new(&points);
+    debug!(
+        points = points.len(),
+        rects = all_rects.len(),
+        "built rects"
+    );
 
-    all_rects.sort_by(|a, b| b.area.cmp(&a.area));
+    ctx.phase("sort rects");
+    all_rects.sort_by_key(|r| std::cmp::Reverse(r.area));
+
+    Ok(all_rects)
+}
 
-    let max_rect_area = all_rects.iter().map(|r| r.area).next().unwrap();
+fn largest_rect_area(sorted_rects: &[Rect]) -> usize {
+    sorted_rects.iter().map(|r| r.area).next().unwrap()
+}
 
+fn largest_in_bounds_rect_area(sorted_rects: &[Rect], polygon: &Polygon, ctx: &SolveCtx) -> usize {
     // Processing in sorted order, so the first rectangle to pass the filter
     // will be the largest that fits.
-    let max_in_bound_rect_area = all_rects
+    ctx.phase("search rects");
+    sorted_rects
         .iter()
-        // .enumerate()
-        // .inspect(|(idx, rect)| {
-        //     println!(
-        //         "processing rect: {} of {} (area = {}) {rect:?}",
-        //         idx,
-        //         all_rects.len(),
-        //         rect.area
-        //     )
-        // })
-        // .skip(47694)
-        // .map(|(_, rect)| rect)
-        .filter(|r| rect_in_bounds(r, &polygon))
+        .enumerate()
+        .inspect(|(idx, _)| ctx.progress(*idx, sorted_rects.len()))
+        .map(|(_, rect)| rect)
+        .filter(|r| rect_in_bounds(r, polygon))
+        .inspect(|r| debug!(?r, "largest rect in bounds"))
         .map(|r| r.area)
         .next()
-        .unwrap();
+        .unwrap()
+}
+
+pub struct Problem9;
+
+impl Solver for Problem9 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            day: 9,
+            title: "Movie Theater",
+            tags: &["geometry"],
+            has_part2: true,
+        }
+    }
 
-    Answer {
-        part1: max_rect_area,
-        part2: max_in_bound_rect_area,
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        Ok(largest_rect_area(&build_sorted_rects(input, ctx)?).into())
+    }
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+        let all_rects = build_sorted_rects(input, ctx)?;
+        let polygon = Polygon::new(&build_points(input)?)?;
+
+        Ok(largest_in_bounds_rect_area(&all_rects, &polygon, ctx).into())
     }
 }
 
 fn build_rects(points: &[Point]) -> Vec<Rect> {
-    points
-        .iter()
-        .combinations(2)
-        .map(|v| Rect::new(v[0], v[1]))
+    combinatorics::pairs(points)
+        .map(|(a, b)| Rect::new(a, b))
         .collect::<Vec<_>>()
 }
 
-fn build_points(input: &str) -> Vec<Point> {
-    input
+/// Parses the red tiles, in order. There have to be at least two, to make a rectangle from.
+fn build_points(input: &str) -> Result<Vec<Point>, AocError> {
+    let points = input
         .lines()
-        .map(|l| l.split_once(",").unwrap())
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .map(|(x, y)| Point::new(x, y))
-        .collect::<Vec<_>>()
+        .map(|l| {
+            let invalid = || AocError::Parse(format!("point should be like 7,1: {l}"));
+            let (x, y) = l.split_once(",").ok_or_else(invalid)?;
+            let (x, y) = (
+                x.parse().map_err(|_| invalid())?,
+                y.parse().map_err(|_| invalid())?,
+            );
+
+            Ok(Point::new(x, y))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if points.len() < 2 {
+        return Err(AocError::Parse(
+            "there should be at least two points".to_string(),
+        ));
+    }
+
+    Ok(points)
+}
+
+/// Parses the points, and the polygon they make.
+pub(crate) fn parse(input: &str) -> Result<(Vec<Point>, Polygon), AocError> {
+    let points = build_points(input)?;
+    let polygon = Polygon::new(&points)?;
+
+    Ok((points, polygon))
+}
+
+/// Generates a random polygon of `size` side-by-side columns, each spanning from a random top to a
+/// random bottom. Every top is above every bottom, so neighboring columns overlap and the polygon
+/// stays in one piece.
+///
+/// Every coordinate is even, so no two borders are closer than 2 tiles apart, like the real input.
+pub(crate) fn generate(size: usize, rng: &mut impl Rng) -> String {
+    const HALF: usize = 25_000;
+
+    let columns = size.max(1);
+    let (mut tops, mut bottoms): (Vec<usize>, Vec<usize>) = (vec![], vec![]);
+    for _ in 0..columns {
+        // neighboring columns need different heights, or their borders would run together
+        let top = loop {
+            let top = rng.random_range(0..HALF) * 2;
+            if tops.last() != Some(&top) {
+                break top;
+            }
+        };
+        let bottom = loop {
+            let bottom = rng.random_range(HALF..2 * HALF) * 2;
+            if bottoms.last() != Some(&bottom) {
+                break bottom;
+            }
+        };
+        tops.push(top);
+        bottoms.push(bottom);
+    }
+
+    let mut xs = vec![0];
+    for _ in 0..columns {
+        xs.push(xs[xs.len() - 1] + rng.random_range(1..=1000) * 2);
+    }
+
+    // clockwise: along the tops to the right, then back along the bottoms
+    let mut points = vec![];
+    for (idx, &top) in tops.iter().enumerate() {
+        points.push((xs[idx], top));
+        points.push((xs[idx + 1], top));
+    }
+    for (idx, &bottom) in bottoms.iter().enumerate().rev() {
+        points.push((xs[idx + 1], bottom));
+        points.push((xs[idx], bottom));
+    }
+
+    points.iter().map(|(x, y)| format!("{x},{y}\n")).collect()
+}
+
+/// Solves by filling in which tiles are inside the polygon, then checking every tile of every
+/// rectangle. Only fast enough for small inputs, but simple enough to check `solve` against.
+///
+/// Tiles strictly between the same borders are all alike, so one tile stands in for each stretch
+/// between neighboring coordinates. That keeps the fill to O(n²) tiles rather than the whole floor.
+pub(crate) fn brute_force(input: &str) -> Result<Answer, AocError> {
+    let points = build_points(input)?;
+    let stand_ins = |coords: Vec<usize>| -> Vec<usize> {
+        let mut coords: Vec<usize> = coords.into_iter().flat_map(|c| [c, c + 1]).collect();
+        coords.sort_unstable();
+        coords.dedup();
+        coords
+    };
+    let xs = stand_ins(points.iter().map(|p| p.x).collect());
+    let ys = stand_ins(points.iter().map(|p| p.y).collect());
+
+    let vertices: Vec<Point2<i64>> = points.iter().map(|p| p.map(|c| c as i64)).collect();
+    let inside: HashSet<(usize, usize)> = xs
+        .iter()
+        .cartesian_product(&ys)
+        .map(|(&x, &y)| (x, y))
+        .filter(|&(x, y)| polygon::contains(&vertices, Point2::new(x as i64, y as i64)))
+        .collect();
+
+    let rects = build_rects(&points);
+    let every_tile_inside = |rect: &Rect| {
+        let xs_in_rect =
+            rect.top_left.x.min(rect.bottom_right.x)..=rect.top_left.x.max(rect.bottom_right.x);
+        let ys_in_rect =
+            rect.top_left.y.min(rect.bottom_right.y)..=rect.top_left.y.max(rect.bottom_right.y);
+
+        xs.iter()
+            .filter(|x| xs_in_rect.contains(x))
+            .cartesian_product(ys.iter().filter(|y| ys_in_rect.contains(y)))
+            .all(|(&x, &y)| inside.contains(&(x, y)))
+    };
+
+    Ok(Answer {
+        part1: rects.iter().map(|r| r.area).max().unwrap_or_default(),
+        part2: Some(
+            rects
+                .iter()
+                .filter(|r| every_tile_inside(r))
+                .map(|r| r.area)
+                .max()
+                .unwrap_or_default(),
+        ),
+    })
 }
 
 fn rect_in_bounds(rect: &Rect, polygon: &Polygon) -> bool {
@@ -329,23 +488,18 @@ fn has_intersections_vertical(line: &Line, polygon: &Polygon) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use proptest::{collection::vec, prelude::*};
+
     use super::*;
+    use crate::examples;
 
     #[test]
     fn solve_basic_input() {
-        let input = r#"
-7,1
-11,1
-11,7
-9,7
-9,5
-2,5
-2,3
-7,3"#;
+        let input = examples::read("9");
 
-        let result = solve(input.trim());
+        let result = solve(&input, &SolveCtx::default()).unwrap();
         assert_eq!(result.part1, 50);
-        assert_eq!(result.part2, 24);
+        assert_eq!(result.part2, Some(24));
     }
 
     #[test]
@@ -365,18 +519,10 @@ mod tests {
         // .........X.X..
         // .........3X2..
         // ..............
-        let input = r#"
-7,1
-11,1
-11,7
-9,7
-9,5
-2,5
-2,3
-7,3"#;
+        let input = examples::read("9");
 
-        let points = build_points(input.trim());
-        let poly = Polygon::new(&points);
+        let points = build_points(&input).unwrap();
+        let poly = Polygon::new(&points).unwrap();
 
         // this is the top line of the polygon, going backwards
         // meaning the rect corner used was "1", and the "0" is implied.
@@ -428,24 +574,16 @@ mod tests {
         // .........X.X..
         // .........3X2..
         // ..............
-        let input = r#"
-7,1
-11,1
-11,7
-9,7
-9,5
-2,5
-2,3
-7,3"#;
+        let input = examples::read("9");
 
-        let points = build_points(input.trim());
+        let points = build_points(&input).unwrap();
         let point_11_1 = &points[1];
         let point_9_7 = &points[3];
         let point_9_5 = &points[4];
         let point_2_5 = &points[5];
         let point_2_3 = &points[6];
         let point_7_3 = &points[7];
-        let poly = Polygon::new(&points);
+        let poly = Polygon::new(&points).unwrap();
 
         assert!(rect_in_bounds(&Rect::new(point_7_3, point_11_1), &poly));
         assert!(rect_in_bounds(&Rect::new(point_9_7, point_9_5), &poly));
@@ -546,8 +684,8 @@ mod tests {
         // ..|....|
         // ..B-A.76
         // ..  9-8
-        let points = build_points(input.trim());
-        let poly = Polygon::new(&points);
+        let points = build_points(input.trim()).unwrap();
+        let poly = Polygon::new(&points).unwrap();
         assert!(rect_in_bounds(&Rect::new(&points[11], &points[0]), &poly));
         assert!(rect_in_bounds(&Rect::new(&points[11], &points[1]), &poly));
         assert!(!rect_in_bounds(&Rect::new(&points[11], &points[2]), &poly));
@@ -755,8 +893,8 @@ mod tests {
         // ....2-3.6-7..
         // ..0-1.4-5.8-9
         // ..B---------A
-        let points = build_points(input.trim());
-        let poly = Polygon::new(&points);
+        let points = build_points(input.trim()).unwrap();
+        let poly = Polygon::new(&points).unwrap();
         assert!(rect_in_bounds(&Rect::new(&points[0], &points[10]), &poly));
         assert!(rect_in_bounds(&Rect::new(&points[1], &points[10]), &poly));
         assert!(rect_in_bounds(&Rect::new(&points[4], &points[10]), &poly));
@@ -776,4 +914,73 @@ mod tests {
 
         assert!(rect_in_bounds(&Rect::new(&points[11], &points[9]), &poly));
     }
+
+    fn vertices(points: &[Point]) -> Vec<Point2<i64>> {
+        points.iter().map(|p| p.map(|c| c as i64)).collect()
+    }
+
+    /// A random rectilinear polygon, shaped like a histogram: columns of varying widths and
+    /// heights standing on a shared floor. Points go clockwise, like the puzzle's.
+    ///
+    /// Opposite borders are always at least two apart, like the puzzle's, since edges are
+    /// imagined on the sides of tiles and borders right next to each other would share one.
+    fn histogram() -> impl Strategy<Value = Vec<Point>> {
+        const FLOOR: usize = 12;
+
+        let column = (2..5_usize, 0..(FLOOR - 1));
+        vec(column, 1..6)
+            .prop_filter("neighboring columns need different heights", |columns| {
+                columns.windows(2).all(|pair| pair[0].1 != pair[1].1)
+            })
+            .prop_map(|columns| {
+                let mut x = 0;
+                let mut points = vec![Point::new(x, FLOOR)];
+                for (width, top) in columns {
+                    points.push(Point::new(x, top));
+                    x += width;
+                    points.push(Point::new(x, top));
+                }
+                points.push(Point::new(x, FLOOR));
+
+                points
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn concave_vertices_balance_convex(points in histogram()) {
+            let polygon = Polygon::new(&points).unwrap();
+
+            // walking all the way around turns four more times one way than the other
+            let convex = points.len() - polygon.concave_vertices.len();
+            prop_assert_eq!(convex - polygon.concave_vertices.len(), 4);
+        }
+
+        #[test]
+        fn rect_in_bounds_matches_checking_every_tile(points in histogram()) {
+            let polygon = Polygon::new(&points).unwrap();
+
+            for rect in build_rects(&points) {
+                let xs = rect.top_left.x.min(rect.bottom_right.x)..=rect.top_left.x.max(rect.bottom_right.x);
+                let ys = rect.top_left.y.min(rect.bottom_right.y)..=rect.top_left.y.max(rect.bottom_right.y);
+                let every_tile_inside = xs
+                    .flat_map(|x| ys.clone().map(move |y| (x, y)))
+                    .all(|(x, y)| polygon::contains(&vertices(&points), Point2::new(x as i64, y as i64)));
+
+                prop_assert_eq!(rect_in_bounds(&rect, &polygon), every_tile_inside, "{:?}", rect);
+            }
+        }
+
+        #[test]
+        fn picks_theorem_counts_every_tile_inside(points in histogram()) {
+            let vertices = vertices(&points);
+            let width = points.iter().map(|p| p.x).max().unwrap() as i64;
+
+            let inside = (0..=width)
+                .flat_map(|x| (0..=12).map(move |y| Point2::new(x, y)))
+                .filter(|&tile| polygon::contains(&vertices, tile))
+                .count();
+            prop_assert_eq!(inside as i64, polygon::tiles_covered(&vertices));
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use pprof::protos::Message;
+
+use aoc::{
+    ctx::SolveCtx,
+    error::AocError,
+    solver::{Part, Solver},
+};
+
+/// Samples per second. Slightly off a round number, so sampling doesn't line up with anything
+/// else that runs on a timer.
+const SAMPLE_FREQUENCY: i32 = 997;
+
+const PROFILE_DIR: &str = "profiles";
+
+/// What came out of profiling a day.
+pub struct Profiled {
+    /// How many times the solver ran while being sampled.
+    pub runs: usize,
+
+    /// A pprof protobuf, for `go tool pprof` and friends.
+    pub pprof_path: PathBuf,
+
+    /// A flamegraph of the same samples, viewable in a browser.
+    pub flamegraph_path: PathBuf,
+}
+
+/// Runs the solver in a loop for `duration` (at least once) while sampling its stacks, then writes
+/// the samples out as a pprof profile and a flamegraph.
+pub fn profile(
+    solver: &dyn Solver,
+    input: &str,
+    part: Part,
+    duration: Duration,
+) -> Result<Profiled, AocError> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLE_FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(|e| AocError::Other(format!("could not start profiler: {e}")))?;
+
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < duration {
+        solver.solve_parts(input, part, &SolveCtx::default())?;
+        runs += 1;
+    }
+
+    let report = guard
+        .report()
+        .build()
+        .map_err(|e| AocError::Other(format!("could not build profile: {e}")))?;
+
+    std::fs::create_dir_all(PROFILE_DIR)
+        .map_err(|e| AocError::Other(format!("could not create {PROFILE_DIR}: {e}")))?;
+    let (pprof_path, flamegraph_path) = paths(solver.day());
+
+    let mut pprof = Vec::new();
+    report
+        .pprof()
+        .map_err(|e| e.to_string())
+        .and_then(|profile| profile.write_to_vec(&mut pprof).map_err(|e| e.to_string()))
+        .map_err(|e| AocError::Other(format!("could not encode profile: {e}")))?;
+    File::create(&pprof_path)
+        .and_then(|mut file| file.write_all(&pprof))
+        .map_err(|e| AocError::Other(format!("could not write {}: {e}", pprof_path.display())))?;
+
+    let flamegraph = File::create(&flamegraph_path).map_err(|e| {
+        AocError::Other(format!(
+            "could not write {}: {e}",
+            flamegraph_path.display()
+        ))
+    })?;
+    report
+        .flamegraph(flamegraph)
+        .map_err(|e| AocError::Other(format!("could not render flamegraph: {e}")))?;
+
+    Ok(Profiled {
+        runs,
+        pprof_path,
+        flamegraph_path,
+    })
+}
+
+/// Where a day's pprof profile and flamegraph are written.
+fn paths(day: u32) -> (PathBuf, PathBuf) {
+    let dir = PathBuf::from(PROFILE_DIR);
+    (
+        dir.join(format!("day{day}.pb")),
+        dir.join(format!("day{day}.svg")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_named_by_day() {
+        let (pprof, flamegraph) = paths(9);
+        assert_eq!(pprof, PathBuf::from("profiles/day9.pb"));
+        assert_eq!(flamegraph, PathBuf::from("profiles/day9.svg"));
+    }
+}
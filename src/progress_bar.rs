@@ -0,0 +1,59 @@
+use std::io::{self, Write};
+
+/// How many characters wide the bar itself is, not counting the label and percentage.
+const WIDTH: usize = 30;
+
+/// Renders a single-line progress bar, like: `search rects [#########.....] 64.2% 642/1000`
+pub fn render(phase: Option<&str>, done: usize, total: usize) -> String {
+    let fraction = if total == 0 {
+        0.0
+    } else {
+        (done as f64 / total as f64).clamp(0.0, 1.0)
+    };
+    let filled = (fraction * WIDTH as f64) as usize;
+
+    let label = phase.map(|p| format!("{p} ")).unwrap_or_default();
+    format!(
+        "{label}[{}{}] {:.1}% {done}/{total}",
+        "#".repeat(filled),
+        ".".repeat(WIDTH - filled),
+        fraction * 100.0
+    )
+}
+
+/// Redraws the bar in place on stderr.
+pub fn draw(line: &str) {
+    eprint!("\r\x1B[K{line}");
+    let _ = io::stderr().flush();
+}
+
+/// Removes the bar, so whatever is printed next starts on a clean line.
+pub fn clear() {
+    eprint!("\r\x1B[K");
+    let _ = io::stderr().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_partial() {
+        assert_eq!(
+            render(Some("search"), 1, 3),
+            "search [##########....................] 33.3% 1/3"
+        );
+    }
+
+    #[test]
+    fn render_bounds() {
+        assert_eq!(
+            render(None, 0, 0),
+            "[..............................] 0.0% 0/0"
+        );
+        assert_eq!(
+            render(None, 5, 5),
+            "[##############################] 100.0% 5/5"
+        );
+    }
+}
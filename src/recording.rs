@@ -0,0 +1,97 @@
+//! Recording the frames drawn with `--visualize` as an asciinema cast, so the animation can be
+//! shared, and replayed with `asciinema play`, without solving again.
+
+use std::path::Path;
+
+use serde_json::json;
+
+/// Frames play back this many seconds apart, since solvers draw them far quicker than anyone
+/// could watch.
+const FRAME_INTERVAL: f64 = 0.1;
+
+/// Every frame a solver drew, in order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Recording {
+    frames: Vec<String>,
+}
+
+impl Recording {
+    pub fn push(&mut self, frame: &str) {
+        self.frames.push(frame.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The recording as an asciinema v2 cast: a header sized to fit the biggest frame, then each
+    /// frame as output which clears the screen before drawing it.
+    pub fn to_cast(&self) -> String {
+        let width = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.lines())
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default();
+        let height = self
+            .frames
+            .iter()
+            .map(|frame| frame.lines().count())
+            .max()
+            .unwrap_or_default();
+
+        let header = json!({ "version": 2, "width": width.max(1), "height": height.max(1) });
+        let mut cast = format!("{header}\n");
+        for (idx, frame) in self.frames.iter().enumerate() {
+            // the recording plays on a raw terminal, which only goes back to the start of the line
+            // when told to
+            let output = format!("\x1b[2J\x1b[H{}", frame.replace('\n', "\r\n"));
+            let event = json!([idx as f64 * FRAME_INTERVAL, "o", output]);
+            cast.push_str(&format!("{event}\n"));
+        }
+
+        cast
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_cast())
+            .map_err(|e| format!("could not write {}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn casts_each_frame() {
+        let mut recording = Recording::default();
+        recording.push("@@@\n@.@");
+        recording.push("@.\n..\n..");
+
+        let cast = recording.to_cast();
+        let lines: Vec<Value> = cast
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+
+        assert_eq!(lines[0], json!({ "version": 2, "width": 3, "height": 3 }));
+        assert_eq!(lines[1], json!([0.0, "o", "\x1b[2J\x1b[H@@@\r\n@.@"]));
+        assert_eq!(lines[2][0], json!(FRAME_INTERVAL));
+    }
+
+    #[test]
+    fn empty_recording_is_only_a_header() {
+        let cast = Recording::default().to_cast();
+        assert_eq!(cast.lines().count(), 1);
+        assert!(cast.starts_with(r#"{"#));
+    }
+}
@@ -0,0 +1,478 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use aoc::{
+    error::AocError,
+    solver::{AnswerValue, Metadata, PartialAnswer},
+};
+
+use crate::expected::Expected;
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const RED: &str = "31";
+const GREEN: &str = "32";
+
+/// How the runner prints results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("format should be text or json: {s}")),
+        }
+    }
+}
+
+/// The outcome of running one day's solver.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub day: u32,
+    pub part1: Option<AnswerValue>,
+    pub part2: Option<AnswerValue>,
+
+    /// Part 2 was asked for, but the puzzle doesn't have one, so it's missing rather than unsolved.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub part2_missing: bool,
+
+    #[serde(rename = "elapsed_ms", serialize_with = "as_millis")]
+    pub elapsed: Duration,
+
+    #[serde(rename = "timings_ms")]
+    pub timings: Timings,
+
+    // the known correct answers, if they're known for the input that was solved
+    #[serde(skip)]
+    pub expected: Expected,
+}
+
+/// Where the time went while solving. Parse time is only known for solvers which report a
+/// "parse" phase, and is the total across both parts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct Timings {
+    #[serde(serialize_with = "optional_as_millis")]
+    pub parse: Option<Duration>,
+
+    #[serde(serialize_with = "optional_as_millis")]
+    pub part1: Option<Duration>,
+
+    #[serde(serialize_with = "optional_as_millis")]
+    pub part2: Option<Duration>,
+}
+
+impl Timings {
+    /// Like: `parse 1.00ms, part1 2.50ms`, leaving out whatever wasn't timed.
+    fn describe(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            ("parse", self.parse),
+            ("part1", self.part1),
+            ("part2", self.part2),
+        ]
+        .into_iter()
+        .filter_map(|(name, duration)| duration.map(|d| format!("{name} {d:.2?}")))
+        .collect();
+
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// How text output is decorated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub color: bool,
+}
+
+impl Style {
+    /// Wraps text in an ANSI escape code, if color is enabled.
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1B[{code}m{text}\x1B[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Report {
+    pub fn new(day: u32, answer: PartialAnswer, elapsed: Duration) -> Self {
+        Self {
+            day,
+            part1: answer.part1,
+            part2: answer.part2,
+            part2_missing: false,
+            elapsed,
+            timings: Timings::default(),
+            expected: Expected::default(),
+        }
+    }
+
+    /// The first wrong answer, if any. Answers which aren't known can't be wrong.
+    pub fn failure(&self) -> Option<AocError> {
+        if self
+            .part1
+            .as_ref()
+            .and_then(|a| self.expected.check_part1(a))
+            == Some(false)
+        {
+            return Some(AocError::WrongPart1);
+        }
+        if self
+            .part2
+            .as_ref()
+            .and_then(|a| self.expected.check_part2(a))
+            == Some(false)
+        {
+            return Some(AocError::WrongPart2);
+        }
+
+        None
+    }
+
+    pub fn render(&self, format: Format, style: Style) -> String {
+        match format {
+            Format::Text => self.render_text(style),
+            Format::Json => serde_json::to_string(self).expect("report should serialize"),
+        }
+    }
+
+    /// Renders just the answers, a line per part, for piping into other commands. A part which
+    /// wasn't solved is an empty line, so part 2 is always on the second line.
+    pub fn render_quiet(&self) -> String {
+        [&self.part1, &self.part2]
+            .map(|answer| answer.as_ref().map(|a| a.to_string()).unwrap_or_default())
+            .join("\n")
+    }
+
+    /// Renders a header, a line per solved part (checked when the answer is known), and timing.
+    fn render_text(&self, style: Style) -> String {
+        let mut lines = vec![style.paint(BOLD, &format!("Day {}", self.day))];
+
+        let parts = [
+            ("part1", &self.part1, &self.expected.part1, false),
+            (
+                "part2",
+                &self.part2,
+                &self.expected.part2,
+                self.part2_missing,
+            ),
+        ];
+        for (name, answer, expected, missing) in parts {
+            let Some(answer) = answer else {
+                // a part which wasn't asked for is left out, but one that doesn't exist says so
+                if missing {
+                    lines.push(style.paint(DIM, &format!("  {name}: none")));
+                }
+                continue;
+            };
+
+            let check = match expected {
+                Some(expected) if expected == answer => format!(" {}", style.paint(GREEN, "✓")),
+                Some(expected) => {
+                    format!(" {} expected{}", style.paint(RED, "✗"), inline(expected))
+                }
+                None => String::new(),
+            };
+            lines.push(format!("  {name}:{}{check}", inline(answer)));
+        }
+
+        let took = match self.timings.describe() {
+            Some(breakdown) => format!("  took: {:.2?} ({breakdown})", self.elapsed),
+            None => format!("  took: {:.2?}", self.elapsed),
+        };
+        lines.push(style.paint(DIM, &took));
+        lines.join("\n")
+    }
+}
+
+/// Renders an answer to follow a label on the same line. Answers spanning several lines, like
+/// letters drawn in ASCII art, start on the next line and are indented beneath the label instead.
+fn inline(answer: &AnswerValue) -> String {
+    let text = answer.to_string();
+    if !text.contains('\n') {
+        return format!(" {text}");
+    }
+
+    text.lines().map(|line| format!("\n    {line}")).collect()
+}
+
+/// Renders the results from running several days, in the order given.
+pub fn render_all(
+    results: &[(u32, Result<Report, AocError>)],
+    format: Format,
+    style: Style,
+) -> String {
+    match format {
+        Format::Text => results
+            .iter()
+            .map(|(day, result)| match result {
+                Ok(report) => report.render_text(style),
+                Err(e) => format!(
+                    "{}\n  {} {e}",
+                    style.paint(BOLD, &format!("Day {day}")),
+                    style.paint(RED, "ERROR:")
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Format::Json => {
+            // rendered one at a time, so each report keeps its field order
+            let entries: Vec<String> = results
+                .iter()
+                .map(|(day, result)| match result {
+                    Ok(report) => report.render(Format::Json, style),
+                    Err(e) => serde_json::json!({ "day": day, "error": e.to_string() }).to_string(),
+                })
+                .collect();
+            format!("[{}]", entries.join(","))
+        }
+    }
+}
+
+/// Renders the catalog of solvers, one row per day.
+pub fn render_catalog(catalog: &[Metadata], format: Format) -> String {
+    if format == Format::Json {
+        return serde_json::to_string(catalog).expect("catalog should serialize");
+    }
+
+    let width = catalog
+        .iter()
+        .map(|m| m.title.len())
+        .chain(["title".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![format!(
+        "{:>3}  {:width$}  {:5}  tags",
+        "day", "title", "part2"
+    )];
+    for metadata in catalog {
+        let line = format!(
+            "{:>3}  {:width$}  {:5}  {}",
+            metadata.day,
+            metadata.title,
+            if metadata.has_part2 { "yes" } else { "none" },
+            metadata.tags.join(", ")
+        );
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+fn as_millis<S: serde::Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64() * 1000.0)
+}
+
+fn optional_as_millis<S: serde::Serializer>(
+    elapsed: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match elapsed {
+        Some(elapsed) => as_millis(elapsed, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let answer = PartialAnswer {
+            part1: Some(AnswerValue::Int(50)),
+            part2: None,
+        };
+        Report::new(9, answer, Duration::from_micros(1500))
+    }
+
+    const PLAIN: Style = Style { color: false };
+
+    #[test]
+    fn render_text() {
+        assert_eq!(
+            report().render(Format::Text, PLAIN),
+            "Day 9\n  part1: 50\n  took: 1.50ms"
+        );
+    }
+
+    #[test]
+    fn render_text_checks_known_answers() {
+        let mut report = Report::new(
+            9,
+            PartialAnswer {
+                part1: Some(AnswerValue::Int(50)),
+                part2: Some(AnswerValue::Int(24)),
+            },
+            Duration::from_millis(3),
+        );
+        report.expected = Expected {
+            part1: Some(AnswerValue::Int(50)),
+            part2: Some(AnswerValue::Int(25)),
+        };
+
+        assert_eq!(
+            report.render(Format::Text, PLAIN),
+            "Day 9\n  part1: 50 ✓\n  part2: 24 ✗ expected 25\n  took: 3.00ms"
+        );
+    }
+
+    #[test]
+    fn wrong_answers_are_failures() {
+        let mut report = report();
+        assert_eq!(report.failure(), None);
+
+        report.expected.part1 = Some(AnswerValue::Int(50));
+        report.expected.part2 = Some(AnswerValue::Int(1));
+        assert_eq!(
+            report.failure(),
+            None,
+            "part2 wasn't solved, so it can't be wrong"
+        );
+
+        report.part2 = Some(AnswerValue::Int(2));
+        assert_eq!(report.failure(), Some(AocError::WrongPart2));
+
+        report.part1 = Some(AnswerValue::Int(49));
+        assert_eq!(report.failure(), Some(AocError::WrongPart1));
+    }
+
+    #[test]
+    fn render_text_with_timings() {
+        let mut report = report();
+        report.timings = Timings {
+            parse: Some(Duration::from_micros(250)),
+            part1: Some(Duration::from_micros(1200)),
+            part2: None,
+        };
+
+        assert_eq!(
+            report.render(Format::Text, PLAIN),
+            "Day 9\n  part1: 50\n  took: 1.50ms (parse 250.00µs, part1 1.20ms)"
+        );
+    }
+
+    #[test]
+    fn render_missing_part2() {
+        let mut report = report();
+        report.part2_missing = true;
+
+        assert_eq!(
+            report.render(Format::Text, PLAIN),
+            "Day 9\n  part1: 50\n  part2: none\n  took: 1.50ms"
+        );
+        assert!(
+            report
+                .render(Format::Json, PLAIN)
+                .contains(r#""part2":null,"part2_missing":true"#)
+        );
+    }
+
+    #[test]
+    fn render_text_answers() {
+        let mut report = report();
+        report.part1 = Some(AnswerValue::from("4,6,3"));
+        report.part2 = Some(AnswerValue::from("#..#\n####"));
+        report.expected.part2 = Some(AnswerValue::from("#..#\n#..#"));
+
+        assert_eq!(
+            report.render(Format::Text, PLAIN),
+            "Day 9\n  part1: 4,6,3\n  part2:\n    #..#\n    #### ✗ expected\n    #..#\n    #..#\n  took: 1.50ms"
+        );
+    }
+
+    #[test]
+    fn render_text_in_color() {
+        let rendered = report().render(Format::Text, Style { color: true });
+        assert!(rendered.starts_with("\x1B[1mDay 9\x1B[0m"));
+    }
+
+    #[test]
+    fn render_quiet() {
+        assert_eq!(report().render_quiet(), "50\n");
+
+        let answer = PartialAnswer {
+            part1: None,
+            part2: Some(AnswerValue::Int(24)),
+        };
+        let report = Report::new(9, answer, Duration::ZERO);
+        assert_eq!(report.render_quiet(), "\n24");
+    }
+
+    #[test]
+    fn render_json() {
+        assert_eq!(
+            report().render(Format::Json, PLAIN),
+            r#"{"day":9,"part1":50,"part2":null,"elapsed_ms":1.5,"timings_ms":{"parse":null,"part1":null,"part2":null}}"#
+        );
+    }
+
+    #[test]
+    fn render_all_text() {
+        let results = vec![
+            (9, Ok(report())),
+            (10, Err(AocError::MissingInput("inputs/10.txt".into()))),
+        ];
+        assert_eq!(
+            render_all(&results, Format::Text, PLAIN),
+            "Day 9\n  part1: 50\n  took: 1.50ms\nDay 10\n  ERROR: file does not exist: inputs/10.txt"
+        );
+    }
+
+    #[test]
+    fn render_all_json() {
+        let results = vec![
+            (9, Ok(report())),
+            (10, Err(AocError::MissingInput("inputs/10.txt".into()))),
+        ];
+        assert_eq!(
+            render_all(&results, Format::Json, PLAIN),
+            r#"[{"day":9,"part1":50,"part2":null,"elapsed_ms":1.5,"timings_ms":{"parse":null,"part1":null,"part2":null}},{"day":10,"error":"file does not exist: inputs/10.txt"}]"#
+        );
+    }
+
+    #[test]
+    fn render_catalog_text() {
+        let catalog = [
+            Metadata {
+                day: 4,
+                title: "Printing Department",
+                tags: &["grid"],
+                has_part2: true,
+            },
+            Metadata {
+                day: 12,
+                title: "Christmas Tree Farm",
+                tags: &["heuristic", "packing"],
+                has_part2: false,
+            },
+        ];
+
+        assert_eq!(
+            render_catalog(&catalog, Format::Text),
+            [
+                "day  title                part2  tags",
+                "  4  Printing Department  yes    grid",
+                " 12  Christmas Tree Farm  none   heuristic, packing",
+            ]
+            .join("\n")
+        );
+        assert!(render_catalog(&catalog, Format::Json).starts_with(r#"[{"day":4,"#));
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
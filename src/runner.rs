@@ -0,0 +1,592 @@
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+use tracing::{debug_span, info_span};
+
+use aoc::{
+    ctx::{self, SolveCtx},
+    error::AocError,
+    solver::{AnswerValue, Part, PartialAnswer, Solver},
+};
+
+use crate::{
+    cli::Args,
+    expected::Expected,
+    recording::Recording,
+    report::{Report, Timings},
+    watchdog::{self, Watched},
+};
+
+/// How long a timed out solve waits for the watchdog to stop, once the solver is cancelled.
+const WATCHDOG_GRACE: Duration = Duration::from_secs(1);
+
+/// Reads the day's input, then solves it.
+pub fn run_day(
+    solver: &'static dyn Solver,
+    args: &Args,
+    answers: &HashMap<u32, Expected>,
+) -> Result<Report, AocError> {
+    let start = Instant::now();
+
+    let filename = args.input_path(solver.day());
+    let input = std::fs::read_to_string(&filename).map_err(|_| AocError::MissingInput(filename))?;
+
+    // several days run at once here, so their progress bars would trample each other
+    let mut report = solve(solver, &input, args, start, false)?;
+    report.expected = expected_for(solver.day(), args, answers);
+
+    Ok(report)
+}
+
+/// The expected answers for a day. These only apply to the real input, so nothing is expected
+/// when a different input was given.
+pub fn expected_for(day: u32, args: &Args, answers: &HashMap<u32, Expected>) -> Expected {
+    if !args.is_real_input() {
+        return Expected::default();
+    }
+
+    answers.get(&day).cloned().unwrap_or_default()
+}
+
+/// Solves the input while the watchdog keeps an eye on it, optionally drawing a progress bar. The
+/// report's elapsed time is measured from `start`.
+///
+/// A solver which can't solve the input returns why as an error. One which panics has a bug, and is
+/// caught and returned as a crash, rather than taking the runner down.
+///
+/// With `--timeout`, solving happens on a worker thread which is cancelled once time runs out.
+/// Solvers only notice when they next report progress, so one which never reports is left to
+/// finish in the background while the runner moves on.
+pub fn solve(
+    solver: &'static dyn Solver,
+    input: &str,
+    args: &Args,
+    start: Instant,
+    show_progress: bool,
+) -> Result<Report, AocError> {
+    check_params(solver, args)?;
+    let (ctx, events) = SolveCtx::new();
+    let ctx = ctx
+        .visualized(args.visualize)
+        .with_params(args.params.clone());
+    let cancel = ctx.cancel_handle();
+    let watchdog = watchdog::spawn(
+        solver.day(),
+        events,
+        cancel.clone(),
+        args.soft_deadline,
+        watchdog::STATUS_INTERVAL,
+        show_progress,
+        args.record.as_ref().map(|_| Recording::default()),
+    );
+
+    let parts = match args.timeout {
+        None => {
+            let parts = solve_parts(solver, input, args.part, &ctx);
+            drop(ctx);
+            parts
+        }
+        Some(timeout) => {
+            let (sender, receiver) = mpsc::channel();
+            let (input, part) = (input.to_string(), args.part);
+            thread::spawn(move || {
+                // the runner may have stopped listening, if time ran out
+                let _ = sender.send(solve_parts(solver, &input, part, &ctx));
+            });
+
+            match receiver.recv_timeout(timeout) {
+                Ok(parts) => parts,
+                Err(RecvTimeoutError::Timeout) => {
+                    cancel.cancel();
+
+                    // the watchdog notices the cancel quickly, even if the solver never does
+                    if let Some(watched) = watchdog::join_within(watchdog, WATCHDOG_GRACE) {
+                        save_recording(&watched, args)?;
+                    }
+                    return Err(AocError::Timeout(timeout));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    unreachable!("solver panics are caught, so a result is always sent")
+                }
+            }
+        }
+    };
+    let watched = watchdog.join().expect("watchdog should not panic");
+    save_recording(&watched, args)?;
+    let phase_starts = watched.phase_starts;
+
+    let (part1, part2) = parts?;
+    let timings = Timings {
+        parse: [&part1, &part2]
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.parse_time(&phase_starts))
+            .reduce(|a, b| a + b),
+        part1: part1.as_ref().map(|p| p.end - p.start),
+        part2: part2.as_ref().map(|p| p.end - p.start),
+    };
+    let answer = PartialAnswer {
+        part1: part1.map(|p| p.answer),
+        part2: part2.map(|p| p.answer),
+    };
+
+    let mut report = Report::new(solver.day(), answer, start.elapsed());
+    report.timings = timings;
+    report.part2_missing = args.part.includes_part2() && !solver.metadata().has_part2;
+
+    Ok(report)
+}
+
+/// Saves the frames the watchdog recorded, for `--record`.
+fn save_recording(watched: &Watched, args: &Args) -> Result<(), AocError> {
+    if let (Some(recording), Some(path)) = (&watched.recording, &args.record) {
+        recording.save(path).map_err(AocError::Other)?;
+        eprintln!("recorded {} frames: {}", recording.len(), path.display());
+    }
+
+    Ok(())
+}
+
+/// Checks every `--param` is one the day's solver reads, so a misspelled name isn't quietly
+/// solved with the puzzle's own value instead.
+fn check_params(solver: &dyn Solver, args: &Args) -> Result<(), AocError> {
+    let known: Vec<&str> = solver.params().iter().map(|param| param.name).collect();
+    match args
+        .params
+        .keys()
+        .find(|name| !known.contains(&name.as_str()))
+    {
+        None => Ok(()),
+        Some(name) if known.is_empty() => Err(AocError::Usage(format!(
+            "day {} has no params, so can't take {name}",
+            solver.day()
+        ))),
+        Some(name) => Err(AocError::Usage(format!(
+            "day {} has no param {name}, only {}",
+            solver.day(),
+            known.join(", ")
+        ))),
+    }
+}
+
+/// Solves the requested part(s), catching any crash.
+fn solve_parts(
+    solver: &dyn Solver,
+    input: &str,
+    part: Part,
+    ctx: &SolveCtx,
+) -> Result<(Option<TimedPart>, Option<TimedPart>), AocError> {
+    // each part is timed separately, rather than through `solve_parts`, to see where time goes
+    catch_crash(|| {
+        let _day = info_span!("solve", day = solver.day()).entered();
+        let part1 = part
+            .includes_part1()
+            .then(|| {
+                let _part = debug_span!("part1").entered();
+                timed(|| solver.solve_part1(input, ctx))
+            })
+            .transpose()?;
+        let part2 = solver
+            .solves_part2(part)
+            .then(|| {
+                let _part = debug_span!("part2").entered();
+                timed(|| solver.solve_part2(input, ctx))
+            })
+            .transpose()?;
+        Ok((part1, part2))
+    })
+}
+
+/// Runs `solve`, returning a panic as `AocError::Crashed`. Solvers return an error for input they
+/// can't solve, so this is only a last resort against bugs, which print their panic as usual.
+pub fn catch_crash<T>(solve: impl FnOnce() -> Result<T, AocError>) -> Result<T, AocError> {
+    panic::catch_unwind(AssertUnwindSafe(solve)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        Err(AocError::Crashed(message))
+    })
+}
+
+/// One part's answer, and when solving it started and ended.
+struct TimedPart {
+    answer: AnswerValue,
+    start: Instant,
+    end: Instant,
+}
+
+impl TimedPart {
+    /// Time spent in the "parse" phases reported while this part was being solved, if any were.
+    fn parse_time(&self, phase_starts: &[(&'static str, Instant)]) -> Option<Duration> {
+        let within: Vec<(&'static str, Instant)> = phase_starts
+            .iter()
+            .copied()
+            .filter(|(_, at)| (self.start..self.end).contains(at))
+            .collect();
+
+        ctx::phase_durations(&within, self.end)
+            .into_iter()
+            .filter(|(name, _)| *name == "parse")
+            .map(|(_, duration)| duration)
+            .reduce(|a, b| a + b)
+    }
+}
+
+fn timed(solve: impl FnOnce() -> Result<AnswerValue, AocError>) -> Result<TimedPart, AocError> {
+    let start = Instant::now();
+    let answer = solve()?;
+
+    Ok(TimedPart {
+        answer,
+        start,
+        end: Instant::now(),
+    })
+}
+
+/// Runs every solver on a thread pool, so the slow days don't hold up the fast ones. Results are
+/// returned in the same order as the solvers.
+pub fn run_all(
+    solvers: &[&'static dyn Solver],
+    args: &Args,
+    answers: &HashMap<u32, Expected>,
+) -> Vec<(u32, Result<Report, AocError>)> {
+    solvers
+        .par_iter()
+        .map(|solver| (solver.day(), run_day(*solver, args, answers)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{examples, solver, solver::Metadata};
+
+    #[test]
+    fn run_all_keeps_day_order() {
+        // a missing input keeps this fast, while still running every solver's path
+        let args = Args::parse(["all", "--input", "does/not/exist.txt"].map(String::from)).unwrap();
+        let results = run_all(solver::SOLVERS, &args, &HashMap::new());
+
+        let days: Vec<u32> = results.iter().map(|(day, _)| *day).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<_>>());
+        assert_eq!(
+            results[0].1.as_ref().unwrap_err(),
+            &AocError::MissingInput("does/not/exist.txt".into())
+        );
+    }
+
+    #[test]
+    fn every_example_has_its_known_answers() {
+        // day 8 makes fewer connections for its example, and day 11's part 2 has its own example
+        let examples: [(&[&str], &str, Option<&str>); 13] = [
+            (&["1", "--example"], "3", Some("6")),
+            (&["2", "--example"], "1227775554", Some("4174379265")),
+            (&["3", "--example"], "357", Some("3121910778619")),
+            (&["4", "--example"], "13", Some("43")),
+            (&["5", "--example"], "3", Some("14")),
+            (&["6", "--example"], "4277556", Some("3263827")),
+            (&["7", "--example"], "21", Some("40")),
+            (
+                &["8", "--example", "--param", "connections=10"],
+                "40",
+                Some("25272"),
+            ),
+            (&["9", "--example"], "50", Some("24")),
+            (&["10", "--example"], "7", Some("33")),
+            (&["11", "--example", "--part", "1"], "5", None),
+            (&["11", "--input", "examples/11-2.txt"], "4", Some("2")),
+            (&["12", "--example"], "3", None),
+        ];
+
+        for (args, part1, part2) in examples {
+            let args = Args::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+            let solver = solver::find(&args.day).unwrap();
+            let report = run_day(solver, &args, &HashMap::new()).unwrap();
+
+            let day = solver.day();
+            assert_eq!(report.part1.unwrap().to_string(), part1, "day {day} part 1");
+            assert_eq!(
+                report.part2.map(|answer| answer.to_string()).as_deref(),
+                part2,
+                "day {day} part 2"
+            );
+        }
+    }
+
+    #[test]
+    fn solve_times_parts_and_parsing() {
+        let args = Args::parse(["5".to_string()]).unwrap();
+        let input = examples::read("5");
+
+        let report = solve(
+            solver::find("5").unwrap(),
+            &input,
+            &args,
+            Instant::now(),
+            false,
+        )
+        .unwrap();
+        let timings = report.timings;
+
+        assert!(timings.parse.is_some());
+        assert!(timings.part1.is_some() && timings.part2.is_some());
+        assert!(timings.parse.unwrap() <= timings.part1.unwrap() + timings.part2.unwrap());
+    }
+
+    #[test]
+    fn params_reach_the_solver() {
+        let input = examples::read("3");
+        let solve_with = |args: &[&str]| {
+            let args = Args::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+            solve(
+                solver::find("3").unwrap(),
+                &input,
+                &args,
+                Instant::now(),
+                false,
+            )
+        };
+
+        let report = solve_with(&["3", "--param", "part1_batteries=1"]).unwrap();
+        assert_eq!(report.part1, Some(AnswerValue::Int(9 + 9 + 8 + 9)));
+
+        assert!(matches!(
+            solve_with(&["3", "--param", "part1_battery=1"]),
+            Err(AocError::Usage(_))
+        ));
+        assert!(matches!(
+            solve_with(&["3", "--param", "part1_batteries=lots"]),
+            Err(AocError::Usage(_))
+        ));
+        assert!(matches!(
+            solve(
+                solver::find("1").unwrap(),
+                "",
+                &Args::parse(["1", "--param", "size=10"].map(String::from)).unwrap(),
+                Instant::now(),
+                false,
+            ),
+            Err(AocError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn parse_time_only_counts_parse_phases_within_the_part() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let part = TimedPart {
+            answer: AnswerValue::Int(0),
+            start: at(10),
+            end: at(20),
+        };
+
+        let phases = [("parse", at(0)), ("parse", at(10)), ("search", at(14))];
+        assert_eq!(part.parse_time(&phases), Some(Duration::from_millis(4)));
+        assert_eq!(part.parse_time(&[("search", at(12))]), None);
+    }
+
+    struct Unparseable;
+
+    impl Solver for Unparseable {
+        fn metadata(&self) -> Metadata {
+            Metadata {
+                day: 99,
+                title: "Unparseable",
+                tags: &[],
+                has_part2: true,
+            }
+        }
+
+        fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            let n: usize = input
+                .parse()
+                .map_err(|_| AocError::Parse(format!("should be a number: {input}")))?;
+            Ok(n.into())
+        }
+
+        fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            // a bug, which only shows up on input part 1 can't parse
+            Ok(input.parse::<usize>().unwrap().into())
+        }
+    }
+
+    #[test]
+    fn solver_errors_are_returned() {
+        let args = Args::parse(["99", "--part", "1"].map(String::from)).unwrap();
+        let result = solve(&Unparseable, "not a number", &args, Instant::now(), false);
+
+        assert_eq!(
+            result.unwrap_err(),
+            AocError::Parse("should be a number: not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn solver_panics_are_crashes() {
+        let args = Args::parse(["99", "--part", "2"].map(String::from)).unwrap();
+        let result = solve(&Unparseable, "not a number", &args, Instant::now(), false);
+
+        assert!(matches!(result, Err(AocError::Crashed(_))));
+    }
+
+    /// Reports progress forever, so it only stops when cancelled.
+    struct Endless;
+
+    impl Solver for Endless {
+        fn metadata(&self) -> Metadata {
+            Metadata {
+                day: 98,
+                title: "Endless",
+                tags: &[],
+                has_part2: false,
+            }
+        }
+
+        fn solve_part1(&self, _input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            loop {
+                ctx.progress(0, 1);
+            }
+        }
+
+        fn solve_part2(&self, _input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            Ok(AnswerValue::Int(0))
+        }
+    }
+
+    #[test]
+    fn solvers_time_out() {
+        let args = Args::parse(["98", "--timeout", "0.05"].map(String::from)).unwrap();
+        let result = solve(&Endless, "", &args, Instant::now(), false);
+
+        assert_eq!(
+            result.unwrap_err(),
+            AocError::Timeout(Duration::from_millis(50))
+        );
+    }
+
+    /// Draws one frame, then reports progress forever like `Endless`.
+    struct EndlessDrawing;
+
+    impl Solver for EndlessDrawing {
+        fn metadata(&self) -> Metadata {
+            Metadata {
+                day: 97,
+                ..Endless.metadata()
+            }
+        }
+
+        fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            ctx.frame(|| "the first frame".to_string());
+            Endless.solve_part1(input, ctx)
+        }
+
+        fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError> {
+            Endless.solve_part2(input, ctx)
+        }
+    }
+
+    #[test]
+    fn timing_out_still_saves_the_recording() {
+        let path = std::env::temp_dir().join("aoc-timing-out-still-saves-the-recording.cast");
+        let _ = std::fs::remove_file(&path);
+        let args = Args::parse(
+            [
+                "97",
+                "--timeout",
+                "0.05",
+                "--visualize",
+                "--record",
+                path.to_str().unwrap(),
+            ]
+            .map(String::from),
+        )
+        .unwrap();
+
+        let result = solve(&EndlessDrawing, "", &args, Instant::now(), false);
+        assert!(matches!(result, Err(AocError::Timeout(_))));
+
+        let cast = std::fs::read_to_string(&path).unwrap();
+        assert!(cast.contains("the first frame"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn expected_only_for_real_input() {
+        let answers = HashMap::from([(
+            9,
+            Expected {
+                part1: Some(AnswerValue::Int(50)),
+                part2: None,
+            },
+        )]);
+
+        let args = Args::parse(["9".to_string()]).unwrap();
+        assert_eq!(
+            expected_for(9, &args, &answers).part1,
+            Some(AnswerValue::Int(50))
+        );
+        assert_eq!(expected_for(10, &args, &answers), Expected::default());
+
+        let args = Args::parse(["9", "--input", "examples/9.txt"].map(String::from)).unwrap();
+        assert_eq!(expected_for(9, &args, &answers), Expected::default());
+    }
+
+    #[test]
+    fn solve_reports_answer() {
+        let args = Args::parse(["1".to_string()]).unwrap();
+        let input = examples::read("1");
+
+        let report = solve(
+            solver::find("1").unwrap(),
+            &input,
+            &args,
+            Instant::now(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(report.day, 1);
+        assert_eq!(
+            PartialAnswer {
+                part1: report.part1,
+                part2: report.part2
+            },
+            PartialAnswer {
+                part1: Some(AnswerValue::Int(3)),
+                part2: Some(AnswerValue::Int(6))
+            }
+        );
+        assert!(!report.part2_missing);
+    }
+
+    #[test]
+    fn solve_reports_missing_part2() {
+        let input = examples::read("12");
+        let solve_day_12 = |args: &[&str]| {
+            let args = Args::parse(args.iter().map(|s| s.to_string())).unwrap();
+            solve(
+                solver::find("12").unwrap(),
+                &input,
+                &args,
+                Instant::now(),
+                false,
+            )
+            .unwrap()
+        };
+
+        let report = solve_day_12(&["12"]);
+        assert_eq!(report.part2, None);
+        assert!(report.part2_missing);
+
+        // part 1 on its own never asked for part 2
+        assert!(!solve_day_12(&["12", "--part", "1"]).part2_missing);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{fs, path::PathBuf};
+
+use aoc::examples;
+
+const LIB_PATH: &str = "src/lib.rs";
+const SOLVER_PATH: &str = "src/solver.rs";
+const GOLDEN_PATH: &str = "src/golden.rs";
+
+/// Creates a new day's solver from a template, an empty example file to paste the puzzle's
+/// example into, and registers the solver so it can be run. Returns the files which were written.
+pub fn scaffold(day: u32) -> Result<Vec<PathBuf>, String> {
+    let problem_path = PathBuf::from(format!("src/problems/problem{day}.rs"));
+    if problem_path.exists() {
+        return Err(format!("{} already exists", problem_path.display()));
+    }
+
+    let lib = read(LIB_PATH)?;
+    let solver = read(SOLVER_PATH)?;
+    let golden = read(GOLDEN_PATH)?;
+    let lib = register_module(&lib, day)?;
+    let solver = register_solver(&solver, day)?;
+    let golden = register_golden_test(&golden, day)?;
+
+    write(&problem_path, &template(day))?;
+    write(&PathBuf::from(LIB_PATH), &lib)?;
+    write(&PathBuf::from(SOLVER_PATH), &solver)?;
+    write(&PathBuf::from(GOLDEN_PATH), &golden)?;
+
+    let mut written = vec![
+        problem_path,
+        LIB_PATH.into(),
+        SOLVER_PATH.into(),
+        GOLDEN_PATH.into(),
+    ];
+    let example_path = examples::path(&day.to_string());
+    if !example_path.exists() {
+        write(&example_path, "")?;
+        written.push(example_path);
+    }
+
+    Ok(written)
+}
+
+/// A solver which compiles, but needs the actual solution filled in.
+fn template(day: u32) -> String {
+    format!(
+        r#"use crate::{{
+    ctx::SolveCtx,
+    error::AocError,
+    shared::Answer,
+    solver::{{AnswerValue, Metadata, Solver}},
+}};
+
+pub fn solve(input: &str) -> Result<Answer, AocError> {{
+    Ok(Answer {{
+        part1: part1(input)?,
+        part2: Some(part2(input)?),
+    }})
+}}
+
+fn part1(_input: &str) -> Result<usize, AocError> {{
+    todo!("part 1")
+}}
+
+fn part2(_input: &str) -> Result<usize, AocError> {{
+    todo!("part 2")
+}}
+
+pub struct Problem{day};
+
+impl Solver for Problem{day} {{
+    fn metadata(&self) -> Metadata {{
+        Metadata {{
+            day: {day},
+            title: "TODO",
+            tags: &[],
+            has_part2: true,
+        }}
+    }}
+
+    fn solve_part1(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {{
+        Ok(part1(input)?.into())
+    }}
+
+    fn solve_part2(&self, input: &str, _ctx: &SolveCtx) -> Result<AnswerValue, AocError> {{
+        Ok(part2(input)?.into())
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn solve_basic_input() {{
+        let input = examples::read("{day}");
+
+        let result = solve(&input).unwrap();
+        assert_eq!(result.part1, 0);
+        assert_eq!(result.part2, Some(0));
+    }}
+}}
+"#
+    )
+}
+
+/// Adds the day's module to the `problems` module in lib.rs, keeping them sorted like rustfmt
+/// does.
+fn register_module(lib: &str, day: u32) -> Result<String, String> {
+    let start = lib
+        .find("pub mod problems {\n")
+        .ok_or("could not find the problems module in lib.rs")?
+        + "pub mod problems {\n".len();
+    let end = start
+        + lib[start..]
+            .find("}\n")
+            .ok_or("could not find the end of the problems module in lib.rs")?;
+
+    let mut modules: Vec<String> = lib[start..end].lines().map(String::from).collect();
+    modules.push(format!("    pub mod problem{day};"));
+    // by name, so the trailing semicolons don't put problem13 before problem1
+    modules.sort_by_key(|line| line.trim().trim_end_matches(';').to_string());
+
+    Ok(format!(
+        "{}{}\n{}",
+        &lib[..start],
+        modules.join("\n"),
+        &lib[end..]
+    ))
+}
+
+/// Adds the day's solver to the end of `SOLVERS` in solver.rs, which keeps them in day order as
+/// long as days are added in order.
+fn register_solver(solver: &str, day: u32) -> Result<String, String> {
+    let solvers = solver
+        .find("pub static SOLVERS")
+        .ok_or("could not find SOLVERS in solver.rs")?;
+    let end = solvers
+        + solver[solvers..]
+            .find("];")
+            .ok_or("could not find the end of SOLVERS in solver.rs")?;
+
+    Ok(format!(
+        "{}    &problem{day}::Problem{day},\n{}",
+        &solver[..end],
+        &solver[end..]
+    ))
+}
+
+/// Adds a full-input test for the day to the end of `golden_tests!` in golden.rs.
+fn register_golden_test(golden: &str, day: u32) -> Result<String, String> {
+    let tests = golden
+        .find("golden_tests! {")
+        .ok_or("could not find golden_tests! in golden.rs")?;
+    let end = tests
+        + golden[tests..]
+            .find("\n}")
+            .ok_or("could not find the end of golden_tests! in golden.rs")?
+        + 1;
+
+    Ok(format!(
+        "{}    day{day} => {day},\n{}",
+        &golden[..end],
+        &golden[end..]
+    ))
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("could not read {path}: {e}"))
+}
+
+fn write(path: &PathBuf, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("could not write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_module_in_sorted_order() {
+        let lib =
+            "pub mod problems {\n    pub mod problem1;\n    pub mod problem2;\n}\n\npub mod ctx;\n";
+
+        assert_eq!(
+            register_module(lib, 13).unwrap(),
+            "pub mod problems {\n    pub mod problem1;\n    pub mod problem13;\n    pub mod problem2;\n}\n\npub mod ctx;\n"
+        );
+    }
+
+    #[test]
+    fn registers_solver_last() {
+        let solver = "pub static SOLVERS: &[&dyn Solver] = &[\n    &problem12::Problem12,\n];\n";
+
+        assert_eq!(
+            register_solver(solver, 13).unwrap(),
+            "pub static SOLVERS: &[&dyn Solver] = &[\n    &problem12::Problem12,\n    &problem13::Problem13,\n];\n"
+        );
+    }
+
+    #[test]
+    fn registers_golden_test_last() {
+        let golden = "golden_tests! {\n    day12 => 12,\n}\n\nfn check() {}\n";
+
+        assert_eq!(
+            register_golden_test(golden, 13).unwrap(),
+            "golden_tests! {\n    day12 => 12,\n    day13 => 13,\n}\n\nfn check() {}\n"
+        );
+    }
+
+    #[test]
+    fn registering_needs_somewhere_to_register() {
+        assert!(register_module("pub mod cli;\n", 13).is_err());
+        assert!(register_solver("pub fn find() {}\n", 13).is_err());
+        assert!(register_golden_test("fn check() {}\n", 13).is_err());
+    }
+
+    #[test]
+    fn registers_in_the_real_files() {
+        assert!(register_module(&read(LIB_PATH).unwrap(), 13).is_ok());
+        assert!(register_solver(&read(SOLVER_PATH).unwrap(), 13).is_ok());
+        assert!(register_golden_test(&read(GOLDEN_PATH).unwrap(), 13).is_ok());
+    }
+
+    #[test]
+    fn template_is_for_the_day() {
+        let template = template(13);
+        assert!(template.contains("pub struct Problem13;"));
+        assert!(template.contains("day: 13,"));
+        assert!(template.contains(r#"examples::read("13")"#));
+    }
+
+    #[test]
+    fn template_imports_from_its_own_crate() {
+        // the solver is written into the library, which can't refer to itself as aoc
+        let template = template(13);
+        assert!(template.contains("use crate::examples;"));
+        assert!(!template.contains("aoc::"));
+    }
+}
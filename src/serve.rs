@@ -0,0 +1,147 @@
+use std::{sync::Arc, thread, time::Instant};
+
+use tiny_http::{Header, Response, Server};
+
+use aoc::{
+    error::AocError,
+    solver::{self, Part},
+};
+
+use crate::{
+    cli::Args,
+    report::{self, Format, Style},
+    runner,
+};
+
+/// Where the server listens unless told otherwise. Local only, since solving is expensive.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8025";
+
+/// Serves the solvers over HTTP until interrupted:
+///
+/// - `POST /solve/9` solves the request body as day 9's input, returning the report as JSON.
+///   Solve a single part with `?part=1` or `?part=2`.
+/// - `GET /solvers` returns the catalog of solvers as JSON.
+///
+/// Each request is handled on its own thread, so a slow solve doesn't hold up the others.
+pub fn serve(addr: &str, args: Args) -> Result<(), String> {
+    let server = Server::http(addr).map_err(|e| format!("could not listen on {addr}: {e}"))?;
+    eprintln!("listening on http://{}", server.server_addr());
+
+    let args = Arc::new(args);
+    for mut request in server.incoming_requests() {
+        let args = Arc::clone(&args);
+        thread::spawn(move || {
+            let mut body = String::new();
+            let (status, json) = match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => handle(request.method().as_str(), request.url(), &body, &args),
+                Err(e) => error(400, &format!("could not read body: {e}")),
+            };
+
+            let content_type = Header::from_bytes("Content-Type", "application/json")
+                .expect("content type header should be valid");
+            let response = Response::from_string(json)
+                .with_status_code(status)
+                .with_header(content_type);
+            if let Err(e) = request.respond(response) {
+                eprintln!("could not respond: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Routes a request, returning the status code and JSON body to respond with.
+fn handle(method: &str, url: &str, body: &str, args: &Args) -> (u16, String) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    match (method, path.strip_prefix("/solve/")) {
+        ("POST", Some(day)) => match solve(day, query, body, args) {
+            Ok(json) => (200, json),
+            Err(e) => error(status_for(&e), &e.to_string()),
+        },
+        (_, Some(_)) => error(405, "use POST to solve"),
+        ("GET", None) if path == "/solvers" => {
+            let catalog: Vec<_> = solver::SOLVERS.iter().map(|s| s.metadata()).collect();
+            (200, report::render_catalog(&catalog, Format::Json))
+        }
+        _ => error(404, &format!("no such endpoint: {method} {path}")),
+    }
+}
+
+fn solve(day: &str, query: &str, input: &str, args: &Args) -> Result<String, AocError> {
+    let solver = solver::find(day).ok_or_else(|| AocError::UnknownDay(day.to_string()))?;
+
+    let mut args = args.clone();
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "part" => args.part = value.parse::<Part>().map_err(AocError::Usage)?,
+            _ => return Err(AocError::Usage(format!("unknown query parameter: {key}"))),
+        }
+    }
+
+    let report = runner::solve(solver, input, &args, Instant::now(), false)?;
+    Ok(report.render(Format::Json, Style { color: false }))
+}
+
+fn status_for(error: &AocError) -> u16 {
+    match error {
+        AocError::Usage(_) => 400,
+        AocError::UnknownDay(_) => 404,
+        AocError::Parse(_) | AocError::Unsolvable(_) => 422,
+        _ => 500,
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::examples;
+
+    fn args() -> Args {
+        Args::parse(["serve".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn solve_posted_input() {
+        let (status, json) = handle("POST", "/solve/1", &examples::read("1"), &args());
+
+        assert_eq!(status, 200);
+        assert!(
+            json.starts_with(r#"{"day":1,"part1":3,"part2":6,"#),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn solve_one_part() {
+        let (status, json) = handle("POST", "/solve/1?part=2", &examples::read("1"), &args());
+
+        assert_eq!(status, 200);
+        assert!(
+            json.starts_with(r#"{"day":1,"part1":null,"part2":6,"#),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn list_solvers() {
+        let (status, json) = handle("GET", "/solvers", "", &args());
+
+        assert_eq!(status, 200);
+        assert!(json.contains(r#""title":"Secret Entrance""#));
+    }
+
+    #[test]
+    fn errors_have_statuses() {
+        assert_eq!(handle("POST", "/solve/42", "", &args()).0, 404);
+        assert_eq!(handle("POST", "/solve/1", "L1x\n", &args()).0, 422);
+        assert_eq!(handle("POST", "/solve/1?part=3", "", &args()).0, 400);
+        assert_eq!(handle("GET", "/solve/1", "", &args()).0, 405);
+        assert_eq!(handle("GET", "/", "", &args()).0, 404);
+    }
+}
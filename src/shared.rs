@@ -3,19 +3,81 @@ use std::{
     ops::{Add, Div, Range, Rem, Sub},
 };
 
-/// Splits a string into partitions of the requested size
+use crate::error::AocError;
+
+pub mod biguint;
+pub mod bitset;
+pub mod combinatorics;
+pub mod cycle;
+pub mod digits;
+pub mod direction;
+pub mod disjoint_set;
+pub mod gf2;
+pub mod graph;
+pub mod grid;
+pub mod grid3;
+pub mod hex;
+pub mod iter;
+pub mod kdtree;
+pub mod linalg;
+pub mod numtheory;
+pub mod parse;
+pub mod point;
+pub mod polygon;
+pub mod range_query;
+pub mod range_set;
+pub mod rational;
+pub mod search;
+pub mod sparse_grid;
+pub mod tree;
+
+pub use biguint::BigUint;
+pub use bitset::BitSet;
+pub use direction::Direction;
+pub use disjoint_set::DisjointSet;
+pub use graph::{Graph, MinCut};
+pub use grid::{Grid, Regions, flood_fill, label_regions};
+pub use grid3::{Connectivity, Grid3, Neighborator3, flood_fill3};
+pub use hex::{Hex, HexDirection};
+pub use iter::IterExt;
+pub use point::{Point2, Point3};
+pub use range_query::{DifferenceGrid, FenwickTree, SegmentTree};
+pub use range_set::{RangeSet, intersect_ranges, split_range, subtract_range};
+pub use rational::Rational;
+pub use search::{CycleError, dijkstra, dijkstra_path, floyd_warshall, topo_sort};
+pub use sparse_grid::SparseGrid;
+
+/// Splits a string into partitions of the requested size, counted in chars so multi-byte
+/// characters are never cut in half. When the string doesn't split evenly, the last partition is
+/// the shorter leftover.
 pub struct PartitionIterator<'a> {
     pub remaining: &'a str,
     pub partition_size: usize,
 }
 
 impl<'a> PartitionIterator<'a> {
+    /// Panics if `partition_size` is zero, since that would never finish.
     pub fn new(to_split: &'a str, partition_size: usize) -> Self {
+        assert!(partition_size > 0, "partition size should not be zero");
+
         PartitionIterator {
             remaining: to_split,
             partition_size,
         }
     }
+
+    /// Like `new`, but for strings which should split evenly. A zero partition size, or a string
+    /// whose length isn't a multiple of it, is an error rather than a short last partition.
+    pub fn try_new(to_split: &'a str, partition_size: usize) -> Result<Self, AocError> {
+        let len = to_split.chars().count();
+        if partition_size == 0 || !len.is_multiple_of(partition_size) {
+            return Err(AocError::Parse(format!(
+                "{len} chars don't split evenly into partitions of {partition_size}: {to_split}"
+            )));
+        }
+
+        Ok(Self::new(to_split, partition_size))
+    }
 }
 
 impl<'a> Iterator for PartitionIterator<'a> {
@@ -26,7 +88,13 @@ impl<'a> Iterator for PartitionIterator<'a> {
             return None;
         }
 
-        let (partition, remaining) = self.remaining.split_at(self.partition_size);
+        // the byte offset of the first char past this partition, or the end for a short tail
+        let end = self
+            .remaining
+            .char_indices()
+            .nth(self.partition_size)
+            .map_or(self.remaining.len(), |(idx, _)| idx);
+        let (partition, remaining) = self.remaining.split_at(end);
         self.remaining = remaining;
 
         Some(partition)
@@ -182,19 +250,33 @@ where
     }
 }
 
+/// Both parts' answers. Usually whole numbers, but anything which converts to an `AnswerValue`
+/// works, like a `BigUint` for answers which outgrow `usize`. Part 2 is None for a puzzle which
+/// doesn't have one, like the final day.
 #[derive(Debug)]
-pub struct Answer {
-    pub part1: usize,
-    pub part2: usize,
+pub struct Answer<T = usize> {
+    pub part1: T,
+    pub part2: Option<T>,
 }
 
-/// Enables calling .sum() on an iterator of Answers
-impl Sum for Answer {
+impl<T> Answer<T> {
+    /// The answer to a puzzle without a part 2.
+    pub fn part1_only(part1: T) -> Self {
+        Self { part1, part2: None }
+    }
+}
+
+/// Enables calling .sum() on an iterator of Answers. Part 2 is only summed if every Answer has
+/// one.
+impl<T: Default + Add<Output = T>> Sum for Answer<T> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut total = Answer { part1: 0, part2: 0 };
+        let mut total = Answer {
+            part1: T::default(),
+            part2: Some(T::default()),
+        };
         for val in iter {
-            total.part1 += val.part1;
-            total.part2 += val.part2;
+            total.part1 = total.part1 + val.part1;
+            total.part2 = total.part2.zip(val.part2).map(|(sum, part2)| sum + part2);
         }
 
         total
@@ -205,6 +287,43 @@ impl Sum for Answer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn partition_evenly() {
+        let parts: Vec<&str> = PartitionIterator::new("123456", 2).collect();
+        assert_eq!(parts, ["12", "34", "56"]);
+    }
+
+    #[test]
+    fn partition_yields_short_tail() {
+        let parts: Vec<&str> = PartitionIterator::new("1234567", 3).collect();
+        assert_eq!(parts, ["123", "456", "7"]);
+
+        let parts: Vec<&str> = PartitionIterator::new("12", 5).collect();
+        assert_eq!(parts, ["12"]);
+    }
+
+    #[test]
+    fn partition_counts_chars_not_bytes() {
+        let parts: Vec<&str> = PartitionIterator::new("é€a😀b", 2).collect();
+        assert_eq!(parts, ["é€", "a😀", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "partition size should not be zero")]
+    fn partition_size_zero_panics() {
+        PartitionIterator::new("123", 0);
+    }
+
+    #[test]
+    fn partition_checked() {
+        let parts: Vec<&str> = PartitionIterator::try_new("ab€d", 2).unwrap().collect();
+        assert_eq!(parts, ["ab", "€d"]);
+
+        assert!(PartitionIterator::try_new("abc", 2).is_err());
+        assert!(PartitionIterator::try_new("abc", 0).is_err());
+        assert!(PartitionIterator::try_new("", 3).unwrap().next().is_none());
+    }
+
     #[test]
     fn numeric_partition_by_1() {
         let mut iter = NumericPartitionIterator::new(12345, 1);
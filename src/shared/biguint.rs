@@ -0,0 +1,385 @@
+//! Unsigned integers of any size, for answers which outgrow `usize`.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign},
+    str::FromStr,
+};
+
+use crate::error::AocError;
+
+/// Each limb holds 32 bits, so multiplying two limbs fits in a u64.
+const LIMB_BITS: u32 = 32;
+
+/// The largest power of ten which fits in a limb, for converting to and from decimal nine digits
+/// at a time.
+const DECIMAL_LIMB: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// An unsigned integer with as many digits as it needs. Only the arithmetic answers need, adding
+/// and multiplying, is supported, along with subtracting a smaller number.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    // least significant first, without trailing zeros, so zero is empty
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The value, if it's small enough to fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |n, &limb| n << LIMB_BITS | u128::from(limb)),
+        )
+    }
+
+    /// The value, if it's small enough to fit.
+    pub fn to_usize(&self) -> Option<usize> {
+        self.to_u128().and_then(|n| usize::try_from(n).ok())
+    }
+
+    /// `self - other`, or None when `other` is bigger, since that would go below zero.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0;
+        for (idx, &limb) in self.limbs.iter().enumerate() {
+            let subtrahend = i64::from(other.limbs.get(idx).copied().unwrap_or(0)) + borrow;
+            let mut n = i64::from(limb) - subtrahend;
+            borrow = 0;
+            if n < 0 {
+                n += 1 << LIMB_BITS;
+                borrow = 1;
+            }
+            limbs.push(n as u32);
+        }
+
+        let mut difference = BigUint { limbs };
+        difference.trim();
+        Some(difference)
+    }
+
+    /// Multiplies by a single limb, then adds another, like shifting in a decimal digit.
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = u64::from(addend);
+        for limb in &mut self.limbs {
+            let n = u64::from(*limb) * u64::from(factor) + carry;
+            *limb = n as u32;
+            carry = n >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    /// Divides by a single limb in place, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let n = (remainder << LIMB_BITS) | u64::from(*limb);
+            *limb = (n / u64::from(divisor)) as u32;
+            remainder = n % u64::from(divisor);
+        }
+        self.trim();
+
+        remainder as u32
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut n: u128) -> Self {
+        let mut limbs = Vec::new();
+        while n > 0 {
+            limbs.push(n as u32);
+            n >>= LIMB_BITS;
+        }
+
+        Self { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self::from(u128::from(n))
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(n: usize) -> Self {
+        Self::from(n as u128)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0;
+        for (idx, &limb) in long.iter().enumerate() {
+            let n = u64::from(limb) + u64::from(short.get(idx).copied().unwrap_or(0)) + carry;
+            limbs.push(n as u32);
+            carry = n >> LIMB_BITS;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+
+        BigUint { limbs }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        *self = &*self + other;
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, other: BigUint) {
+        *self += &other;
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Long multiplication, a limb at a time.
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::default();
+        }
+
+        let mut limbs = vec![0_u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let n = u64::from(a) * u64::from(b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = n as u32;
+                carry = n >> LIMB_BITS;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        let mut product = BigUint { limbs };
+        product.trim();
+        product
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, other: &BigUint) {
+        *self = &*self * other;
+    }
+}
+
+impl MulAssign for BigUint {
+    fn mul_assign(&mut self, other: BigUint) {
+        *self *= &other;
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::default(), |sum, n| sum + n)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::from(1_u64), |product, n| product * n)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // without trailing zeros, more limbs is always bigger
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // peel off nine digits at a time, least significant first
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            chunks.push(n.div_rem_small(DECIMAL_LIMB));
+        }
+
+        let (most_significant, rest) = chunks.split_last().expect("nonzero has digits");
+        write!(f, "{most_significant}")?;
+        for chunk in rest.iter().rev() {
+            write!(f, "{chunk:0width$}", width = DECIMAL_DIGITS)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AocError::Parse(format!("should be a whole number: {s}")));
+        }
+
+        // shift in nine digits at a time, most significant first
+        let mut n = BigUint::default();
+        let first_len = match s.len() % DECIMAL_DIGITS {
+            0 => DECIMAL_DIGITS,
+            len => len,
+        };
+        let (first, mut rest) = s.split_at(first_len);
+        n.mul_add_small(1, first.parse().expect("checked for digits"));
+        while !rest.is_empty() {
+            let (chunk, remaining) = rest.split_at(DECIMAL_DIGITS);
+            n.mul_add_small(DECIMAL_LIMB, chunk.parse().expect("checked for digits"));
+            rest = remaining;
+        }
+
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_decimal() {
+        for s in [
+            "0",
+            "7",
+            "1000000000",
+            "18446744073709551616",
+            "123456789012345678901234567890",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("000123").to_string(), "123");
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn arithmetic_past_u128() {
+        let max = BigUint::from(u128::MAX);
+
+        assert_eq!(
+            (&max + &BigUint::from(1_u64)).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            (&max * &max).to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(&max * &BigUint::default(), BigUint::default());
+    }
+
+    #[test]
+    fn sums_and_products() {
+        let product: BigUint = (1..=30_u64).map(BigUint::from).product();
+        assert_eq!(product.to_string(), "265252859812191058636308480000000");
+
+        let sum: BigUint = [u64::MAX, u64::MAX].into_iter().map(BigUint::from).sum();
+        assert_eq!(sum.to_u128(), Some(2 * u128::from(u64::MAX)));
+    }
+
+    #[test]
+    fn subtracting() {
+        let max = BigUint::from(u128::MAX);
+        let past_max = &max + &BigUint::from(1_u64);
+
+        assert_eq!(past_max.checked_sub(&max), Some(BigUint::from(1_u64)));
+        assert_eq!(
+            past_max.checked_sub(&BigUint::from(1_u64)),
+            Some(max.clone())
+        );
+        assert_eq!(max.checked_sub(&max), Some(BigUint::default()));
+        assert_eq!(max.checked_sub(&past_max), None);
+        assert_eq!(
+            big("100000000000000000000").checked_sub(&big("1")),
+            Some(big("99999999999999999999"))
+        );
+    }
+
+    #[test]
+    fn converts_down_when_it_fits() {
+        assert_eq!(BigUint::from(42_usize).to_usize(), Some(42));
+        assert_eq!(
+            big("340282366920938463463374607431768211456").to_u128(),
+            None
+        );
+        assert_eq!(BigUint::default().to_usize(), Some(0));
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(big("18446744073709551616") > BigUint::from(u64::MAX));
+        assert!(big("5") < big("7"));
+        assert_eq!(big("0"), BigUint::default());
+    }
+}
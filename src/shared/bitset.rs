@@ -0,0 +1,267 @@
+//! A set of small numbers packed into bits, for tracking which of a dense range of indices have
+//! been seen or used without the hashing that a `HashSet` does for every lookup.
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign};
+
+const BLOCK_BITS: usize = u64::BITS as usize;
+
+/// A set of `usize`s, one bit each. It grows to fit the biggest number set, so it's best kept to
+/// numbers that are all fairly small.
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    // bit `i` is bit `i % 64` of block `i / 64`, and any blocks past the last set bit may be zero
+    blocks: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty set with room for the numbers below `bits` before it has to grow.
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            blocks: Vec::with_capacity(bits.div_ceil(BLOCK_BITS)),
+        }
+    }
+
+    /// Adds `bit` to the set, returning whether it was missing.
+    pub fn set(&mut self, bit: usize) -> bool {
+        let (block, mask) = locate(bit);
+        if block >= self.blocks.len() {
+            self.blocks.resize(block + 1, 0);
+        }
+
+        let was_missing = self.blocks[block] & mask == 0;
+        self.blocks[block] |= mask;
+        was_missing
+    }
+
+    /// Takes `bit` out of the set, returning whether it was there.
+    pub fn clear(&mut self, bit: usize) -> bool {
+        let (block, mask) = locate(bit);
+        let Some(block) = self.blocks.get_mut(block) else {
+            return false;
+        };
+
+        let was_set = *block & mask != 0;
+        *block &= !mask;
+        was_set
+    }
+
+    /// Whether `bit` is in the set.
+    pub fn test(&self, bit: usize) -> bool {
+        let (block, mask) = locate(bit);
+        self.blocks
+            .get(block)
+            .is_some_and(|block| block & mask != 0)
+    }
+
+    /// How many numbers are in the set.
+    pub fn count_ones(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|&block| block == 0)
+    }
+
+    /// Whether every number in this set is also in `other`.
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.blocks
+            .iter()
+            .enumerate()
+            .all(|(idx, &block)| block & !other.blocks.get(idx).copied().unwrap_or(0) == 0)
+    }
+
+    /// Whether the sets have no numbers in common.
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.blocks
+            .iter()
+            .zip(&other.blocks)
+            .all(|(a, b)| a & b == 0)
+    }
+
+    /// The numbers in the set, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().enumerate().flat_map(|(idx, &block)| {
+            let mut left = block;
+            std::iter::from_fn(move || {
+                if left == 0 {
+                    return None;
+                }
+
+                let bit = left.trailing_zeros() as usize;
+                left &= left - 1;
+                Some(idx * BLOCK_BITS + bit)
+            })
+        })
+    }
+
+    /// Combines each block with the one at the same position in `other`, treating missing blocks
+    /// as empty.
+    fn combine_with(&mut self, other: &BitSet, op: fn(u64, u64) -> u64) {
+        if other.blocks.len() > self.blocks.len() {
+            self.blocks.resize(other.blocks.len(), 0);
+        }
+
+        for (idx, block) in self.blocks.iter_mut().enumerate() {
+            *block = op(*block, other.blocks.get(idx).copied().unwrap_or(0));
+        }
+    }
+}
+
+/// The block holding `bit`, and the mask picking it out of that block.
+fn locate(bit: usize) -> (usize, u64) {
+    (bit / BLOCK_BITS, 1 << (bit % BLOCK_BITS))
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        // trailing empty blocks don't change what's in the set
+        let longest = self.blocks.len().max(other.blocks.len());
+        (0..longest).all(|idx| {
+            self.blocks.get(idx).copied().unwrap_or(0)
+                == other.blocks.get(idx).copied().unwrap_or(0)
+        })
+    }
+}
+
+impl Eq for BitSet {}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for bit in iter {
+            set.set(bit);
+        }
+        set
+    }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a & b);
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a | b);
+    }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, other: &BitSet) {
+        self.combine_with(other, |a, b| a ^ b);
+    }
+}
+
+impl BitAnd for &BitSet {
+    type Output = BitSet;
+
+    fn bitand(self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set &= other;
+        set
+    }
+}
+
+impl BitOr for &BitSet {
+    type Output = BitSet;
+
+    fn bitor(self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set |= other;
+        set
+    }
+}
+
+impl BitXor for &BitSet {
+    type Output = BitSet;
+
+    fn bitxor(self, other: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set ^= other;
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn set_clear_and_test() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+
+        assert!(set.set(3));
+        assert!(set.set(200));
+        assert!(!set.set(3));
+        assert!(set.test(3));
+        assert!(set.test(200));
+        assert!(!set.test(4));
+        assert!(!set.test(10_000));
+        assert_eq!(set.count_ones(), 2);
+
+        assert!(set.clear(200));
+        assert!(!set.clear(200));
+        assert!(!set.clear(10_000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    fn equal_regardless_of_capacity() {
+        let mut grown: BitSet = [1, 500].into_iter().collect();
+        grown.clear(500);
+
+        assert_eq!(grown, [1].into_iter().collect());
+        assert_ne!(grown, BitSet::new());
+        assert!(!grown.is_empty());
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        let a: BitSet = [1, 2, 64, 100].into_iter().collect();
+        let b: BitSet = [2, 3, 100].into_iter().collect();
+
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), [2, 100]);
+        assert_eq!((&a | &b).iter().collect::<Vec<_>>(), [1, 2, 3, 64, 100]);
+        assert_eq!((&a ^ &b).iter().collect::<Vec<_>>(), [1, 3, 64]);
+
+        assert!((&a & &b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(!a.is_disjoint(&b));
+        assert!((&a ^ &b).is_disjoint(&(&a & &b)));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_btreeset(
+            a in vec(0..300_usize, 0..40),
+            b in vec(0..300_usize, 0..40),
+        ) {
+            let (set_a, set_b): (BitSet, BitSet) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+            let (tree_a, tree_b): (BTreeSet<usize>, BTreeSet<usize>) =
+                (a.into_iter().collect(), b.into_iter().collect());
+
+            prop_assert_eq!(set_a.count_ones(), tree_a.len());
+            prop_assert!((&set_a & &set_b).iter().eq(tree_a.intersection(&tree_b).copied()));
+            prop_assert!((&set_a | &set_b).iter().eq(tree_a.union(&tree_b).copied()));
+            prop_assert!(
+                (&set_a ^ &set_b).iter().eq(tree_a.symmetric_difference(&tree_b).copied())
+            );
+            prop_assert_eq!(set_a.is_subset(&set_b), tree_a.is_subset(&tree_b));
+            prop_assert_eq!(set_a.is_disjoint(&set_b), tree_a.is_disjoint(&tree_b));
+        }
+    }
+}
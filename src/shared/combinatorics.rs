@@ -0,0 +1,172 @@
+//! Going through orderings and selections of items, like every route through a handful of
+//! valves, or every pair of points to make a rectangle from.
+
+/// Rearranges `items` into the next ordering, in lexicographic order, returning true. When they're
+/// already in the last ordering, they're put back in the first (sorted) instead, returning false.
+///
+/// Repeated items are only rearranged among themselves once, so every distinct ordering comes up
+/// exactly once.
+pub fn next_permutation<T: Ord>(items: &mut [T]) -> bool {
+    // the longest run at the end which only goes down is already in its last ordering
+    let Some(pivot) = items.windows(2).rposition(|pair| pair[0] < pair[1]) else {
+        items.reverse();
+        return false;
+    };
+
+    // swap the item before that run with the next bigger item in the run, then restart the run
+    let successor = items
+        .iter()
+        .rposition(|item| *item > items[pivot])
+        .expect("the run has something bigger than the pivot");
+    items.swap(pivot, successor);
+    items[pivot + 1..].reverse();
+
+    true
+}
+
+/// Every distinct ordering of `items`, in lexicographic order. Repeated items don't repeat
+/// orderings, so `[1, 1, 2]` has three rather than six.
+pub fn multiset_permutations<T: Ord + Clone>(items: &[T]) -> impl Iterator<Item = Vec<T>> {
+    let mut next = items.to_vec();
+    next.sort_unstable();
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let current = next.clone();
+        done = !next_permutation(&mut next);
+        Some(current)
+    })
+}
+
+/// Every pair of different items, in the order they appear, without allocating.
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(move |(idx, first)| items[idx + 1..].iter().map(move |second| (first, second)))
+}
+
+/// Every way of choosing `k` of the items, keeping their order, with the selections themselves in
+/// lexicographic order of position.
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (k <= items.len()).then(|| (0..k).collect()),
+    }
+}
+
+/// See `combinations`.
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+
+    // the positions of the next selection, or None once they've run out
+    indices: Option<Vec<usize>>,
+}
+
+impl<'a, T> Iterator for Combinations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let selection = indices.iter().map(|&idx| &self.items[idx]).collect();
+
+        // move the last index which still has room along, and bunch the ones after it up behind it
+        let (n, k) = (self.items.len(), indices.len());
+        match (0..k).rev().find(|&pos| indices[pos] < n - k + pos) {
+            Some(pos) => {
+                indices[pos] += 1;
+                for after in pos + 1..k {
+                    indices[after] = indices[after - 1] + 1;
+                }
+            }
+            None => self.indices = None,
+        }
+
+        Some(selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_permutation_in_order() {
+        let mut items = [1, 2, 3];
+        let mut seen = vec![items];
+        while next_permutation(&mut items) {
+            seen.push(items);
+        }
+
+        assert_eq!(
+            seen,
+            [
+                [1, 2, 3],
+                [1, 3, 2],
+                [2, 1, 3],
+                [2, 3, 1],
+                [3, 1, 2],
+                [3, 2, 1]
+            ]
+        );
+        assert_eq!(items, [1, 2, 3], "wraps back around to the first");
+    }
+
+    #[test]
+    fn next_permutation_of_nothing() {
+        let mut items: [u32; 0] = [];
+        assert!(!next_permutation(&mut items));
+
+        let mut items = [7];
+        assert!(!next_permutation(&mut items));
+    }
+
+    #[test]
+    fn multiset_permutations_skip_repeats() {
+        let orderings: Vec<Vec<char>> = multiset_permutations(&['b', 'a', 'a']).collect();
+        assert_eq!(
+            orderings,
+            [
+                vec!['a', 'a', 'b'],
+                vec!['a', 'b', 'a'],
+                vec!['b', 'a', 'a']
+            ]
+        );
+
+        assert_eq!(multiset_permutations(&[1, 2, 3, 4]).count(), 24);
+        assert_eq!(multiset_permutations(&[1, 1, 2, 2]).count(), 6);
+    }
+
+    #[test]
+    fn pairs_of_items() {
+        let pairs: Vec<(&char, &char)> = pairs(&['a', 'b', 'c']).collect();
+        assert_eq!(pairs, [(&'a', &'b'), (&'a', &'c'), (&'b', &'c')]);
+
+        assert_eq!(super::pairs(&[1]).count(), 0);
+    }
+
+    #[test]
+    fn combinations_of_items() {
+        let chosen: Vec<Vec<&u32>> = combinations(&[1, 2, 3, 4], 2).collect();
+        assert_eq!(
+            chosen,
+            [
+                vec![&1, &2],
+                vec![&1, &3],
+                vec![&1, &4],
+                vec![&2, &3],
+                vec![&2, &4],
+                vec![&3, &4]
+            ]
+        );
+
+        assert_eq!(combinations(&[1, 2, 3, 4, 5], 3).count(), 10);
+        assert_eq!(combinations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 3).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+    }
+}
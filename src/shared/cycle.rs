@@ -0,0 +1,247 @@
+//! Finding where a simulation starts repeating itself, for puzzles like "what does the grid look
+//! like after 1,000,000,000 spins", which would take far too long to step through one at a time.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Where the states start going round in a loop, counted in steps from the initial state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// How many steps it takes to reach the first state which comes round again.
+    pub start: usize,
+
+    /// How many steps it takes to come back round to the same state.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state is the same as the one after `steps` steps.
+    pub fn equivalent_step(&self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+}
+
+/// Steps a simulation along one state at a time, remembering every state it's seen so it can
+/// skip ahead by whole cycles as soon as they start repeating.
+pub struct Simulator<S, F> {
+    step: F,
+
+    // every distinct state reached, indexed by the step it was first reached at
+    history: Vec<S>,
+    seen: HashMap<S, usize>,
+    cycle: Option<Cycle>,
+
+    // once there's a cycle this runs on past the end of the history, which it then wraps into
+    steps_taken: usize,
+}
+
+impl<S, F> Simulator<S, F>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    pub fn new(initial: S, step: F) -> Self {
+        Self {
+            step,
+            seen: HashMap::from([(initial.clone(), 0)]),
+            history: vec![initial],
+            cycle: None,
+            steps_taken: 0,
+        }
+    }
+
+    /// The state after every step taken so far.
+    pub fn state(&self) -> &S {
+        let step = match self.cycle {
+            Some(cycle) => cycle.equivalent_step(self.steps_taken),
+            None => self.steps_taken,
+        };
+
+        &self.history[step]
+    }
+
+    pub fn steps_taken(&self) -> usize {
+        self.steps_taken
+    }
+
+    /// The cycle, once the states have started repeating.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Takes `steps` more steps, returning the state after them. Once the states start repeating
+    /// the rest are skipped, so this is quick however many there are.
+    pub fn run_steps(&mut self, steps: usize) -> &S {
+        let mut left = steps;
+        while left > 0 && self.cycle.is_none() {
+            self.step_once();
+            left -= 1;
+        }
+
+        self.steps_taken += left;
+        self.state()
+    }
+
+    /// Steps until the states start repeating, returning the cycle. Never returns if they never
+    /// repeat.
+    pub fn find_cycle(&mut self) -> Cycle {
+        loop {
+            if let Some(cycle) = self.cycle {
+                return cycle;
+            }
+            self.step_once();
+        }
+    }
+
+    fn step_once(&mut self) {
+        if self.cycle.is_none() {
+            // with no cycle yet, the current state is always the last one in the history
+            let next = (self.step)(&self.history[self.steps_taken]);
+            match self.seen.get(&next) {
+                Some(&start) => {
+                    let length = self.history.len() - start;
+                    self.cycle = Some(Cycle { start, length });
+                }
+                None => {
+                    self.seen.insert(next.clone(), self.history.len());
+                    self.history.push(next);
+                }
+            }
+        }
+
+        self.steps_taken += 1;
+    }
+}
+
+/// Steps from `initial` until a state repeats, and returns where the repeating starts and how
+/// long it takes to come round. Never returns if the states never repeat.
+pub fn detect_cycle<S, F>(initial: S, step: F) -> Cycle
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    Simulator::new(initial, step).find_cycle()
+}
+
+/// The state after `steps` steps from `initial`, only stepping until the states start repeating,
+/// then skipping ahead by whole cycles.
+pub fn state_after<S, F>(initial: S, step: F, steps: usize) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    Simulator::new(initial, step).run_steps(steps).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_after_a_lead_in() {
+        // 0, 1, 2, 3, 4, 5, 6, then back round to 3
+        let next = |&n: &u32| if n == 6 { 3 } else { n + 1 };
+
+        let cycle = detect_cycle(0, next);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 3,
+                length: 4
+            }
+        );
+
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(7), 3);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+    }
+
+    #[test]
+    fn cycle_from_the_start() {
+        let cycle = detect_cycle(0_u32, |&n| (n + 1) % 5);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 5
+            }
+        );
+
+        let fixed = detect_cycle("same", |&s| s);
+        assert_eq!(
+            fixed,
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn state_after_many_steps() {
+        let next = |&n: &u64| (n * n + 1) % 1000;
+
+        let mut expected = 2;
+        for _ in 0..10_000 {
+            expected = next(&expected);
+        }
+
+        assert_eq!(state_after(2, next, 10_000), expected);
+        assert_eq!(state_after(2, next, 0), 2);
+        assert_eq!(state_after(2, next, 1), 5);
+    }
+
+    #[test]
+    fn state_after_without_repeating() {
+        // never repeats, but only has to go a few steps
+        assert_eq!(state_after(0_u64, |&n| n + 1, 10), 10);
+    }
+
+    #[test]
+    fn state_after_spinning_a_grid() {
+        // rotating a 2x2 grid a quarter turn comes back round after four turns
+        let rotate = |grid: &[[char; 2]; 2]| [[grid[1][0], grid[0][0]], [grid[1][1], grid[0][1]]];
+        let grid = [['a', 'b'], ['c', 'd']];
+
+        assert_eq!(state_after(grid, rotate, 1_000_000_001), rotate(&grid));
+    }
+
+    #[test]
+    fn simulator_runs_on_in_stages() {
+        // 0, 1, 2, 3, 4, 5, 6, then back round to 3
+        let mut simulator = Simulator::new(0_u32, |&n| if n == 6 { 3 } else { n + 1 });
+
+        assert_eq!(*simulator.run_steps(2), 2);
+        assert_eq!(simulator.cycle(), None);
+
+        assert_eq!(*simulator.run_steps(5), 3);
+        assert_eq!(
+            simulator.cycle(),
+            Some(Cycle {
+                start: 3,
+                length: 4
+            })
+        );
+
+        assert_eq!(*simulator.run_steps(1_000_000_000), 3);
+        assert_eq!(simulator.steps_taken(), 1_000_000_007);
+        assert_eq!(*simulator.run_steps(1), 4);
+    }
+
+    #[test]
+    fn simulator_only_steps_until_repeating() {
+        let mut calls = 0;
+        let mut simulator = Simulator::new(0_u32, |&n| {
+            calls += 1;
+            (n + 1) % 10
+        });
+
+        assert_eq!(*simulator.run_steps(1_000_005), 5);
+        assert_eq!(*simulator.state(), 5);
+        drop(simulator);
+        assert_eq!(calls, 10);
+    }
+}
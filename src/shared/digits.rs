@@ -0,0 +1,200 @@
+//! Working with the decimal digits of a number by arithmetic, rather than formatting it as a
+//! string and parsing it back, for puzzles about ids made of repeated digits and the like.
+
+/// How many digits `n` has. 0 has one digit.
+pub fn digit_count(n: u128) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// The digits of `n`, most significant first.
+pub fn split_digits(n: u64) -> Vec<u8> {
+    let mut digits = Vec::with_capacity(digit_count(n.into()) as usize);
+    let mut left = n;
+    loop {
+        digits.push((left % 10) as u8);
+        left /= 10;
+        if left == 0 {
+            break;
+        }
+    }
+
+    digits.reverse();
+    digits
+}
+
+/// The number made of `digits`, most significant first. The opposite of `split_digits`.
+pub fn from_digits<D: Into<u64>>(digits: impl IntoIterator<Item = D>) -> u64 {
+    digits
+        .into_iter()
+        .fold(0, |number, digit| number * 10 + digit.into())
+}
+
+/// The digits of `a` followed by those of `b`, so `concat_numbers(12, 345)` is 12345.
+pub fn concat_numbers(a: u64, b: u64) -> u64 {
+    a * 10_u64.pow(digit_count(b.into())) + b
+}
+
+/// Whether `n` is the same `block_len` digits repeated at least twice, like 123123 with blocks of
+/// 3, or 7777 with blocks of 1 or 2.
+pub fn is_repeated_block(n: u128, block_len: u32) -> bool {
+    let len = digit_count(n);
+    if block_len == 0 || !len.is_multiple_of(block_len) || len / block_len < 2 {
+        return false;
+    }
+
+    // n repeats its last block exactly when it's that block times 1, 0..01, 0..010..01, and so on
+    let shift = 10_u128.pow(block_len);
+    let block = n % shift;
+    let repeats = (1..len / block_len).fold(1, |ones: u128, _| ones * shift + 1);
+
+    // for the longest u128s, a big enough block repeated would be more than fits
+    block.checked_mul(repeats) == Some(n)
+}
+
+/// Whether `s` is the same `block_len` bytes repeated at least twice, for digits written out,
+/// which can run longer than any integer holds.
+pub fn is_repeated_block_str(s: &str, block_len: usize) -> bool {
+    let bytes = s.as_bytes();
+    if block_len == 0 || !bytes.len().is_multiple_of(block_len) || bytes.len() / block_len < 2 {
+        return false;
+    }
+
+    let block = &bytes[..block_len];
+    bytes.chunks(block_len).all(|chunk| chunk == block)
+}
+
+/// Which numbers count as made of a repeated block of digits.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RepdigitPattern {
+    /// One block written twice, like 6464 or 123123.
+    Twice,
+
+    /// One block written at least twice, like 6464, 111, or 121212.
+    AtLeastTwice,
+}
+
+impl RepdigitPattern {
+    /// The shortest block `n` repeats to fit the pattern, or None when it doesn't.
+    pub fn block_len(self, n: u128) -> Option<u32> {
+        let len = digit_count(n);
+        match self {
+            RepdigitPattern::Twice => {
+                (len.is_multiple_of(2) && is_repeated_block(n, len / 2)).then_some(len / 2)
+            }
+            RepdigitPattern::AtLeastTwice => {
+                (1..=len / 2).find(|&block_len| is_repeated_block(n, block_len))
+            }
+        }
+    }
+
+    pub fn matches(self, n: u128) -> bool {
+        self.block_len(n).is_some()
+    }
+
+    /// Whether the digits in `s` fit the pattern, however many there are.
+    pub fn matches_str(self, s: &str) -> bool {
+        let len = s.len();
+        match self {
+            RepdigitPattern::Twice => len.is_multiple_of(2) && is_repeated_block_str(s, len / 2),
+            RepdigitPattern::AtLeastTwice => {
+                (1..=len / 2).any(|block_len| is_repeated_block_str(s, block_len))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn count_digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(123_456), 6);
+        assert_eq!(digit_count(u64::MAX.into()), 20);
+        assert_eq!(digit_count(u128::MAX), 39);
+    }
+
+    #[test]
+    fn splitting_and_joining() {
+        assert_eq!(split_digits(0), [0]);
+        assert_eq!(split_digits(1203), [1, 2, 0, 3]);
+        assert_eq!(from_digits([1_u8, 2, 0, 3]), 1203);
+        assert_eq!(from_digits([9_u32, 8]), 98);
+        assert_eq!(from_digits(Vec::<u8>::new()), 0);
+
+        assert_eq!(concat_numbers(12, 345), 12345);
+        assert_eq!(concat_numbers(10, 0), 100);
+        assert_eq!(concat_numbers(0, 7), 7);
+    }
+
+    #[test]
+    fn repeated_blocks() {
+        assert!(is_repeated_block(123_123, 3));
+        assert!(is_repeated_block(7777, 1));
+        assert!(is_repeated_block(7777, 2));
+        assert!(is_repeated_block(1010, 2));
+
+        assert!(!is_repeated_block(7777, 3));
+        assert!(!is_repeated_block(7777, 4));
+        assert!(!is_repeated_block(123_124, 3));
+        assert!(!is_repeated_block(1001, 2));
+        assert!(!is_repeated_block(5, 1));
+        assert!(!is_repeated_block(55, 0));
+
+        // past what fits in a u64
+        let doubled = 1_234_567_890_123_456_789 * (10_u128.pow(19) + 1);
+        assert!(is_repeated_block(doubled, 19));
+        assert!(!is_repeated_block(doubled + 1, 19));
+    }
+
+    #[test]
+    fn repdigit_patterns() {
+        assert_eq!(RepdigitPattern::Twice.block_len(123_123), Some(3));
+        assert_eq!(RepdigitPattern::Twice.block_len(1111), Some(2));
+        assert_eq!(RepdigitPattern::Twice.block_len(111), None);
+        assert_eq!(RepdigitPattern::AtLeastTwice.block_len(111), Some(1));
+        assert_eq!(RepdigitPattern::AtLeastTwice.block_len(121_212), Some(2));
+        assert_eq!(RepdigitPattern::AtLeastTwice.block_len(7), None);
+
+        // far longer than a u128 holds
+        let long = "1234567890".repeat(6);
+        assert!(RepdigitPattern::Twice.matches_str(&long));
+        assert!(RepdigitPattern::AtLeastTwice.matches_str(&long));
+        assert!(!RepdigitPattern::AtLeastTwice.matches_str(&format!("{long}1")));
+
+        assert!(is_repeated_block_str("abab", 2));
+        assert!(!is_repeated_block_str("abab", 4));
+        assert!(!is_repeated_block_str("", 1));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_through_digits(n in any::<u64>()) {
+            prop_assert_eq!(from_digits(split_digits(n)), n);
+            prop_assert_eq!(split_digits(n).len() as u32, digit_count(n.into()));
+        }
+
+        #[test]
+        fn repeated_blocks_match_strings(n in 0..10_000_000_u128, block_len in 1..8_u32) {
+            let s = n.to_string();
+            let expected = s.len() % block_len as usize == 0
+                && s.len() / block_len as usize >= 2
+                && s.as_bytes().chunks(block_len as usize).all(|chunk| chunk == &s.as_bytes()[..block_len as usize]);
+
+            prop_assert_eq!(is_repeated_block(n, block_len), expected);
+            prop_assert_eq!(is_repeated_block_str(&s, block_len as usize), expected);
+        }
+
+        #[test]
+        fn patterns_match_strings(n in any::<u128>()) {
+            for pattern in [RepdigitPattern::Twice, RepdigitPattern::AtLeastTwice] {
+                prop_assert_eq!(pattern.matches(n), pattern.matches_str(&n.to_string()));
+            }
+        }
+    }
+}
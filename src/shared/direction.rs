@@ -0,0 +1,105 @@
+//! The four ways to step across a grid.
+
+use crate::error::AocError;
+
+/// A direction on a grid, where y grows downward like the rows of an input.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The direction a quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// The direction a quarter turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.reverse().turn_right()
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    /// The `(dx, dy)` of one step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+}
+
+/// Parses either letters or arrows, like `U` or `^`.
+impl std::str::FromStr for Direction {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" | "^" => Ok(Direction::Up),
+            "R" | ">" => Ok(Direction::Right),
+            "D" | "v" => Ok(Direction::Down),
+            "L" | "<" => Ok(Direction::Left),
+            _ => Err(AocError::Parse(format!(
+                "direction should be U, D, L, R, or an arrow: {s}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Right.reverse(), Direction::Left);
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+        }
+    }
+
+    #[test]
+    fn direction_offsets_undo_their_reverse() {
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.offset();
+            let (back_x, back_y) = direction.reverse().offset();
+            assert_eq!((dx + back_x, dy + back_y), (0, 0));
+        }
+        assert_eq!(Direction::Up.offset(), (0, -1));
+    }
+
+    #[test]
+    fn parse_direction() {
+        assert_eq!("U".parse(), Ok(Direction::Up));
+        assert_eq!("v".parse(), Ok(Direction::Down));
+        assert_eq!("<".parse(), Ok(Direction::Left));
+        assert_eq!("R".parse(), Ok(Direction::Right));
+        assert!("N".parse::<Direction>().is_err());
+    }
+}
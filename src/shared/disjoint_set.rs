@@ -0,0 +1,112 @@
+//! Union-find, for merging elements into sets and asking which set each one is in.
+
+/// Tracks which elements are connected, as elements `0..len` which start out in sets of their own.
+/// Finding an element's set compresses the path to its root, and unions hang the shallower tree
+/// under the deeper one, so both stay close to constant time.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    num_sets: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            num_sets: len,
+        }
+    }
+
+    /// Finds the root of the set containing `element`.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // point everything along the way directly at the root
+        let mut current = element;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+
+        root
+    }
+
+    /// Joins the sets containing `a` and `b`. Returns false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.num_sets -= 1;
+
+        true
+    }
+
+    /// How many elements are in the same set as `element`, including itself.
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    pub fn num_sets(&self) -> usize {
+        self.num_sets
+    }
+
+    /// The size of every set, in no particular order.
+    pub fn set_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.parents.len())
+            .filter(|&element| self.parents[element] == element)
+            .map(|root| self.sizes[root])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disjoint_set_starts_separate() {
+        let mut sets = DisjointSet::new(3);
+
+        assert_eq!(sets.num_sets(), 3);
+        assert_ne!(sets.find(0), sets.find(1));
+        assert_eq!(sets.set_size(2), 1);
+    }
+
+    #[test]
+    fn disjoint_set_unions() {
+        let mut sets = DisjointSet::new(6);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert_eq!(sets.find(0), sets.find(3));
+        assert_ne!(sets.find(0), sets.find(4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.num_sets(), 3);
+
+        let mut sizes: Vec<_> = sets.set_sizes().collect();
+        sizes.sort();
+        assert_eq!(sizes, [1, 1, 4]);
+    }
+}
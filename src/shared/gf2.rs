@@ -0,0 +1,240 @@
+//! Linear algebra over GF(2), where every value is a bit and adding is xor, for systems like
+//! "which switches flip these lights", where flipping twice is the same as not flipping at all.
+//!
+//! Each row of a matrix is a `BitSet`, with bit `col` set when the row has a 1 in that column.
+
+use super::BitSet;
+
+/// A matrix in reduced row echelon form, along with the column of each row's leading 1. Rows
+/// after the last pivot are all zeros, at least in the columns that were reduced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rref {
+    pub rows: Vec<BitSet>,
+    pub pivots: Vec<usize>,
+}
+
+/// Row reduces a matrix, only picking pivots from the first `cols` columns. Bits in any columns
+/// after those are carried along by the row operations without leading a row, which is how the
+/// constants in an augmented matrix stay lined up with their equations.
+pub fn rref(matrix: &[BitSet], cols: usize) -> Rref {
+    let mut rows = matrix.to_vec();
+    let mut pivots = Vec::new();
+
+    for col in 0..cols {
+        let r = pivots.len();
+        if r == rows.len() {
+            break;
+        }
+
+        // no row left has this column set, so it doesn't lead a row
+        let Some(pivot_row) = (r..rows.len()).find(|&i| rows[i].test(col)) else {
+            continue;
+        };
+        rows.swap(r, pivot_row);
+
+        // every value is already 0 or 1, so clearing the column only takes xoring the pivot row
+        let pivot = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != r && row.test(col) {
+                *row ^= &pivot;
+            }
+        }
+
+        pivots.push(col);
+    }
+
+    Rref { rows, pivots }
+}
+
+/// How many of the rows are independent of each other.
+pub fn rank(matrix: &[BitSet], cols: usize) -> usize {
+    rref(matrix, cols).pivots.len()
+}
+
+/// A basis for every `x` where `matrix * x` is all zeros. Every such `x` is some of these xored
+/// together.
+pub fn nullspace(matrix: &[BitSet], cols: usize) -> Vec<BitSet> {
+    nullspace_of_reduced(&rref(matrix, cols), cols)
+}
+
+/// Setting one free column, and each pivot column that row needs to cancel it out, gives one
+/// vector of the basis per free column.
+fn nullspace_of_reduced(reduced: &Rref, cols: usize) -> Vec<BitSet> {
+    (0..cols)
+        .filter(|col| !reduced.pivots.contains(col))
+        .map(|free| {
+            let mut vector: BitSet = [free].into_iter().collect();
+            for (row, &pivot) in reduced.rows.iter().zip(&reduced.pivots) {
+                if row.test(free) {
+                    vector.set(pivot);
+                }
+            }
+            vector
+        })
+        .collect()
+}
+
+/// Every solution to a system of equations over GF(2): `particular` xored with any combination of
+/// the `nullspace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// The solution with every free variable left as 0.
+    pub particular: BitSet,
+
+    /// One vector per free variable, any of which can be xored in without changing the result.
+    pub nullspace: Vec<BitSet>,
+}
+
+impl Solution {
+    /// Every solution, starting with `particular`. There are 2 to the power of the number of free
+    /// variables, so this is only practical while there are few of them.
+    pub fn solutions(&self) -> impl Iterator<Item = BitSet> + '_ {
+        assert!(
+            self.nullspace.len() < usize::BITS as usize,
+            "too many free variables to go through every solution"
+        );
+
+        (0..1_usize << self.nullspace.len()).map(|chosen| {
+            let mut solution = self.particular.clone();
+            for (idx, vector) in self.nullspace.iter().enumerate() {
+                if chosen & (1 << idx) != 0 {
+                    solution ^= vector;
+                }
+            }
+            solution
+        })
+    }
+}
+
+/// Solves `a * x = b` for `x`, where `a` has `cols` columns. None when there's no solution.
+pub fn solve(a: &[BitSet], cols: usize, b: &[bool]) -> Option<Solution> {
+    assert_eq!(a.len(), b.len(), "each equation should have a constant");
+
+    // the constants go in one extra column, past the ones that get pivots
+    let augmented: Vec<BitSet> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &constant)| {
+            let mut row = row.clone();
+            if constant {
+                row.set(cols);
+            } else {
+                row.clear(cols);
+            }
+            row
+        })
+        .collect();
+    let reduced = rref(&augmented, cols);
+
+    // a row with nothing left but its constant says 0 = 1
+    if reduced.rows[reduced.pivots.len()..]
+        .iter()
+        .any(|row| row.test(cols))
+    {
+        return None;
+    }
+
+    let particular = reduced
+        .rows
+        .iter()
+        .zip(&reduced.pivots)
+        .filter(|(row, _)| row.test(cols))
+        .map(|(_, &pivot)| pivot)
+        .collect();
+    let nullspace = nullspace_of_reduced(&reduced, cols);
+
+    Some(Solution {
+        particular,
+        nullspace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    fn matrix(rows: &[&[usize]]) -> Vec<BitSet> {
+        rows.iter()
+            .map(|cols| cols.iter().copied().collect())
+            .collect()
+    }
+
+    /// `matrix * x`, one bit per row.
+    fn multiply(matrix: &[BitSet], x: &BitSet) -> Vec<bool> {
+        matrix
+            .iter()
+            .map(|row| (row & x).count_ones() % 2 == 1)
+            .collect()
+    }
+
+    #[test]
+    fn reduces_and_ranks() {
+        // the third row is the first two xored together
+        let m = matrix(&[&[0, 1], &[1, 2], &[0, 2]]);
+        let reduced = rref(&m, 3);
+
+        assert_eq!(reduced.pivots, [0, 1]);
+        assert_eq!(reduced.rows, matrix(&[&[0, 2], &[1, 2], &[]]));
+        assert_eq!(rank(&m, 3), 2);
+
+        let basis = nullspace(&m, 3);
+        assert_eq!(basis, matrix(&[&[0, 1, 2]]));
+        assert_eq!(multiply(&m, &basis[0]), [false, false, false]);
+    }
+
+    #[test]
+    fn solves_lights() {
+        // the example machine [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1), as which buttons flip each
+        // light, where the fewest presses is 2
+        let lights = matrix(&[&[4, 5], &[1, 5], &[2, 3, 4], &[0, 1, 3]]);
+        let target = [false, true, true, false];
+        let solution = solve(&lights, 6, &target).unwrap();
+
+        assert_eq!(solution.nullspace.len(), 2);
+        for x in solution.solutions() {
+            assert_eq!(multiply(&lights, &x), target);
+        }
+        assert_eq!(solution.solutions().map(|x| x.count_ones()).min(), Some(2));
+    }
+
+    #[test]
+    fn no_solution() {
+        // both rows are the same, but want different answers
+        let m = matrix(&[&[0, 1], &[0, 1]]);
+        assert_eq!(solve(&m, 2, &[true, false]), None);
+        assert!(solve(&m, 2, &[true, true]).is_some());
+    }
+
+    proptest! {
+        #[test]
+        fn every_solution_is_found(
+            rows in vec(vec(any::<bool>(), 6), 1..6),
+            target in vec(any::<bool>(), 6),
+        ) {
+            let m: Vec<BitSet> = rows
+                .iter()
+                .map(|row| (0..6).filter(|&col| row[col]).collect())
+                .collect();
+            let target = &target[..m.len()];
+
+            // every x, checked by hand
+            let expected: Vec<BitSet> = (0..1_usize << 6)
+                .map(|x| (0..6).filter(|&col| x & (1 << col) != 0).collect::<BitSet>())
+                .filter(|x| multiply(&m, x) == target)
+                .collect();
+
+            match solve(&m, 6, target) {
+                None => prop_assert!(expected.is_empty()),
+                Some(solution) => {
+                    let mut found: Vec<BitSet> = solution.solutions().collect();
+                    found.sort_by_key(|x| x.iter().collect::<Vec<_>>());
+                    let mut expected = expected;
+                    expected.sort_by_key(|x| x.iter().collect::<Vec<_>>());
+                    prop_assert_eq!(found, expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,417 @@
+//! A graph of labelled nodes, for puzzles which list each node with the ones it connects to, like
+//! `aaa: bbb ccc`. Labels are swapped for indices as they're added, so walking the graph is just
+//! indexing into lists.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{floyd_warshall, parse};
+use crate::error::AocError;
+
+/// Nodes numbered from 0 in the order their labels were first seen, each with a list of the
+/// nodes it has edges to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    directed: bool,
+
+    labels: Vec<String>,
+    ids: HashMap<String, usize>,
+
+    // an undirected edge is listed on both of its ends
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// An empty graph where edges only go one way.
+    pub fn directed() -> Self {
+        Self::empty(true)
+    }
+
+    /// An empty graph where edges go both ways.
+    pub fn undirected() -> Self {
+        Self::empty(false)
+    }
+
+    fn empty(directed: bool) -> Self {
+        Self {
+            directed,
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    /// Parses a node per line, like `aaa: bbb ccc`, with edges from each node to the ones after
+    /// the colon. Nodes only mentioned after a colon are added too, with no edges of their own.
+    pub fn parse_directed(input: &str) -> Result<Self, AocError> {
+        Self::directed().with_adjacency(input)
+    }
+
+    /// Parses a node per line like `parse_directed`, with each edge going both ways.
+    pub fn parse_undirected(input: &str) -> Result<Self, AocError> {
+        Self::undirected().with_adjacency(input)
+    }
+
+    fn with_adjacency(mut self, input: &str) -> Result<Self, AocError> {
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (label, neighbors) = parse::labelled(line).ok_or_else(|| {
+                AocError::Parse(format!("node should be like aaa: bbb ccc: {line}"))
+            })?;
+
+            let from = self.add_node(label.trim());
+            for neighbor in neighbors.split_whitespace() {
+                let to = self.add_node(neighbor);
+                self.add_edge(from, to);
+            }
+        }
+
+        Ok(self)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// How many nodes there are.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The node with `label`, adding it when there isn't one yet.
+    pub fn add_node(&mut self, label: &str) -> usize {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+
+        let id = self.labels.len();
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    /// Adds an edge from `from` to `to`, and back again when the graph is undirected. Panics when
+    /// either node doesn't exist.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        assert!(to < self.len(), "{to} is not a node");
+
+        self.adjacency[from].push(to);
+        if !self.directed && from != to {
+            self.adjacency[to].push(from);
+        }
+    }
+
+    /// The node with `label`, or None when there isn't one.
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    /// The label of node `id`. Panics when there isn't one.
+    pub fn label(&self, id: usize) -> &str {
+        &self.labels[id]
+    }
+
+    /// The nodes `id` has edges to, in the order they were added.
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        &self.adjacency[id]
+    }
+
+    /// Every edge as `(from, to)`. Undirected edges only come up once, with the lower node first.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(from, neighbors)| neighbors.iter().map(move |&to| (from, to)))
+            .filter(|&(from, to)| self.directed || from <= to)
+    }
+
+    /// How many edges it takes to get between every pair of nodes, as `distances[from][to]`. None
+    /// where `to` can't be reached. See `floyd_warshall`, which this uses.
+    pub fn all_distances(&self) -> Vec<Vec<Option<usize>>> {
+        let edges = self
+            .adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(from, neighbors)| neighbors.iter().map(move |&to| (from, to, 1)));
+
+        floyd_warshall(self.len(), edges)
+    }
+
+    /// The fewest edges which need cutting so nothing can get from `source` to `sink`, by Dinic's
+    /// algorithm. Every edge carries one unit of flow, so this is also the maximum flow between
+    /// them. Panics when `source` and `sink` are the same node.
+    pub fn min_cut(&self, source: usize, sink: usize) -> MinCut {
+        assert_ne!(
+            source, sink,
+            "the source and sink should be different nodes"
+        );
+
+        let mut network = Network::new(self);
+        let mut flow = 0;
+        while let Some(levels) = network.levels(source, sink) {
+            // each node's next arc left to try, so dead ends aren't tried again this round
+            let mut next_arc = vec![0; self.len()];
+            while network.augment(source, sink, &levels, &mut next_arc) {
+                flow += 1;
+            }
+        }
+
+        // whatever the source can still reach is its side of the cut
+        let reachable = network.reachable(source);
+        let edges = self
+            .edges()
+            .filter(|&(from, to)| reachable[from] != reachable[to])
+            .filter(|&(from, _)| !self.directed || reachable[from])
+            .collect();
+        let source_side = (0..self.len()).filter(|&node| reachable[node]).collect();
+
+        MinCut {
+            flow,
+            edges,
+            source_side,
+        }
+    }
+}
+
+/// The edges `Graph::min_cut` found to cut, and what they split off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// How many edges are cut, which is also the maximum flow.
+    pub flow: usize,
+
+    /// The edges cut, as `(from, to)` in the same form `Graph::edges` gives them.
+    pub edges: Vec<(usize, usize)>,
+
+    /// The nodes still reachable from the source after the cut, in order.
+    pub source_side: Vec<usize>,
+}
+
+/// The residual network for `min_cut`, where every arc is stored next to its reverse, so arc `i`
+/// and arc `i ^ 1` undo each other.
+struct Network {
+    // the arcs leaving each node, as indices into `to` and `capacity`
+    arcs: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    capacity: Vec<usize>,
+}
+
+impl Network {
+    fn new(graph: &Graph) -> Self {
+        let mut network = Self {
+            arcs: vec![Vec::new(); graph.len()],
+            to: Vec::new(),
+            capacity: Vec::new(),
+        };
+
+        for (from, to) in graph.edges() {
+            // an undirected edge can carry its unit either way
+            let back = if graph.directed { 0 } else { 1 };
+            network.add_arc(from, to, 1);
+            network.add_arc(to, from, back);
+        }
+
+        network
+    }
+
+    fn add_arc(&mut self, from: usize, to: usize, capacity: usize) {
+        self.arcs[from].push(self.to.len());
+        self.to.push(to);
+        self.capacity.push(capacity);
+    }
+
+    /// How many arcs with capacity left it takes to reach each node from `source`, or None when
+    /// `sink` can't be reached any more.
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<Option<usize>>> {
+        let mut levels = vec![None; self.arcs.len()];
+        levels[source] = Some(0);
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &arc in &self.arcs[node] {
+                let next = self.to[arc];
+                if self.capacity[arc] > 0 && levels[next].is_none() {
+                    levels[next] = levels[node].map(|level| level + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        levels[sink].is_some().then_some(levels)
+    }
+
+    /// Pushes a unit of flow from `node` to `sink`, only ever going one level further on. Returns
+    /// whether it found a way through.
+    fn augment(
+        &mut self,
+        node: usize,
+        sink: usize,
+        levels: &[Option<usize>],
+        next_arc: &mut [usize],
+    ) -> bool {
+        if node == sink {
+            return true;
+        }
+
+        while next_arc[node] < self.arcs[node].len() {
+            let arc = self.arcs[node][next_arc[node]];
+            let next = self.to[arc];
+            let deeper = levels[next] == levels[node].map(|level| level + 1);
+            if self.capacity[arc] > 0 && deeper && self.augment(next, sink, levels, next_arc) {
+                self.capacity[arc] -= 1;
+                self.capacity[arc ^ 1] += 1;
+                return true;
+            }
+
+            next_arc[node] += 1;
+        }
+
+        false
+    }
+
+    /// Which nodes `source` can reach along arcs with capacity left.
+    fn reachable(&self, source: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.arcs.len()];
+        reachable[source] = true;
+
+        let mut to_visit = vec![source];
+        while let Some(node) = to_visit.pop() {
+            for &arc in &self.arcs[node] {
+                let next = self.to[arc];
+                if self.capacity[arc] > 0 && !reachable[next] {
+                    reachable[next] = true;
+                    to_visit.push(next);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "aaa: bbb ccc\nbbb: ccc\n\nddd: aaa";
+
+    fn labelled_neighbors<'a>(graph: &'a Graph, label: &str) -> Vec<&'a str> {
+        let id = graph.id(label).unwrap();
+        graph
+            .neighbors(id)
+            .iter()
+            .map(|&neighbor| graph.label(neighbor))
+            .collect()
+    }
+
+    #[test]
+    fn parse_directed() {
+        let graph = Graph::parse_directed(INPUT).unwrap();
+
+        assert!(graph.is_directed());
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.label(2), "ccc");
+        assert_eq!(labelled_neighbors(&graph, "aaa"), ["bbb", "ccc"]);
+        assert_eq!(labelled_neighbors(&graph, "ccc"), Vec::<&str>::new());
+        assert_eq!(graph.edges().count(), 4);
+        assert_eq!(graph.id("eee"), None);
+    }
+
+    #[test]
+    fn parse_undirected() {
+        let graph = Graph::parse_undirected(INPUT).unwrap();
+
+        assert!(!graph.is_directed());
+        assert_eq!(labelled_neighbors(&graph, "aaa"), ["bbb", "ccc", "ddd"]);
+        assert_eq!(labelled_neighbors(&graph, "ccc"), ["aaa", "bbb"]);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            [(0, 1), (0, 2), (0, 3), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn parse_malformed() {
+        assert!(Graph::parse_directed("aaa bbb").is_err());
+        assert!(Graph::parse_directed("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn building_by_hand() {
+        let mut graph = Graph::undirected();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        assert_eq!(graph.add_node("a"), a);
+
+        graph.add_edge(a, b);
+        graph.add_edge(a, a);
+        assert_eq!(graph.neighbors(a), [b, a]);
+        assert_eq!(graph.neighbors(b), [a]);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), [(a, b), (a, a)]);
+    }
+
+    #[test]
+    fn all_distances() {
+        let directed = Graph::parse_directed(INPUT).unwrap();
+        let distances = directed.all_distances();
+        assert_eq!(distances[0], [Some(0), Some(1), Some(1), None]);
+        assert_eq!(distances[3], [Some(1), Some(2), Some(2), Some(0)]);
+
+        let undirected = Graph::parse_undirected(INPUT).unwrap();
+        assert_eq!(
+            undirected.all_distances()[2],
+            [Some(1), Some(1), Some(0), Some(2)]
+        );
+    }
+
+    #[test]
+    fn min_cut_three_wires() {
+        let graph = Graph::parse_undirected(
+            "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\n\
+             rhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\nntq: jqt hfx bvb xhk\n\
+             nvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\nfrs: qnr lhk lsr",
+        )
+        .unwrap();
+        let node = |label| graph.id(label).unwrap();
+
+        let cut = graph.min_cut(node("jqt"), node("cmg"));
+        assert_eq!(cut.flow, 3);
+        assert_eq!(
+            cut.source_side.len() * (graph.len() - cut.source_side.len()),
+            54
+        );
+
+        let mut wires: Vec<(&str, &str)> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let (a, b) = (graph.label(a), graph.label(b));
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        wires.sort_unstable();
+        assert_eq!(wires, [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+
+        // on the same side of those wires, there are more ways between them
+        let cut = graph.min_cut(node("jqt"), node("xhk"));
+        assert_eq!(cut.flow, 4);
+    }
+
+    #[test]
+    fn min_cut_directed() {
+        // only two edges leave s, and everything else can carry both units on to t
+        let graph = Graph::parse_directed("s: a b\na: b\nb: t c\nc: t\nt: s").unwrap();
+        let node = |label| graph.id(label).unwrap();
+
+        let cut = graph.min_cut(node("s"), node("t"));
+        assert_eq!(cut.flow, 2);
+        assert_eq!(cut.source_side, [node("s")]);
+        assert_eq!(cut.edges, [(node("s"), node("a")), (node("s"), node("b"))]);
+
+        // nothing goes back the other way except the one edge
+        assert_eq!(graph.min_cut(node("t"), node("s")).flow, 1);
+        assert_eq!(graph.min_cut(node("c"), node("a")).flow, 1);
+    }
+}
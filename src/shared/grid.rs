@@ -0,0 +1,448 @@
+//! A rectangular grid of cells, the shape most puzzle inputs come in, along with flood fills for
+//! finding the regions of like cells in one.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use super::{Direction, Neighborator};
+use crate::error::AocError;
+
+/// A rectangular grid of cells, indexed by `(x, y)` with `(0, 0)` at the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    // row by row, so each row is a contiguous slice
+    cells: Vec<T>,
+
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Parses a grid with a row per line, mapping each char to a cell. Chars the mapping rejects
+    /// are errors, and so are empty grids or rows of different widths. Blank lines at the end are
+    /// skipped, since they aren't rows.
+    pub fn from_str(s: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, AocError> {
+        let mut lines: Vec<&str> = s.lines().collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(AocError::Parse("grid should not be empty".to_string()));
+        }
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            if line.chars().count() != width {
+                return Err(AocError::Parse(format!(
+                    "every row should be {width} wide: {line}"
+                )));
+            }
+            for c in line.chars() {
+                cells.push(
+                    cell(c).ok_or_else(|| AocError::Parse(format!("unexpected character: {c}")))?,
+                );
+            }
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height: lines.len(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The `(width, height)` of the grid.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The cell at `(x, y)`, or None when that's out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Replaces the cell at `(x, y)`. Panics when that's out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self[(x, y)] = value;
+    }
+
+    /// Every cell along with its `(x, y)`, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| ((idx % self.width, idx / self.width), cell))
+    }
+
+    /// Each row, from top to bottom. A grid with no width still has its rows, which are empty.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Up to eight in bounds neighbors of `(x, y)`.
+    pub fn neighbors(&self, x: usize, y: usize) -> Neighborator {
+        Neighborator::new((x, y), self.dimensions())
+    }
+
+    /// Up to four in bounds neighbors of `(x, y)`, leaving out the diagonals.
+    pub fn orthogonal_neighbors(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = self.dimensions();
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            let (dx, dy) = direction.offset();
+            let x = x.checked_add_signed(dx as isize).filter(|&x| x < width)?;
+            let y = y.checked_add_signed(dy as isize).filter(|&y| y < height)?;
+            Some((x, y))
+        })
+    }
+
+    /// A grid of the same size, with each cell mapped to something else.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// A new `width` by `height` grid, where each cell is copied from the `(x, y)` that `source`
+    /// gives for it.
+    fn rearranged(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// The grid turned a quarter turn clockwise, so the left column becomes the top row.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let bottom = self.height.saturating_sub(1);
+        self.rearranged(self.height, self.width, |x, y| (y, bottom - x))
+    }
+
+    /// The grid turned a quarter turn anticlockwise, so the right column becomes the top row.
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let right = self.width.saturating_sub(1);
+        self.rearranged(self.height, self.width, |x, y| (right - y, x))
+    }
+
+    /// The grid flipped over its top left to bottom right diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearranged(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_h(&self) -> Self
+    where
+        T: Clone,
+    {
+        let right = self.width.saturating_sub(1);
+        self.rearranged(self.width, self.height, |x, y| (right - x, y))
+    }
+
+    /// The grid mirrored top to bottom.
+    pub fn flip_v(&self) -> Self
+    where
+        T: Clone,
+    {
+        let bottom = self.height.saturating_sub(1);
+        self.rearranged(self.width, self.height, |x, y| (x, bottom - y))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Draws the grid with a line per row, like puzzle inputs.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every cell reachable from `start` by stepping up, down, left, or right through passable cells,
+/// including `start` itself. Empty when `start` is out of bounds or isn't passable.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    passable: impl Fn(&T) -> bool,
+) -> HashSet<(usize, usize)> {
+    let mut filled = HashSet::new();
+    if !grid.get(start.0, start.1).is_some_and(&passable) {
+        return filled;
+    }
+
+    filled.insert(start);
+    let mut to_visit = vec![start];
+    while let Some((x, y)) = to_visit.pop() {
+        for neighbor in grid.orthogonal_neighbors(x, y) {
+            if passable(&grid[neighbor]) && filled.insert(neighbor) {
+                to_visit.push(neighbor);
+            }
+        }
+    }
+
+    filled
+}
+
+/// The connected areas of passable cells in a grid, as found by `label_regions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// Which region each cell is in, numbered in the order they're found, row by row. Impassable
+    /// cells aren't in any.
+    pub labels: Grid<Option<usize>>,
+
+    /// How many cells are in each region, by number.
+    pub sizes: Vec<usize>,
+}
+
+/// Splits the passable cells into regions, where cells are in the same region when a flood fill
+/// from one reaches the other.
+pub fn label_regions<T>(grid: &Grid<T>, passable: impl Fn(&T) -> bool) -> Regions {
+    let mut labels = grid.map(|_| None);
+    let mut sizes = Vec::new();
+
+    for (start, cell) in grid.cells() {
+        if labels[start].is_some() || !passable(cell) {
+            continue;
+        }
+
+        let region = flood_fill(grid, start, &passable);
+        for &point in &region {
+            labels[point] = Some(sizes.len());
+        }
+        sizes.push(region.len());
+    }
+
+    Regions { labels, sizes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::from_str("ab.\n.cd", Some).unwrap()
+    }
+
+    fn rooms() -> Grid<char> {
+        Grid::from_str("..#..\n..#.#\n###..\n#...#", Some).unwrap()
+    }
+
+    #[test]
+    fn orthogonal_neighbors() {
+        let grid = grid();
+        let v: Vec<(usize, usize)> = grid.orthogonal_neighbors(1, 0).collect();
+        assert_eq!(v.len(), 3);
+        assert!(v.contains(&(0, 0)));
+        assert!(v.contains(&(2, 0)));
+        assert!(v.contains(&(1, 1)));
+        assert!(!v.contains(&(0, 1)));
+    }
+
+    #[test]
+    fn flood_fill_stays_inside_walls() {
+        let grid = rooms();
+        let filled = flood_fill(&grid, (0, 0), |&c| c == '.');
+        assert_eq!(filled, HashSet::from([(0, 0), (1, 0), (0, 1), (1, 1)]));
+
+        let filled = flood_fill(&grid, (3, 1), |&c| c == '.');
+        assert_eq!(filled.len(), 8);
+        assert!(filled.contains(&(4, 0)));
+
+        // diagonal steps don't count
+        let checkers = Grid::from_str(".#\n#.", Some).unwrap();
+        assert_eq!(flood_fill(&checkers, (0, 0), |&c| c == '.').len(), 1);
+    }
+
+    #[test]
+    fn flood_fill_from_a_wall_is_empty() {
+        let grid = rooms();
+        assert!(flood_fill(&grid, (2, 0), |&c| c == '.').is_empty());
+        assert!(flood_fill(&grid, (9, 9), |&c| c == '.').is_empty());
+    }
+
+    #[test]
+    fn label_regions_numbers_each_area() {
+        let grid = rooms();
+        let regions = label_regions(&grid, |&c| c == '.');
+
+        assert_eq!(regions.sizes, [4, 8]);
+        assert_eq!(regions.labels[(0, 0)], Some(0));
+        assert_eq!(regions.labels[(3, 0)], Some(1));
+        assert_eq!(regions.labels[(1, 3)], Some(1));
+        assert_eq!(regions.labels[(2, 0)], None);
+    }
+
+    #[test]
+    fn grid_from_str() {
+        let grid = grid();
+
+        assert_eq!(grid.dimensions(), (3, 2));
+        assert_eq!(grid[(1, 0)], 'b');
+        assert_eq!(grid[(2, 1)], 'd');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+
+    #[test]
+    fn grid_from_malformed_str() {
+        let digit = |c: char| c.to_digit(10);
+
+        assert!(Grid::from_str("12\n3", digit).is_err());
+        assert!(Grid::from_str("12\n3x", digit).is_err());
+        assert!(Grid::from_str("", digit).is_err());
+        assert!(Grid::from_str("\n\n", digit).is_err());
+        assert!(Grid::from_str("12\n\n34", digit).is_err());
+        assert_eq!(Grid::from_str("12\n34", digit).unwrap()[(0, 1)], 3);
+
+        // blank lines at the end aren't rows
+        let grid = Grid::from_str("12\n34\n\n\n", digit).unwrap();
+        assert_eq!(grid.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn grid_set() {
+        let mut grid = Grid::new(2, 2, 0);
+        grid.set(1, 0, 5);
+        grid[(0, 1)] += 2;
+
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[0, 5], [2, 0]]);
+    }
+
+    #[test]
+    fn rows_of_a_grid_with_no_width() {
+        let grid = Grid::new(0, 3, 0);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[]; 3]);
+        assert_eq!(grid.to_string(), "\n\n\n");
+    }
+
+    #[test]
+    fn grid_cells_are_row_by_row() {
+        let letters: Vec<((usize, usize), char)> = grid()
+            .cells()
+            .filter(|(_, c)| c.is_alphabetic())
+            .map(|(at, &c)| (at, c))
+            .collect();
+
+        assert_eq!(
+            letters,
+            [((0, 0), 'a'), ((1, 0), 'b'), ((1, 1), 'c'), ((2, 1), 'd')]
+        );
+    }
+
+    #[test]
+    fn grid_display() {
+        assert_eq!(grid().to_string(), "ab.\n.cd\n");
+        assert_eq!(
+            grid()
+                .map(|c| if *c == '.' { '#' } else { '.' })
+                .to_string(),
+            "..#\n#..\n"
+        );
+    }
+
+    #[test]
+    fn grid_rotations() {
+        // ab.
+        // .cd
+        assert_eq!(grid().rotate_cw().to_string(), ".a\ncb\nd.\n");
+        assert_eq!(grid().rotate_ccw().to_string(), ".d\nbc\na.\n");
+        assert_eq!(grid().rotate_cw().rotate_ccw(), grid());
+
+        let turned_twice = grid().rotate_cw().rotate_cw();
+        assert_eq!(turned_twice.to_string(), "dc.\n.ba\n");
+        assert_eq!(turned_twice, grid().flip_h().flip_v());
+    }
+
+    #[test]
+    fn grid_reflections() {
+        assert_eq!(grid().transpose().to_string(), "a.\nbc\n.d\n");
+        assert_eq!(grid().flip_h().to_string(), ".ba\ndc.\n");
+        assert_eq!(grid().flip_v().to_string(), ".cd\nab.\n");
+        assert_eq!(grid().transpose().transpose(), grid());
+        assert_eq!(grid().transpose().flip_h(), grid().rotate_cw());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn grid_index_out_of_bounds() {
+        let _ = grid()[(3, 0)];
+    }
+}
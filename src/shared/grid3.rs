@@ -0,0 +1,406 @@
+//! A grid with a third dimension, for puzzles about blocks stacked in space, and the neighbors of a
+//! cell in it counted either by face or by every touching cell.
+
+use std::{
+    collections::HashSet,
+    ops::{Index, IndexMut},
+};
+
+/// Which cells around a 3D location count as its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// The six cells sharing a face.
+    Faces,
+
+    /// All 26 cells touching it, including along edges and at corners.
+    All,
+}
+
+const FACE_DELTAS_3: [(i32, i32, i32); 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+// every offset in the surrounding 3x3x3 cube, skipping the center
+const ALL_DELTAS_3: [(i32, i32, i32); 26] = {
+    let mut deltas = [(0, 0, 0); 26];
+    let (mut cube, mut index) = (0, 0);
+    while cube < 27 {
+        if cube != 13 {
+            deltas[index] = (cube / 9 - 1, cube / 3 % 3 - 1, cube % 3 - 1);
+            index += 1;
+        }
+        cube += 1;
+    }
+    deltas
+};
+
+/// Given a location (x, y, z) and limits, returns up to 6 or 26 neighbors which are in bounds,
+/// depending on the connectivity.
+pub struct Neighborator3 {
+    center: (usize, usize, usize),
+    dimensions: (usize, usize, usize),
+    deltas: &'static [(i32, i32, i32)],
+
+    index: usize,
+}
+
+impl Neighborator3 {
+    pub fn new(
+        center: (usize, usize, usize),
+        dimensions: (usize, usize, usize),
+        connectivity: Connectivity,
+    ) -> Self {
+        let deltas: &[(i32, i32, i32)] = match connectivity {
+            Connectivity::Faces => &FACE_DELTAS_3,
+            Connectivity::All => &ALL_DELTAS_3,
+        };
+
+        Self {
+            center,
+            dimensions,
+            deltas,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for Neighborator3 {
+    type Item = (usize, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // steps one coordinate by a delta, if it stays within 0..limit
+        let step = |coord: usize, delta: i32, limit: usize| {
+            coord
+                .checked_add_signed(delta as isize)
+                .filter(|&stepped| stepped < limit)
+        };
+
+        while let Some(&(dx, dy, dz)) = self.deltas.get(self.index) {
+            self.index += 1;
+
+            let (x, y, z) = self.center;
+            let (width, height, depth) = self.dimensions;
+            if let (Some(x), Some(y), Some(z)) =
+                (step(x, dx, width), step(y, dy, height), step(z, dz, depth))
+            {
+                return Some((x, y, z));
+            }
+        }
+
+        None
+    }
+}
+
+/// A box of cells in three dimensions, indexed by `(x, y, z)` from `(0, 0, 0)`, like the cubes of
+/// a lava droplet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid3<T> {
+    // layer by layer, then row by row within each layer
+    cells: Vec<T>,
+
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+type Cell3 = (usize, usize, usize);
+
+impl<T> Grid3<T> {
+    /// Creates a grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height * depth],
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// The `(width, height, depth)` of the grid.
+    pub fn dimensions(&self) -> (usize, usize, usize) {
+        (self.width, self.height, self.depth)
+    }
+
+    fn index_of(&self, (x, y, z): Cell3) -> Option<usize> {
+        (x < self.width && y < self.height && z < self.depth)
+            .then(|| (z * self.height + y) * self.width + x)
+    }
+
+    /// The cell at `(x, y, z)`, or None when that's out of bounds.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&T> {
+        self.index_of((x, y, z)).map(|idx| &self.cells[idx])
+    }
+
+    /// Replaces the cell at `(x, y, z)`. Panics when that's out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, value: T) {
+        self[(x, y, z)] = value;
+    }
+
+    /// Every cell along with its `(x, y, z)`, layer by layer.
+    pub fn cells(&self) -> impl Iterator<Item = (Cell3, &T)> {
+        let (width, height) = (self.width, self.height);
+        self.cells.iter().enumerate().map(move |(idx, cell)| {
+            let (x, y, z) = (idx % width, idx / width % height, idx / (width * height));
+            ((x, y, z), cell)
+        })
+    }
+
+    /// The in bounds neighbors of `(x, y, z)`, either across faces only or all the way round.
+    pub fn neighbors(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+        connectivity: Connectivity,
+    ) -> Neighborator3 {
+        Neighborator3::new((x, y, z), self.dimensions(), connectivity)
+    }
+
+    /// A grid of the same size, with each cell mapped to something else.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid3<U> {
+        Grid3 {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+        }
+    }
+
+    /// How many faces of the solid cells don't touch another solid cell, counting the faces on the
+    /// outside of the grid.
+    pub fn surface_area(&self, solid: impl Fn(&T) -> bool) -> usize {
+        self.exposed_faces(&solid, |_| true)
+    }
+
+    /// Like `surface_area`, but only counting the faces which can be reached from outside, so
+    /// pockets of air sealed inside don't count.
+    pub fn exterior_surface_area(&self, solid: impl Fn(&T) -> bool) -> usize {
+        // the outside is whatever air can be reached from the air around the edges of the grid
+        let mut outside = self.map(|_| false);
+        let mut to_visit: Vec<Cell3> = self
+            .cells()
+            .filter(|&((x, y, z), cell)| !solid(cell) && self.on_edge(x, y, z))
+            .map(|(at, _)| at)
+            .collect();
+        for &at in &to_visit {
+            outside[at] = true;
+        }
+        while let Some((x, y, z)) = to_visit.pop() {
+            for next in self.neighbors(x, y, z, Connectivity::Faces) {
+                if !outside[next] && !solid(&self[next]) {
+                    outside[next] = true;
+                    to_visit.push(next);
+                }
+            }
+        }
+
+        self.exposed_faces(&solid, |at| outside[at])
+    }
+
+    fn on_edge(&self, x: usize, y: usize, z: usize) -> bool {
+        x == 0
+            || y == 0
+            || z == 0
+            || x + 1 == self.width
+            || y + 1 == self.height
+            || z + 1 == self.depth
+    }
+
+    /// How many faces of solid cells are on the outside of the grid, or touch air that `counts`.
+    fn exposed_faces(&self, solid: &impl Fn(&T) -> bool, counts: impl Fn(Cell3) -> bool) -> usize {
+        self.cells()
+            .filter(|(_, cell)| solid(cell))
+            .map(|((x, y, z), _)| {
+                let neighbors: Vec<Cell3> = self.neighbors(x, y, z, Connectivity::Faces).collect();
+                let off_the_edge = 6 - neighbors.len();
+                let open = neighbors
+                    .into_iter()
+                    .filter(|&at| !solid(&self[at]) && counts(at))
+                    .count();
+                off_the_edge + open
+            })
+            .sum()
+    }
+}
+
+impl Grid3<bool> {
+    /// A grid just big enough to hold every point, with those points set.
+    pub fn from_points(points: &[Cell3]) -> Self {
+        let (width, height, depth) = points.iter().fold((0, 0, 0), |(w, h, d), &(x, y, z)| {
+            (w.max(x + 1), h.max(y + 1), d.max(z + 1))
+        });
+
+        let mut grid = Self::new(width, height, depth, false);
+        for &point in points {
+            grid[point] = true;
+        }
+        grid
+    }
+}
+
+impl<T> Index<Cell3> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, at: Cell3) -> &T {
+        let idx = self
+            .index_of(at)
+            .unwrap_or_else(|| panic!("{at:?} is out of bounds"));
+        &self.cells[idx]
+    }
+}
+
+impl<T> IndexMut<Cell3> for Grid3<T> {
+    fn index_mut(&mut self, at: Cell3) -> &mut T {
+        let idx = self
+            .index_of(at)
+            .unwrap_or_else(|| panic!("{at:?} is out of bounds"));
+        &mut self.cells[idx]
+    }
+}
+
+/// Every cell reachable from `start` by stepping across faces through passable cells, including
+/// `start` itself. Empty when `start` is out of bounds or isn't passable.
+pub fn flood_fill3<T>(
+    grid: &Grid3<T>,
+    start: Cell3,
+    passable: impl Fn(&T) -> bool,
+) -> HashSet<Cell3> {
+    let mut filled = HashSet::new();
+    if !grid.get(start.0, start.1, start.2).is_some_and(&passable) {
+        return filled;
+    }
+
+    filled.insert(start);
+    let mut to_visit = vec![start];
+    while let Some((x, y, z)) = to_visit.pop() {
+        for neighbor in grid.neighbors(x, y, z, Connectivity::Faces) {
+            if passable(&grid[neighbor]) && filled.insert(neighbor) {
+                to_visit.push(neighbor);
+            }
+        }
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn neighborator3_faces_in_bounds() {
+        let v: Vec<(usize, usize, usize)> =
+            Neighborator3::new((1, 1, 1), (3, 3, 3), Connectivity::Faces).collect();
+
+        assert_eq!(v.len(), 6);
+        for neighbor in [
+            (0, 1, 1),
+            (2, 1, 1),
+            (1, 0, 1),
+            (1, 2, 1),
+            (1, 1, 0),
+            (1, 1, 2),
+        ] {
+            assert!(v.contains(&neighbor), "{neighbor:?}");
+        }
+    }
+
+    #[test]
+    fn neighborator3_all_in_bounds() {
+        let v: BTreeSet<(usize, usize, usize)> =
+            Neighborator3::new((1, 1, 1), (3, 3, 3), Connectivity::All).collect();
+
+        assert_eq!(v.len(), 26);
+        assert!(!v.contains(&(1, 1, 1)));
+        assert!(v.contains(&(0, 0, 0)));
+        assert!(v.contains(&(2, 2, 2)));
+    }
+
+    #[test]
+    fn neighborator3_corner() {
+        let faces: Vec<(usize, usize, usize)> =
+            Neighborator3::new((0, 0, 0), (3, 3, 3), Connectivity::Faces).collect();
+        assert_eq!(faces.len(), 3);
+        assert!(faces.contains(&(1, 0, 0)));
+        assert!(faces.contains(&(0, 1, 0)));
+        assert!(faces.contains(&(0, 0, 1)));
+
+        let all = Neighborator3::new((2, 2, 2), (3, 3, 3), Connectivity::All);
+        assert_eq!(all.count(), 7);
+    }
+
+    #[test]
+    fn neighborator3_flat() {
+        // a single layer acts like a 2D grid
+        let faces = Neighborator3::new((1, 1, 0), (3, 3, 1), Connectivity::Faces);
+        assert_eq!(faces.count(), 4);
+
+        let all = Neighborator3::new((1, 1, 0), (3, 3, 1), Connectivity::All);
+        assert_eq!(all.count(), 8);
+    }
+
+    /// The example lava droplet, shifted so every cube has air around it.
+    fn droplet() -> Grid3<bool> {
+        let cubes = "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 1,2,5 3,2,5 2,1,5 2,3,5";
+        let points: Vec<(usize, usize, usize)> = cubes
+            .split(' ')
+            .map(|cube| {
+                let xyz: Vec<usize> = cube
+                    .split(',')
+                    .map(|n| n.parse::<usize>().unwrap() + 1)
+                    .collect();
+                (xyz[0], xyz[1], xyz[2])
+            })
+            .collect();
+
+        let mut droplet = Grid3::new(6, 6, 9, false);
+        for point in points {
+            droplet[point] = true;
+        }
+        droplet
+    }
+
+    #[test]
+    fn grid3_surface_area() {
+        let droplet = droplet();
+        assert_eq!(droplet.surface_area(|&solid| solid), 64);
+        assert_eq!(droplet.exterior_surface_area(|&solid| solid), 58);
+
+        // two cubes side by side, right up against the edges of their grid
+        let pair = Grid3::from_points(&[(0, 0, 0), (1, 0, 0)]);
+        assert_eq!(pair.dimensions(), (2, 1, 1));
+        assert_eq!(pair.surface_area(|&solid| solid), 10);
+        assert_eq!(pair.exterior_surface_area(|&solid| solid), 10);
+    }
+
+    #[test]
+    fn grid3_cells_and_flood_fill() {
+        let mut grid = Grid3::new(3, 2, 2, '.');
+        grid.set(2, 1, 1, '#');
+        assert_eq!(grid.get(2, 1, 1), Some(&'#'));
+        assert_eq!(grid.get(3, 0, 0), None);
+        assert_eq!(
+            grid.cells().find(|&(_, &c)| c == '#').map(|(at, _)| at),
+            Some((2, 1, 1))
+        );
+
+        // a wall across the middle of x splits the air in two
+        let mut walled = Grid3::new(3, 2, 2, '.');
+        for (y, z) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            walled.set(1, y, z, '#');
+        }
+        assert_eq!(flood_fill3(&walled, (0, 0, 0), |&c| c == '.').len(), 4);
+        assert!(flood_fill3(&walled, (1, 0, 0), |&c| c == '.').is_empty());
+        assert_eq!(flood_fill3(&grid, (0, 0, 0), |&c| c == '.').len(), 11);
+    }
+}
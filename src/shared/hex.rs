@@ -0,0 +1,207 @@
+//! Hexagonal grids, for puzzles like flipping floor tiles or following a child through a hex
+//! maze. Tiles use axial coordinates, which are cube coordinates with the third one left implied.
+
+use std::str::FromStr;
+
+use crate::error::AocError;
+
+/// A tile on a hex grid, in axial coordinates: `q` grows going east, and `r` grows going south
+/// east. The implied third cube coordinate is `s`, where `q + r + s == 0`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate, which isn't stored since the other two pin it down.
+    pub fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    /// The neighboring tile in `direction`.
+    pub fn step(self, direction: HexDirection) -> Self {
+        let (dq, dr) = direction.offset();
+        Self::new(self.q + dq, self.r + dr)
+    }
+
+    /// The tile at the end of `path`, starting from this one.
+    pub fn walk(self, path: impl IntoIterator<Item = HexDirection>) -> Self {
+        path.into_iter().fold(self, Self::step)
+    }
+
+    /// All six neighboring tiles, clockwise from the east.
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        HexDirection::ALL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+
+    /// The fewest steps between the tiles.
+    pub fn distance(self, other: Self) -> i64 {
+        let (dq, dr, ds) = (self.q - other.q, self.r - other.r, self.s() - other.s());
+        (dq.abs() + dr.abs() + ds.abs()) / 2
+    }
+}
+
+/// A direction on a hex grid whose tiles have a point at the top, so their neighbors are east and
+/// west, and either side of north and south.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection {
+    /// Every direction, clockwise from east.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+        HexDirection::West,
+        HexDirection::NorthWest,
+        HexDirection::NorthEast,
+    ];
+
+    /// The `(dq, dr)` of one step in this direction.
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            HexDirection::East => (1, 0),
+            HexDirection::SouthEast => (0, 1),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::West => (-1, 0),
+            HexDirection::NorthWest => (0, -1),
+            HexDirection::NorthEast => (1, -1),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            HexDirection::East => HexDirection::West,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::West => HexDirection::East,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+        }
+    }
+
+    /// Parses a direction on a grid whose tiles have a flat top instead, like `n` or `sw`. That's
+    /// the same grid turned a twelfth of a turn, so each direction there is one of these, and
+    /// distances come out the same.
+    pub fn from_flat_top(s: &str) -> Result<Self, AocError> {
+        match s {
+            "n" => Ok(HexDirection::NorthWest),
+            "ne" => Ok(HexDirection::NorthEast),
+            "se" => Ok(HexDirection::East),
+            "s" => Ok(HexDirection::SouthEast),
+            "sw" => Ok(HexDirection::SouthWest),
+            "nw" => Ok(HexDirection::West),
+            _ => Err(AocError::Parse(format!(
+                "direction should be n, ne, se, s, sw, or nw: {s}"
+            ))),
+        }
+    }
+
+    /// Parses directions written one after another with nothing between them, like `esenee`.
+    /// That's never ambiguous, since only east and west are a single letter.
+    pub fn parse_path(s: &str) -> Result<Vec<Self>, AocError> {
+        let mut path = Vec::new();
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let chars = if rest.starts_with(['n', 's']) { 2 } else { 1 };
+            let end = rest
+                .char_indices()
+                .nth(chars)
+                .map_or(rest.len(), |(idx, _)| idx);
+            let (direction, after) = rest.split_at(end);
+            path.push(direction.parse()?);
+            rest = after;
+        }
+
+        Ok(path)
+    }
+}
+
+/// Parses a direction like `e` or `nw`.
+impl FromStr for HexDirection {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e" => Ok(HexDirection::East),
+            "se" => Ok(HexDirection::SouthEast),
+            "sw" => Ok(HexDirection::SouthWest),
+            "w" => Ok(HexDirection::West),
+            "nw" => Ok(HexDirection::NorthWest),
+            "ne" => Ok(HexDirection::NorthEast),
+            _ => Err(AocError::Parse(format!(
+                "direction should be e, se, sw, w, nw, or ne: {s}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_are_one_step_away() {
+        let origin = Hex::default();
+        for neighbor in origin.neighbors() {
+            assert_eq!(origin.distance(neighbor), 1);
+            assert_eq!(neighbor.q + neighbor.r + neighbor.s(), 0);
+        }
+
+        for direction in HexDirection::ALL {
+            assert_eq!(origin.step(direction).step(direction.reverse()), origin);
+        }
+    }
+
+    #[test]
+    fn walking_a_path() {
+        let path = HexDirection::parse_path("esew").unwrap();
+        assert_eq!(
+            path,
+            [
+                HexDirection::East,
+                HexDirection::SouthEast,
+                HexDirection::West
+            ]
+        );
+        assert_eq!(Hex::default().walk(path), Hex::new(0, 1));
+
+        // going all the way round comes back
+        let around = HexDirection::parse_path("nwwswee").unwrap();
+        assert_eq!(Hex::default().walk(around), Hex::default());
+
+        assert!(HexDirection::parse_path("nex").is_err());
+        assert!(HexDirection::parse_path("en").is_err());
+        assert!(HexDirection::parse_path("éw").is_err());
+    }
+
+    #[test]
+    fn flat_top_distances() {
+        let walk = |path: &str| {
+            let path = path
+                .split(',')
+                .map(|s| HexDirection::from_flat_top(s).unwrap());
+            Hex::default().distance(Hex::default().walk(path))
+        };
+
+        assert_eq!(walk("ne,ne,ne"), 3);
+        assert_eq!(walk("ne,ne,sw,sw"), 0);
+        assert_eq!(walk("ne,ne,s,s"), 2);
+        assert_eq!(walk("se,sw,se,sw,sw"), 3);
+        assert!(HexDirection::from_flat_top("e").is_err());
+    }
+}
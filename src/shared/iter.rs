@@ -0,0 +1,163 @@
+//! The iterator helpers puzzles keep reaching for, without pulling in all of itertools.
+
+use std::{collections::HashMap, hash::Hash, iter::Sum};
+
+pub trait IterExt: Iterator + Sized {
+    /// Groups the items into `Vec`s of exactly `size`, dropping any leftover which is too short,
+    /// like `slice::chunks_exact`. Panics if `size` is zero.
+    fn chunks_exact_vec(self, size: usize) -> ChunksExactVec<Self> {
+        assert!(size > 0, "chunk size should not be zero");
+
+        ChunksExactVec { iter: self, size }
+    }
+
+    /// Each item paired with the next one, so `[a, b, c]` gives `(a, b)` then `(b, c)`.
+    fn pairwise(self) -> Pairwise<Self>
+    where
+        Self::Item: Clone,
+    {
+        Pairwise {
+            iter: self,
+            previous: None,
+        }
+    }
+
+    /// How many times each distinct item appears.
+    fn counts(self) -> HashMap<Self::Item, usize>
+    where
+        Self::Item: Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+        for item in self {
+            *counts.entry(item).or_default() += 1;
+        }
+
+        counts
+    }
+
+    /// Sums a value taken from each item.
+    fn sum_by<S: Sum, F: FnMut(Self::Item) -> S>(self, f: F) -> S {
+        self.map(f).sum()
+    }
+
+    /// Takes items up to and including the first one which matches. Unlike `take_while`, the item
+    /// that ends it is kept.
+    fn take_until<P: FnMut(&Self::Item) -> bool>(self, predicate: P) -> TakeUntil<Self, P> {
+        TakeUntil {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+/// See `IterExt::chunks_exact_vec`.
+pub struct ChunksExactVec<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for ChunksExactVec<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk: Vec<I::Item> = self.iter.by_ref().take(self.size).collect();
+        (chunk.len() == self.size).then_some(chunk)
+    }
+}
+
+/// See `IterExt::pairwise`.
+pub struct Pairwise<I: Iterator> {
+    iter: I,
+    previous: Option<I::Item>,
+}
+
+impl<I: Iterator<Item: Clone>> Iterator for Pairwise<I> {
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.previous.take() {
+            Some(previous) => previous,
+            None => self.iter.next()?,
+        };
+        let second = self.iter.next()?;
+        self.previous = Some(second.clone());
+
+        Some((first, second))
+    }
+}
+
+/// See `IterExt::take_until`.
+pub struct TakeUntil<I, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for TakeUntil<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.iter.next()?;
+        self.done = (self.predicate)(&item);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_exact_vec_drops_short_tail() {
+        let chunks: Vec<Vec<u32>> = (1..=7).chunks_exact_vec(3).collect();
+        assert_eq!(chunks, [vec![1, 2, 3], vec![4, 5, 6]]);
+
+        assert_eq!((1..=2).chunks_exact_vec(3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size should not be zero")]
+    fn chunks_exact_vec_of_zero_panics() {
+        let _ = (1..=2).chunks_exact_vec(0);
+    }
+
+    #[test]
+    fn pairwise() {
+        let pairs: Vec<(char, char)> = "abcd".chars().pairwise().collect();
+        assert_eq!(pairs, [('a', 'b'), ('b', 'c'), ('c', 'd')]);
+
+        assert_eq!([1].into_iter().pairwise().next(), None);
+        assert_eq!(std::iter::empty::<u32>().pairwise().next(), None);
+    }
+
+    #[test]
+    fn counts() {
+        let counts = "abracadabra".chars().counts();
+        assert_eq!(counts[&'a'], 5);
+        assert_eq!(counts[&'b'], 2);
+        assert_eq!(counts[&'d'], 1);
+        assert_eq!(counts.get(&'z'), None);
+    }
+
+    #[test]
+    fn sum_by() {
+        let words = ["one", "three", "five"];
+        assert_eq!(words.iter().sum_by(|word| word.len()), 12);
+    }
+
+    #[test]
+    fn take_until_keeps_the_match() {
+        let taken: Vec<u32> = (1..).take_until(|&n| n * n > 10).collect();
+        assert_eq!(taken, [1, 2, 3, 4]);
+
+        let taken: Vec<u32> = (1..=3).take_until(|&n| n > 10).collect();
+        assert_eq!(taken, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,230 @@
+//! A k-d tree, for finding the points closest to somewhere without measuring the distance to every
+//! point, like connecting junction boxes to their nearest neighbors.
+
+use std::collections::BinaryHeap;
+
+/// A point, and its index in the slice the tree was built from.
+type Node<const K: usize> = ([i64; K], usize);
+
+/// Points in `K` dimensions, split in half along each axis in turn. Queries answer with each
+/// point's index in the slice the tree was built from, along with its squared distance, which
+/// stays exact.
+#[derive(Debug, Clone)]
+pub struct KdTree<const K: usize> {
+    // laid out so the middle of any stretch is the node splitting it, with the points before it
+    // on one side of the split and those after on the other
+    nodes: Vec<Node<K>>,
+}
+
+impl<const K: usize> KdTree<K> {
+    pub fn new(points: &[[i64; K]]) -> Self {
+        let mut nodes: Vec<Node<K>> = points.iter().copied().zip(0..).collect();
+        build(&mut nodes, 0);
+
+        Self { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The `k` points closest to `target`, closest first, as `(index, squared distance)`. Points
+    /// the same distance away come in index order.
+    pub fn nearest(&self, target: [i64; K], k: usize) -> Vec<(usize, u128)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // the farthest of the closest found so far is on top, ready to be pushed out
+        let mut closest: BinaryHeap<(u128, usize)> = BinaryHeap::with_capacity(k + 1);
+        search_nearest(&self.nodes, 0, target, k, &mut closest);
+
+        closest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(distance, index)| (index, distance))
+            .collect()
+    }
+
+    /// Every point no farther than `radius` from `target`, closest first, as
+    /// `(index, squared distance)`. Points the same distance away come in index order.
+    pub fn within(&self, target: [i64; K], radius: u64) -> Vec<(usize, u128)> {
+        let radius_squared = u128::from(radius).pow(2);
+
+        let mut found = Vec::new();
+        search_within(&self.nodes, 0, target, radius_squared, &mut found);
+        found.sort_unstable_by_key(|&(index, distance)| (distance, index));
+
+        found
+    }
+}
+
+fn squared_distance<const K: usize>(a: [i64; K], b: [i64; K]) -> u128 {
+    (0..K)
+        .map(|axis| u128::from(a[axis].abs_diff(b[axis])).pow(2))
+        .sum()
+}
+
+fn build<const K: usize>(nodes: &mut [Node<K>], depth: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+
+    let axis = depth % K;
+    let middle = nodes.len() / 2;
+    nodes.select_nth_unstable_by_key(middle, |(point, _)| point[axis]);
+
+    let (before, after) = nodes.split_at_mut(middle);
+    build(before, depth + 1);
+    build(&mut after[1..], depth + 1);
+}
+
+/// The nodes either side of the middle one which splits them, with `target`'s side first, and the
+/// squared distance from `target` to the split. Nothing on the far side can be any closer.
+fn sides<const K: usize>(
+    nodes: &[Node<K>],
+    depth: usize,
+    target: [i64; K],
+) -> (&[Node<K>], &[Node<K>], u128) {
+    let middle = nodes.len() / 2;
+    let (split, _) = nodes[middle];
+    let axis = depth % K;
+    let to_split = u128::from(target[axis].abs_diff(split[axis])).pow(2);
+
+    let (before, after) = (&nodes[..middle], &nodes[middle + 1..]);
+    if target[axis] < split[axis] {
+        (before, after, to_split)
+    } else {
+        (after, before, to_split)
+    }
+}
+
+fn search_nearest<const K: usize>(
+    nodes: &[Node<K>],
+    depth: usize,
+    target: [i64; K],
+    k: usize,
+    closest: &mut BinaryHeap<(u128, usize)>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+
+    let (point, index) = nodes[nodes.len() / 2];
+    closest.push((squared_distance(point, target), index));
+    if closest.len() > k {
+        closest.pop();
+    }
+
+    let (near, far, to_split) = sides(nodes, depth, target);
+    search_nearest(near, depth + 1, target, k, closest);
+
+    // a point exactly as far as the farthest kept could still win on index
+    let farthest = closest.peek().map_or(u128::MAX, |&(distance, _)| distance);
+    if closest.len() < k || to_split <= farthest {
+        search_nearest(far, depth + 1, target, k, closest);
+    }
+}
+
+fn search_within<const K: usize>(
+    nodes: &[Node<K>],
+    depth: usize,
+    target: [i64; K],
+    radius_squared: u128,
+    found: &mut Vec<(usize, u128)>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+
+    let (point, index) = nodes[nodes.len() / 2];
+    let distance = squared_distance(point, target);
+    if distance <= radius_squared {
+        found.push((index, distance));
+    }
+
+    let (near, far, to_split) = sides(nodes, depth, target);
+    search_within(near, depth + 1, target, radius_squared, found);
+    if to_split <= radius_squared {
+        search_within(far, depth + 1, target, radius_squared, found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    fn by_brute_force<const K: usize>(points: &[[i64; K]], target: [i64; K]) -> Vec<(usize, u128)> {
+        let mut all: Vec<(usize, u128)> = points
+            .iter()
+            .enumerate()
+            .map(|(index, &point)| (index, squared_distance(point, target)))
+            .collect();
+        all.sort_unstable_by_key(|&(index, distance)| (distance, index));
+        all
+    }
+
+    #[test]
+    fn nearest_in_2d() {
+        let points = [[0, 0], [5, 5], [1, 1], [-2, 0], [10, 0]];
+        let tree = KdTree::new(&points);
+
+        assert_eq!(tree.nearest([0, 0], 1), [(0, 0)]);
+        assert_eq!(tree.nearest([1, 0], 3), [(0, 1), (2, 1), (3, 9)]);
+        assert_eq!(tree.nearest([9, 9], 0), []);
+        assert_eq!(tree.nearest([9, 9], 10).len(), 5);
+    }
+
+    #[test]
+    fn within_in_3d() {
+        let points = [[0, 0, 0], [1, 1, 1], [3, 0, 0], [0, 0, -2]];
+        let tree = KdTree::new(&points);
+
+        assert_eq!(tree.within([0, 0, 0], 2), [(0, 0), (1, 3), (3, 4)]);
+        assert_eq!(tree.within([10, 10, 10], 2), []);
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree: KdTree<2> = KdTree::new(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.nearest([0, 0], 3), []);
+        assert_eq!(tree.within([0, 0], 3), []);
+    }
+
+    proptest! {
+        #[test]
+        fn nearest_matches_brute_force(
+            points in vec([-20..20_i64, -20..20_i64, -20..20_i64], 1..60),
+            target in [-25..25_i64, -25..25_i64, -25..25_i64],
+            k in 0..10_usize,
+        ) {
+            let tree = KdTree::new(&points);
+            let mut expected = by_brute_force(&points, target);
+            expected.truncate(k);
+
+            prop_assert_eq!(tree.nearest(target, k), expected);
+        }
+
+        #[test]
+        fn within_matches_brute_force(
+            points in vec([-20..20_i64, -20..20_i64], 1..60),
+            target in [-25..25_i64, -25..25_i64],
+            radius in 0..15_u64,
+        ) {
+            let tree = KdTree::new(&points);
+            let expected: Vec<(usize, u128)> = by_brute_force(&points, target)
+                .into_iter()
+                .filter(|&(_, distance)| distance <= u128::from(radius).pow(2))
+                .collect();
+
+            prop_assert_eq!(tree.within(target, radius), expected);
+        }
+    }
+}
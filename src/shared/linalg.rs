@@ -0,0 +1,234 @@
+//! Exact linear algebra over rationals, for systems of equations like "which buttons add up to
+//! these joltages" where floats would leave answers like 2.9999999.
+
+use super::{Rational, numtheory::lcm};
+
+/// A matrix in reduced row echelon form, along with the column of each row's leading 1. Rows
+/// after the last pivot are all zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rref {
+    pub rows: Vec<Vec<Rational>>,
+    pub pivots: Vec<usize>,
+}
+
+/// Row reduces a matrix, given as a list of equally long rows.
+pub fn rref(matrix: &[Vec<Rational>]) -> Rref {
+    let mut rows = matrix.to_vec();
+    let cols = rows.first().map_or(0, Vec::len);
+    let mut pivots = Vec::new();
+
+    for col in 0..cols {
+        let r = pivots.len();
+        if r == rows.len() {
+            break;
+        }
+
+        // no row left has anything in this column, so it doesn't lead a row
+        let Some(pivot_row) = (r..rows.len()).find(|&i| !rows[i][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(r, pivot_row);
+
+        // scale the row so it leads with a 1, then clear the column out of every other row
+        let lead = rows[r][col];
+        rows[r].iter_mut().for_each(|value| *value /= lead);
+        let pivot = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if i != r && !factor.is_zero() {
+                for (value, &p) in row.iter_mut().zip(&pivot) {
+                    *value -= p * factor;
+                }
+            }
+        }
+
+        pivots.push(col);
+    }
+
+    Rref { rows, pivots }
+}
+
+/// Every solution to a system of equations. The basic variables are fixed once the free variables
+/// are picked, and the free variables can be anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// The reduced rows with the constants as a final column, one per basic variable.
+    pub reduced: Vec<Vec<Rational>>,
+    pub basic: Vec<usize>,
+    pub free: Vec<usize>,
+
+    /// Each reduced row scaled up by its denominators, so evaluating integers never needs a
+    /// fraction. Pairs the scale with the scaled row.
+    scaled: Vec<(i128, Vec<i128>)>,
+}
+
+impl Solution {
+    fn new(reduced: Vec<Vec<Rational>>, basic: Vec<usize>, free: Vec<usize>) -> Self {
+        let scaled = reduced
+            .iter()
+            .map(|row| {
+                let scale = row.iter().fold(1, |scale, value| {
+                    let denom =
+                        u64::try_from(value.denom()).expect("denominator should fit in u64");
+                    lcm(scale, denom)
+                });
+                let scale = i128::from(scale);
+                let row = row
+                    .iter()
+                    .map(|value| value.numer() * (scale / value.denom()))
+                    .collect();
+
+                (scale, row)
+            })
+            .collect();
+
+        Self {
+            reduced,
+            basic,
+            free,
+            scaled,
+        }
+    }
+
+    /// Like `evaluate`, but for whole numbers only. None if any variable would be a fraction.
+    /// Much faster, for searching lots of values for the free variables.
+    pub fn evaluate_integers(&self, free_values: &[i128]) -> Option<Vec<i128>> {
+        let variables = self.basic.len() + self.free.len();
+        let mut values = vec![0; variables];
+        for (&idx, &value) in self.free.iter().zip(free_values) {
+            values[idx] = value;
+        }
+
+        for ((scale, row), &basic) in self.scaled.iter().zip(&self.basic) {
+            let mut value = row[variables];
+            for &free in &self.free {
+                value -= row[free] * values[free];
+            }
+
+            // most rows are whole already, and i128 division is slow enough to be worth skipping
+            if *scale != 1 {
+                if value % scale != 0 {
+                    return None;
+                }
+                value /= scale;
+            }
+            values[basic] = value;
+        }
+
+        Some(values)
+    }
+
+    /// The value of every variable, given values for the free variables in the order of `free`.
+    pub fn evaluate(&self, free_values: &[Rational]) -> Vec<Rational> {
+        let variables = self.basic.len() + self.free.len();
+        let mut values = vec![Rational::ZERO; variables];
+        for (&idx, &value) in self.free.iter().zip(free_values) {
+            values[idx] = value;
+        }
+
+        // each row reads: basic + (free * coefficients) = constant
+        for (row, &basic) in self.reduced.iter().zip(&self.basic) {
+            let mut value = row[variables];
+            for &free in &self.free {
+                value -= row[free] * values[free];
+            }
+            values[basic] = value;
+        }
+
+        values
+    }
+}
+
+/// Solves `a * x = b`, where `a` is a list of rows. None if there's no solution at all.
+pub fn solve(a: &[Vec<Rational>], b: &[Rational]) -> Option<Solution> {
+    let variables = a.first().map_or(0, Vec::len);
+    let augmented: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &constant)| row.iter().copied().chain([constant]).collect())
+        .collect();
+
+    let Rref { mut rows, pivots } = rref(&augmented);
+
+    // a row leading in the constants reads 0 = constant, which can't be satisfied
+    if pivots.last() == Some(&variables) {
+        return None;
+    }
+
+    rows.truncate(pivots.len());
+    let free = (0..variables).filter(|i| !pivots.contains(i)).collect();
+
+    Some(Solution::new(rows, pivots, free))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> Vec<Vec<Rational>> {
+        rows.iter()
+            .map(|row| row.iter().map(|&n| Rational::from(n)).collect())
+            .collect()
+    }
+
+    fn vector(values: &[i64]) -> Vec<Rational> {
+        values.iter().map(|&n| Rational::from(n)).collect()
+    }
+
+    #[test]
+    fn rref_reduces_exactly() {
+        let reduced = rref(&matrix(&[&[2, 4, 6], &[1, 3, 5], &[3, 7, 11]]));
+
+        assert_eq!(reduced.rows, matrix(&[&[1, 0, -1], &[0, 1, 2], &[0, 0, 0]]));
+        assert_eq!(reduced.pivots, [0, 1]);
+    }
+
+    #[test]
+    fn rref_with_fractions() {
+        let reduced = rref(&matrix(&[&[3, 1], &[1, 2]]));
+
+        assert_eq!(reduced.rows, matrix(&[&[1, 0], &[0, 1]]));
+        assert_eq!(rref(&matrix(&[&[3, 1]])).rows[0][1], Rational::new(1, 3));
+    }
+
+    #[test]
+    fn solve_unique_system() {
+        // x + y = 3, x - y = 1
+        let solution = solve(&matrix(&[&[1, 1], &[1, -1]]), &vector(&[3, 1])).unwrap();
+
+        assert!(solution.free.is_empty());
+        assert_eq!(solution.evaluate(&[]), vector(&[2, 1]));
+    }
+
+    #[test]
+    fn solve_with_free_variables() {
+        // x + z = 4, y + z = 3
+        let solution = solve(&matrix(&[&[1, 0, 1], &[0, 1, 1]]), &vector(&[4, 3])).unwrap();
+
+        assert_eq!(solution.basic, [0, 1]);
+        assert_eq!(solution.free, [2]);
+        assert_eq!(solution.evaluate(&vector(&[1])), vector(&[3, 2, 1]));
+        assert_eq!(solution.evaluate(&vector(&[5])), vector(&[-1, -2, 5]));
+    }
+
+    #[test]
+    fn evaluate_integers_skips_fractions() {
+        // 2x + z = 5, 3y - z = 1
+        let solution = solve(&matrix(&[&[2, 0, 1], &[0, 3, -1]]), &vector(&[5, 1])).unwrap();
+
+        assert_eq!(solution.evaluate_integers(&[5]), Some(vec![0, 2, 5]));
+        assert_eq!(solution.evaluate_integers(&[11]), Some(vec![-3, 4, 11]));
+        assert_eq!(solution.evaluate_integers(&[1]), None);
+        assert_eq!(solution.evaluate_integers(&[2]), None);
+        assert_eq!(
+            solution.evaluate(&vector(&[2])),
+            [Rational::new(3, 2), Rational::ONE, Rational::from(2_i64)]
+        );
+    }
+
+    #[test]
+    fn solve_inconsistent_system() {
+        // x + y = 1, 2x + 2y = 3
+        assert_eq!(solve(&matrix(&[&[1, 1], &[2, 2]]), &vector(&[1, 3])), None);
+    }
+}
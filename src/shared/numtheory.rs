@@ -0,0 +1,251 @@
+//! Number theory for puzzles about cycles lining up, like buses which each leave on their own
+//! schedule, and for answers which are asked for modulo some large prime.
+
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+/// The greatest common divisor. `gcd(0, 0)` is 0.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// The least common multiple, which is when cycles of length `a` and `b` first line up again.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    a / gcd(a, b) * b
+}
+
+/// Finds `(g, x, y)` where `g` is the gcd of `a` and `b`, and `a * x + b * y == g`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    // the coefficients are never bigger than a and b, so they fit back into i64
+    let (g, x, y) = extended_gcd_i128(a.into(), b.into());
+    (g as i64, x as i64, y as i64)
+}
+
+/// Finds `x` in `0..m` where `a * x` is 1 modulo `m`. None if `a` and `m` share a factor, since
+/// then there isn't one.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Chinese remainder theorem: finds the `x` which is `residue` modulo `modulus` for every pair,
+/// returned as `(x, m)` where `x` is the smallest answer and every answer is `x` plus a multiple
+/// of `m`. The moduli don't need to be coprime. None if the congruences contradict each other.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut solution: (i128, i128) = (0, 1);
+
+    for &(residue, modulus) in congruences {
+        let (x, m) = solution;
+        let (residue, modulus) = (i128::from(residue), i128::from(modulus));
+
+        // x + m * k = residue (mod modulus), so m * k = residue - x (mod modulus)
+        let (g, inverse, _) = extended_gcd_i128(m, modulus);
+        let diff = residue - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (diff / g % step * inverse).rem_euclid(step);
+        let combined = m * step;
+        solution = ((x + m * k).rem_euclid(combined), combined);
+    }
+
+    let (x, m) = solution;
+    Some((x.try_into().ok()?, m.try_into().ok()?))
+}
+
+/// A number modulo `M`, like `ModInt<1_000_000_007>`. Arithmetic wraps around `M`, so path
+/// counts can grow as big as they like.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(n: u64) -> Self {
+        Self(n % M)
+    }
+
+    /// The number, in `0..M`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Raises this to `exp`, by squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let (mut base, mut result) = (self, Self::new(1));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+
+        result
+    }
+
+    /// The number which multiplies with this to make 1. None if this shares a factor with `M`,
+    /// which only happens for 0 when `M` is prime.
+    pub fn inverse(self) -> Option<Self> {
+        let m = i64::try_from(M).expect("modulus should fit in an i64");
+        mod_inverse(self.0 as i64, m).map(|inverse| Self(inverse as u64))
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(n: u64) -> Self {
+        Self::new(n)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(((u128::from(self.0) + u128::from(other.0)) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + Self(M - other.0)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self((u128::from(self.0) * u128::from(other.0) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<const M: u64> Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// `extended_gcd`, with room for the products `crt` builds up along the way.
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a.abs(), a.signum(), 0);
+    }
+
+    let (g, x, y) = extended_gcd_i128(b, a.rem_euclid(b));
+    (g, y, x - a.div_euclid(b) * y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+    }
+
+    #[test]
+    fn extended_gcd_finds_coefficients() {
+        for (a, b) in [(240, 46), (46, 240), (-12, 18), (7, 0), (17, 5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+            assert_eq!(a * x + b * y, g, "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn mod_inverse_when_coprime() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    type Mod7 = ModInt<7>;
+    type ModPrime = ModInt<1_000_000_007>;
+
+    #[test]
+    fn mod_int_arithmetic_wraps() {
+        assert_eq!(Mod7::new(10).value(), 3);
+        assert_eq!(Mod7::new(5) + Mod7::new(4), Mod7::new(2));
+        assert_eq!(Mod7::new(2) - Mod7::new(5), Mod7::new(4));
+        assert_eq!(Mod7::new(3) * Mod7::new(5), Mod7::new(1));
+        assert_eq!((1..=6).map(Mod7::new).sum::<Mod7>(), Mod7::new(0));
+    }
+
+    #[test]
+    fn mod_int_doesnt_overflow_large_moduli() {
+        let big = ModInt::<{ u64::MAX - 58 }>::new(u64::MAX - 59);
+        assert_eq!((big + big).value(), u64::MAX - 60);
+        assert_eq!((big * big).value(), 1);
+    }
+
+    #[test]
+    fn mod_int_pow_and_inverse() {
+        assert_eq!(ModPrime::new(2).pow(10), ModPrime::new(1024));
+        assert_eq!(ModPrime::new(3).pow(0), ModPrime::new(1));
+        // fermat's little theorem
+        assert_eq!(ModPrime::new(12345).pow(1_000_000_006), ModPrime::new(1));
+
+        let n = ModPrime::new(987_654_321);
+        assert_eq!(n * n.inverse().unwrap(), ModPrime::new(1));
+        assert_eq!(ModPrime::new(0).inverse(), None);
+        assert_eq!(ModInt::<6>::new(4).inverse(), None);
+    }
+
+    #[test]
+    fn crt_lines_up_buses() {
+        // buses 7, 13, 59, 31, 19 leaving 0, 1, 4, 6, 7 minutes after t
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let congruences: Vec<_> = buses
+            .iter()
+            .map(|&(bus, offset)| ((-offset as i64).rem_euclid(bus), bus))
+            .collect();
+
+        assert_eq!(crt(&congruences), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+    }
+
+    #[test]
+    fn crt_with_shared_factors() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+}
@@ -0,0 +1,130 @@
+//! Parsers for the pieces of input that keep coming up, like lists of numbers or `name: rest`
+//! lines. Each returns None when the input doesn't match, leaving the error message to the caller,
+//! which knows what the whole line should have looked like.
+
+use std::str::FromStr;
+
+use super::Grid;
+
+/// A whole number, signed or not depending on `T`, with any surrounding whitespace ignored.
+pub fn int<T: FromStr>(s: &str) -> Option<T> {
+    s.trim().parse().ok()
+}
+
+/// Items separated by `separator`, each parsed by `item`. Fails if any item does.
+pub fn list<'a, T>(
+    s: &'a str,
+    separator: &str,
+    item: impl Fn(&'a str) -> Option<T>,
+) -> Option<Vec<T>> {
+    s.split(separator).map(item).collect()
+}
+
+/// Whole numbers separated by `separator`, like `1,2,3`.
+pub fn ints<T: FromStr>(s: &str, separator: &str) -> Option<Vec<T>> {
+    list(s, separator, int)
+}
+
+/// What's between the brackets, when `s` is wrapped in them like `[.#]`, `(1,2)` or `{3,5}`.
+pub fn bracketed(s: &str, open: char, close: char) -> Option<&str> {
+    s.strip_prefix(open)?.strip_suffix(close)
+}
+
+/// The name and the rest of a line like `aaa: you hhh`.
+pub fn labelled(line: &str) -> Option<(&str, &str)> {
+    line.split_once(": ")
+}
+
+/// Splits text laid out in columns, like sums written one number above another, into the blocks
+/// between columns which are blank all the way down, from left to right. Every block is as tall as
+/// the text, with short rows padded out with spaces, since trailing spaces tend to get stripped.
+/// Blank lines before and after the text are dropped, but not the spaces starting the first row.
+pub fn column_blocks(s: &str) -> Vec<Grid<char>> {
+    let mut lines: Vec<&str> = s
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or_default();
+    let rows: Vec<Vec<char>> = lines
+        .iter()
+        .map(|line| {
+            let mut row: Vec<char> = line.chars().collect();
+            row.resize(width, ' ');
+            row
+        })
+        .collect();
+    let is_blank = |x: usize| rows.iter().all(|row| row[x].is_whitespace());
+
+    let mut blocks = Vec::new();
+    let mut x = 0;
+    while let Some(start) = (x..width).find(|&x| !is_blank(x)) {
+        let end = (start..width).find(|&x| is_blank(x)).unwrap_or(width);
+
+        let mut block = Grid::new(end - start, rows.len(), ' ');
+        for (y, row) in rows.iter().enumerate() {
+            for (dx, &c) in row[start..end].iter().enumerate() {
+                block[(dx, y)] = c;
+            }
+        }
+        blocks.push(block);
+        x = end;
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ints() {
+        assert_eq!(int::<i32>("-42"), Some(-42));
+        assert_eq!(int::<usize>(" 7\n"), Some(7));
+        assert_eq!(int::<usize>("-7"), None);
+        assert_eq!(int::<i32>("seven"), None);
+    }
+
+    #[test]
+    fn parse_lists() {
+        assert_eq!(ints::<i64>("1,-2,3", ","), Some(vec![1, -2, 3]));
+        assert_eq!(ints::<usize>("1 2 3", " "), Some(vec![1, 2, 3]));
+        assert_eq!(ints::<usize>("1,,3", ","), None);
+
+        let words = list("(1) (2,3)", " ", |s| bracketed(s, '(', ')'));
+        assert_eq!(words, Some(vec!["1", "2,3"]));
+    }
+
+    #[test]
+    fn parse_bracketed() {
+        assert_eq!(bracketed("[.#]", '[', ']'), Some(".#"));
+        assert_eq!(bracketed("{}", '{', '}'), Some(""));
+        assert_eq!(bracketed("(1,2", '(', ')'), None);
+        assert_eq!(bracketed("[1]", '(', ')'), None);
+    }
+
+    #[test]
+    fn splitting_column_blocks() {
+        let blocks = column_blocks("\n 12   7\n345  89\n+    *\n\n");
+        let text: Vec<String> = blocks.iter().map(|block| block.to_string()).collect();
+
+        assert_eq!(text, [" 12\n345\n+  \n", " 7\n89\n* \n"]);
+        assert_eq!(blocks[1].dimensions(), (2, 3));
+        assert!(column_blocks("\n  \n").is_empty());
+    }
+
+    #[test]
+    fn parse_labelled() {
+        assert_eq!(labelled("aaa: you hhh"), Some(("aaa", "you hhh")));
+        assert_eq!(labelled("12x5: 1 0 1"), Some(("12x5", "1 0 1")));
+        assert_eq!(labelled("aaa you hhh"), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Points on a plane and in space, which add, subtract and scale like vectors.
+
+use std::ops::{Add, Mul, Sub};
+
+/// A point on a plane, like a tile on a floor.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts each coordinate, like widening them before multiplying.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Point2<U> {
+        Point2::new(f(self.x), f(self.y))
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Point2<T> {
+    pub fn scale(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// The straight line distance, squared so it stays exact.
+    pub fn squared_distance(self, other: Self) -> T {
+        let (dx, dy) = (abs_diff(self.x, other.x), abs_diff(self.y, other.y));
+        dx * dx + dy * dy
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// A point in space, like a junction box hanging in a playground.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Converts each coordinate, like widening them before multiplying.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Point3<U> {
+        Point3::new(f(self.x), f(self.y), f(self.z))
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Point3<T> {
+    pub fn scale(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    /// The straight line distance, squared so it stays exact.
+    pub fn squared_distance(self, other: Self) -> T {
+        let (dx, dy, dz) = (
+            abs_diff(self.x, other.x),
+            abs_diff(self.y, other.y),
+            abs_diff(self.z, other.z),
+        );
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(point: Point3<T>) -> Self {
+        (point.x, point.y, point.z)
+    }
+}
+
+/// The distance between two numbers, which works for unsigned numbers too.
+fn abs_diff<T: PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_arithmetic() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(4, 6);
+
+        assert_eq!(a + b, Point2::new(5, 8));
+        assert_eq!(b - a, Point2::new(3, 4));
+        assert_eq!(a.scale(3), Point2::new(3, 6));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1),
+            Point3::new(2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn unsigned_point_distances() {
+        let a: Point2<usize> = (4, 6).into();
+        let b = Point2::new(1, 2);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(b.manhattan_distance(a), 7);
+        assert_eq!(a.squared_distance(b), 25);
+
+        let a = Point3::new(162_usize, 817, 812);
+        let b = Point3::new(425, 690, 689);
+        assert_eq!(a.manhattan_distance(b), 263 + 127 + 123);
+        assert_eq!(a.squared_distance(b), 263 * 263 + 127 * 127 + 123 * 123);
+    }
+
+    #[test]
+    fn point_conversions() {
+        let point = Point3::new(1_usize, 2, 3);
+        assert_eq!(point.map(|c| c as i64 - 2), Point3::new(-1, 0, 1));
+        assert_eq!(<(usize, usize, usize)>::from(point), (1, 2, 3));
+        assert_eq!(<(i32, i32)>::from(Point2::from((5, 7))), (5, 7));
+    }
+}
@@ -0,0 +1,180 @@
+//! Polygons on a grid of whole numbers, given as their vertices in order around the border, for
+//! puzzles like digging out a lagoon or fencing in a field. The border closes back from the last
+//! vertex to the first on its own.
+
+use super::{Point2, numtheory::gcd};
+
+type Vertex = Point2<i64>;
+
+/// Each border, from one vertex to the next, ending with the one back to the start.
+fn borders(vertices: &[Vertex]) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&start, &end)| (start, end))
+}
+
+/// Twice the area inside the border, by the shoelace formula. Doubled so it stays a whole number,
+/// since a polygon with diagonal borders can have half a square left over. The vertices can go
+/// either way around.
+pub fn twice_area(vertices: &[Vertex]) -> i64 {
+    borders(vertices)
+        .map(|(start, end)| start.x * end.y - end.x * start.y)
+        .sum::<i64>()
+        .abs()
+}
+
+/// The length of the border, for a polygon whose borders are all horizontal or vertical.
+pub fn perimeter(vertices: &[Vertex]) -> i64 {
+    borders(vertices)
+        .map(|(start, end)| start.manhattan_distance(end))
+        .sum()
+}
+
+/// How many grid points the border passes through, which for horizontal and vertical borders is
+/// the same as the perimeter.
+pub fn boundary_points(vertices: &[Vertex]) -> i64 {
+    borders(vertices)
+        .map(|(start, end)| {
+            let (dx, dy) = (start.x.abs_diff(end.x), start.y.abs_diff(end.y));
+            gcd(dx, dy) as i64
+        })
+        .sum()
+}
+
+/// How many grid points are strictly inside the border, by Pick's theorem: `A = I + B/2 - 1`.
+pub fn interior_points(vertices: &[Vertex]) -> i64 {
+    (twice_area(vertices) - boundary_points(vertices)) / 2 + 1
+}
+
+/// How many tiles the polygon covers when its vertices are the centers of tiles, counting the
+/// tiles along the border as well as those inside it.
+pub fn tiles_covered(vertices: &[Vertex]) -> i64 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Whether `point` is inside the polygon or on its border, by casting a ray to the right and
+/// counting the borders it crosses.
+pub fn contains(vertices: &[Vertex], point: Vertex) -> bool {
+    let mut inside = false;
+    for (start, end) in borders(vertices) {
+        if on_border(start, end, point) {
+            return true;
+        }
+
+        // each border covers the rows from its lower end up to, but not including, its upper end,
+        // so a ray through a vertex only counts it once
+        if (start.y > point.y) != (end.y > point.y) {
+            // where the border crosses the ray's row, compared without dividing
+            let (dx, dy) = (i128::from(end.x - start.x), i128::from(end.y - start.y));
+            let offset = i128::from(point.y - start.y) * dx;
+            let from_start = i128::from(point.x - start.x) * dy;
+            let crossed = if dy > 0 {
+                from_start < offset
+            } else {
+                from_start > offset
+            };
+            inside ^= crossed;
+        }
+    }
+
+    inside
+}
+
+fn on_border(start: Vertex, end: Vertex, point: Vertex) -> bool {
+    let cross = i128::from(end.x - start.x) * i128::from(point.y - start.y)
+        - i128::from(end.y - start.y) * i128::from(point.x - start.x);
+
+    cross == 0
+        && (start.x.min(end.x)..=start.x.max(end.x)).contains(&point.x)
+        && (start.y.min(end.y)..=start.y.max(end.y)).contains(&point.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Vec<Vertex> {
+        points.iter().map(|&point| Point2::from(point)).collect()
+    }
+
+    // an L shape, 4 wide and 3 tall with the top right 2x1 cut out
+    fn l_shape() -> Vec<Vertex> {
+        polygon(&[(0, 0), (2, 0), (2, 1), (4, 1), (4, 3), (0, 3)])
+    }
+
+    #[test]
+    fn area_either_way_around() {
+        let square = polygon(&[(0, 0), (3, 0), (3, 3), (0, 3)]);
+        assert_eq!(twice_area(&square), 18);
+
+        let reversed: Vec<Vertex> = square.into_iter().rev().collect();
+        assert_eq!(twice_area(&reversed), 18);
+
+        assert_eq!(twice_area(&l_shape()), 20);
+    }
+
+    #[test]
+    fn area_can_be_half_a_square() {
+        let triangle = polygon(&[(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(twice_area(&triangle), 1);
+    }
+
+    #[test]
+    fn perimeter_and_boundary() {
+        assert_eq!(perimeter(&l_shape()), 14);
+        assert_eq!(boundary_points(&l_shape()), 14);
+
+        // the diagonal only passes through its two ends, and the point halfway along
+        let triangle = polygon(&[(0, 0), (2, 0), (0, 2)]);
+        assert_eq!(boundary_points(&triangle), 6);
+    }
+
+    #[test]
+    fn picks_theorem() {
+        let square = polygon(&[(0, 0), (3, 0), (3, 3), (0, 3)]);
+        assert_eq!(interior_points(&square), 4);
+        assert_eq!(tiles_covered(&square), 16);
+
+        assert_eq!(interior_points(&l_shape()), 4);
+        assert_eq!(tiles_covered(&l_shape()), 18);
+    }
+
+    #[test]
+    fn tiles_covered_matches_contains() {
+        let shape = l_shape();
+        let counted = (-1..=5)
+            .flat_map(|x| (-1..=4).map(move |y| Point2::new(x, y)))
+            .filter(|&point| contains(&shape, point))
+            .count();
+
+        assert_eq!(counted as i64, tiles_covered(&shape));
+    }
+
+    #[test]
+    fn contains_points() {
+        let shape = l_shape();
+
+        assert!(contains(&shape, Point2::new(1, 1)));
+        assert!(contains(&shape, Point2::new(3, 2)));
+
+        // on a border or a vertex
+        assert!(contains(&shape, Point2::new(1, 0)));
+        assert!(contains(&shape, Point2::new(2, 1)));
+        assert!(contains(&shape, Point2::new(4, 3)));
+
+        // in the cut out corner, and level with vertices outside
+        assert!(!contains(&shape, Point2::new(3, 0)));
+        assert!(!contains(&shape, Point2::new(5, 1)));
+        assert!(!contains(&shape, Point2::new(-1, 3)));
+    }
+
+    #[test]
+    fn contains_with_diagonal_borders() {
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 4)]);
+
+        assert!(contains(&triangle, Point2::new(1, 1)));
+        assert!(contains(&triangle, Point2::new(2, 2)));
+        assert!(!contains(&triangle, Point2::new(3, 2)));
+    }
+}
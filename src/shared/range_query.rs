@@ -0,0 +1,400 @@
+//! Structures for asking about a range of a list while the list keeps changing, like counting
+//! inversions or taking the minimum over a sliding window, or for adding to a whole range at once,
+//! without going over the range each time.
+
+use std::ops::Range;
+
+use super::Grid;
+
+/// Running totals over a list of numbers, where both changing a number and summing a range take
+/// O(log n). Also known as a binary indexed tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenwickTree {
+    // each slot holds the sum of the numbers in a stretch ending at it, one-based so the length of
+    // the stretch is the lowest set bit of its index
+    sums: Vec<i64>,
+}
+
+impl FenwickTree {
+    /// A list of `len` zeroes.
+    pub fn new(len: usize) -> Self {
+        Self {
+            sums: vec![0; len + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to the number at `index`. Panics when that's out of bounds.
+    pub fn add(&mut self, index: usize, delta: i64) {
+        assert!(index < self.len(), "{index} is out of bounds");
+
+        let mut slot = index + 1;
+        while slot < self.sums.len() {
+            self.sums[slot] += delta;
+            slot += lowest_bit(slot);
+        }
+    }
+
+    /// The sum of the first `end` numbers. Panics when `end` is past the end of the list.
+    pub fn prefix_sum(&self, end: usize) -> i64 {
+        assert!(end <= self.len(), "{end} is out of bounds");
+
+        let mut sum = 0;
+        let mut slot = end;
+        while slot > 0 {
+            sum += self.sums[slot];
+            slot -= lowest_bit(slot);
+        }
+
+        sum
+    }
+
+    /// The sum of the numbers in `range`.
+    pub fn range_sum(&self, range: Range<usize>) -> i64 {
+        if range.is_empty() {
+            return 0;
+        }
+
+        self.prefix_sum(range.end) - self.prefix_sum(range.start)
+    }
+}
+
+impl FromIterator<i64> for FenwickTree {
+    fn from_iter<I: IntoIterator<Item = i64>>(iter: I) -> Self {
+        let mut sums: Vec<i64> = std::iter::once(0).chain(iter).collect();
+
+        // pushing each slot's total up to the next slot covering it builds the tree in O(n)
+        for slot in 1..sums.len() {
+            let parent = slot + lowest_bit(slot);
+            if parent < sums.len() {
+                sums[parent] += sums[slot];
+            }
+        }
+
+        Self { sums }
+    }
+}
+
+fn lowest_bit(n: usize) -> usize {
+    n & n.wrapping_neg()
+}
+
+/// A list which can combine any range of itself in O(log n), such as taking its minimum, maximum,
+/// or sum, while still changing one value at a time in O(log n).
+///
+/// `combine` has to be associative, and `identity` has to leave any value unchanged when combined
+/// with it, like 0 for sums.
+#[derive(Debug, Clone)]
+pub struct SegmentTree<T> {
+    // the values are the leaves, in the second half, and each slot before them combines its two
+    // children, at `2 * slot` and `2 * slot + 1`
+    nodes: Vec<T>,
+    identity: T,
+    combine: fn(T, T) -> T,
+}
+
+impl<T: Copy> SegmentTree<T> {
+    pub fn new(values: &[T], identity: T, combine: fn(T, T) -> T) -> Self {
+        let len = values.len();
+        let mut nodes = vec![identity; len];
+        nodes.extend_from_slice(values);
+        for slot in (1..len).rev() {
+            nodes[slot] = combine(nodes[2 * slot], nodes[2 * slot + 1]);
+        }
+
+        Self {
+            nodes,
+            identity,
+            combine,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The value at `index`. Panics when that's out of bounds.
+    pub fn get(&self, index: usize) -> T {
+        assert!(index < self.len(), "{index} is out of bounds");
+
+        self.nodes[self.len() + index]
+    }
+
+    /// Replaces the value at `index`. Panics when that's out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        assert!(index < self.len(), "{index} is out of bounds");
+
+        let mut slot = self.len() + index;
+        self.nodes[slot] = value;
+        while slot > 1 {
+            slot /= 2;
+            self.nodes[slot] = (self.combine)(self.nodes[2 * slot], self.nodes[2 * slot + 1]);
+        }
+    }
+
+    /// Every value in `range` combined, in order. An empty range is the identity. Panics when the
+    /// range goes past the end.
+    pub fn query(&self, range: Range<usize>) -> T {
+        assert!(range.end <= self.len(), "{range:?} is out of bounds");
+
+        // climb from both ends of the range towards each other, keeping each side's values in
+        // order in case combining them isn't commutative
+        let (mut left, mut right) = (range.start + self.len(), range.end + self.len());
+        let (mut from_left, mut from_right) = (self.identity, self.identity);
+        while left < right {
+            if left % 2 == 1 {
+                from_left = (self.combine)(from_left, self.nodes[left]);
+                left += 1;
+            }
+            if right % 2 == 1 {
+                right -= 1;
+                from_right = (self.combine)(self.nodes[right], from_right);
+            }
+            left /= 2;
+            right /= 2;
+        }
+
+        (self.combine)(from_left, from_right)
+    }
+}
+
+impl SegmentTree<i64> {
+    /// Answers the smallest value in a range. An empty range is `i64::MAX`.
+    pub fn min(values: &[i64]) -> Self {
+        Self::new(values, i64::MAX, i64::min)
+    }
+
+    /// Answers the largest value in a range. An empty range is `i64::MIN`.
+    pub fn max(values: &[i64]) -> Self {
+        Self::new(values, i64::MIN, i64::max)
+    }
+
+    /// Answers the sum of a range.
+    pub fn sum(values: &[i64]) -> Self {
+        Self::new(values, 0, |a, b| a + b)
+    }
+}
+
+/// Lots of rectangles added onto a grid, like overlapping claims on a piece of fabric. Adding a
+/// rectangle is O(1) however big it is, and working out every cell's total afterwards is
+/// O(width * height).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceGrid {
+    // each rectangle only marks its corners, which the running totals in `resolve` spread across it
+    deltas: Grid<i64>,
+    width: usize,
+    height: usize,
+}
+
+impl DifferenceGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            deltas: Grid::new(width + 1, height + 1, 0),
+            width,
+            height,
+        }
+    }
+
+    /// Adds `amount` to every cell in the rectangle covering columns `xs` and rows `ys`. Panics
+    /// when it goes past the edge of the grid.
+    pub fn add_rect(&mut self, xs: Range<usize>, ys: Range<usize>, amount: i64) {
+        assert!(
+            xs.end <= self.width && ys.end <= self.height,
+            "{xs:?} by {ys:?} is out of bounds"
+        );
+        if xs.is_empty() || ys.is_empty() {
+            return;
+        }
+
+        self.deltas[(xs.start, ys.start)] += amount;
+        self.deltas[(xs.end, ys.start)] -= amount;
+        self.deltas[(xs.start, ys.end)] -= amount;
+        self.deltas[(xs.end, ys.end)] += amount;
+    }
+
+    /// Every cell's total from all the rectangles added.
+    pub fn resolve(&self) -> Grid<i64> {
+        let mut totals = Grid::new(self.width, self.height, 0);
+        for y in 0..self.height {
+            let mut row_total = 0;
+            for x in 0..self.width {
+                row_total += self.deltas[(x, y)];
+                let above = if y == 0 { 0 } else { totals[(x, y - 1)] };
+                totals[(x, y)] = above + row_total;
+            }
+        }
+
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn fenwick_sums() {
+        let mut tree: FenwickTree = [3, 1, 4, 1, 5].into_iter().collect();
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.prefix_sum(0), 0);
+        assert_eq!(tree.prefix_sum(3), 8);
+        assert_eq!(tree.range_sum(1..4), 6);
+        assert_eq!(tree.range_sum(2..2), 0);
+
+        tree.add(2, -4);
+        assert_eq!(tree.prefix_sum(5), 10);
+        assert_eq!(tree.range_sum(2..3), 0);
+    }
+
+    #[test]
+    fn fenwick_counts_inversions() {
+        // each value counts the bigger values seen before it
+        let values = [3, 1, 2, 0];
+        let mut seen = FenwickTree::new(values.len());
+        let mut inversions = 0;
+        for value in values {
+            inversions += seen.range_sum(value + 1..values.len());
+            seen.add(value, 1);
+        }
+
+        assert_eq!(inversions, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "5 is out of bounds")]
+    fn fenwick_add_out_of_bounds() {
+        FenwickTree::new(5).add(5, 1);
+    }
+
+    #[test]
+    fn segment_tree_queries() {
+        let values = [5, 2, 8, -1, 3];
+        let min = SegmentTree::min(&values);
+        let max = SegmentTree::max(&values);
+        let sum = SegmentTree::sum(&values);
+
+        assert_eq!(min.query(0..5), -1);
+        assert_eq!(min.query(0..3), 2);
+        assert_eq!(max.query(1..4), 8);
+        assert_eq!(max.query(3..5), 3);
+        assert_eq!(sum.query(1..5), 12);
+        assert_eq!(sum.query(2..2), 0);
+        assert_eq!(min.query(4..4), i64::MAX);
+    }
+
+    #[test]
+    fn segment_tree_set() {
+        let mut min = SegmentTree::min(&[5, 2, 8]);
+        min.set(1, 9);
+        assert_eq!(min.get(1), 9);
+        assert_eq!(min.query(0..3), 5);
+        assert_eq!(min.query(1..3), 8);
+    }
+
+    #[test]
+    fn segment_tree_keeps_order() {
+        // each value is a step `x -> a * x + b`, and doing them in a different order gives a
+        // different result, so this checks they're combined left to right
+        let then = |(a1, b1): (i64, i64), (a2, b2): (i64, i64)| (a1 * a2, b1 * a2 + b2);
+        let steps = [(2, 1), (3, 0), (1, 5), (2, 2), (1, -3)];
+        let tree = SegmentTree::new(&steps, (1, 0), then);
+
+        for start in 0..steps.len() {
+            for end in start..=steps.len() {
+                let expected = steps[start..end].iter().fold((1, 0), |f, &g| then(f, g));
+                assert_eq!(tree.query(start..end), expected, "{start}..{end}");
+            }
+        }
+    }
+
+    #[test]
+    fn difference_grid_counts_overlaps() {
+        // the fabric claims #1 @ 1,3: 4x4, #2 @ 3,1: 4x4, and #3 @ 5,5: 2x2
+        let mut fabric = DifferenceGrid::new(8, 8);
+        fabric.add_rect(1..5, 3..7, 1);
+        fabric.add_rect(3..7, 1..5, 1);
+        fabric.add_rect(5..7, 5..7, 1);
+
+        let claims = fabric.resolve();
+        assert_eq!(claims.cells().filter(|&(_, &count)| count >= 2).count(), 4);
+        assert_eq!(claims[(0, 0)], 0);
+        assert_eq!(claims[(1, 3)], 1);
+        assert_eq!(claims[(3, 3)], 2);
+        assert_eq!(claims[(6, 6)], 1);
+    }
+
+    proptest! {
+        #[test]
+        fn difference_grid_matches_adding_cells(
+            rects in vec((0..10_usize, 0..10_usize, 0..10_usize, 0..10_usize, -5..5_i64), 0..10),
+        ) {
+            let mut lazy = DifferenceGrid::new(10, 10);
+            let mut eager = Grid::new(10, 10, 0);
+            for (x1, x2, y1, y2, amount) in rects {
+                let (xs, ys) = (x1.min(x2)..x1.max(x2), y1.min(y2)..y1.max(y2));
+                lazy.add_rect(xs.clone(), ys.clone(), amount);
+                for x in xs {
+                    for y in ys.clone() {
+                        eager[(x, y)] += amount;
+                    }
+                }
+            }
+
+            prop_assert_eq!(lazy.resolve(), eager);
+        }
+
+        #[test]
+        fn fenwick_matches_summing(
+            values in vec(-100..100_i64, 1..40),
+            updates in vec((0..40_usize, -100..100_i64), 0..10),
+            bounds in (0..40_usize, 0..40_usize),
+        ) {
+            let mut values = values;
+            let mut tree: FenwickTree = values.iter().copied().collect();
+            for (index, delta) in updates {
+                let index = index % values.len();
+                values[index] += delta;
+                tree.add(index, delta);
+            }
+
+            let (start, end) = (bounds.0 % values.len(), bounds.1 % (values.len() + 1));
+            let range = start.min(end)..start.max(end);
+            prop_assert_eq!(tree.range_sum(range.clone()), values[range].iter().sum::<i64>());
+        }
+
+        #[test]
+        fn segment_tree_matches_scanning(
+            values in vec(-100..100_i64, 1..40),
+            updates in vec((0..40_usize, -100..100_i64), 0..10),
+            bounds in (0..40_usize, 0..40_usize),
+        ) {
+            let mut values = values;
+            let mut min = SegmentTree::min(&values);
+            let mut max = SegmentTree::max(&values);
+            for (index, value) in updates {
+                let index = index % values.len();
+                values[index] = value;
+                min.set(index, value);
+                max.set(index, value);
+            }
+
+            let (start, end) = (bounds.0 % values.len(), bounds.1 % values.len());
+            let range = start.min(end)..start.max(end) + 1;
+            prop_assert_eq!(min.query(range.clone()), *values[range.clone()].iter().min().unwrap());
+            prop_assert_eq!(max.query(range.clone()), *values[range].iter().max().unwrap());
+        }
+    }
+}
@@ -0,0 +1,496 @@
+//! Inclusive ranges of numbers, and a set of them kept sorted and merged so overlapping ranges only
+//! count once.
+
+use std::ops::RangeInclusive;
+
+/// The numbers in both ranges, or None when they don't overlap.
+pub fn intersect_ranges(
+    a: &RangeInclusive<usize>,
+    b: &RangeInclusive<usize>,
+) -> Option<RangeInclusive<usize>> {
+    let (start, end) = (*a.start().max(b.start()), *a.end().min(b.end()));
+    (start <= end).then_some(start..=end)
+}
+
+/// The numbers in `range` but not in `cut`, as what's left before the cut and what's left after
+/// it. Either can be None, when the cut reaches that end of the range.
+pub fn subtract_range(
+    range: &RangeInclusive<usize>,
+    cut: &RangeInclusive<usize>,
+) -> (Option<RangeInclusive<usize>>, Option<RangeInclusive<usize>>) {
+    if range.is_empty() {
+        return (None, None);
+    }
+    if cut.is_empty() {
+        return (Some(range.clone()), None);
+    }
+
+    let (start, end) = (*range.start(), *range.end());
+    let before = (*cut.start() > start).then(|| start..=end.min(cut.start() - 1));
+    let after = (*cut.end() < end).then(|| start.max(cut.end() + 1)..=end);
+
+    (before, after)
+}
+
+/// Splits `range` into the numbers below `at` and those from `at` on. Either side can be None,
+/// when `at` is past that end of the range.
+pub fn split_range(
+    range: &RangeInclusive<usize>,
+    at: usize,
+) -> (Option<RangeInclusive<usize>>, Option<RangeInclusive<usize>>) {
+    if range.is_empty() {
+        return (None, None);
+    }
+
+    let (start, end) = (*range.start(), *range.end());
+    let below = (at > start).then(|| start..=end.min(at - 1));
+    let above = (at <= end).then(|| start.max(at)..=end);
+
+    (below, above)
+}
+
+/// A set of numbers, stored as sorted ranges which never overlap or touch. Ranges are inclusive,
+/// like most puzzle inputs write them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<RangeInclusive<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every number in `range`, merging it with any ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // the ranges which end before this one starts, and the ranges which start after it ends
+        let first = self
+            .ranges
+            .partition_point(|r| r.end().saturating_add(1) < *range.start());
+        let last = self
+            .ranges
+            .partition_point(|r| *r.start() <= range.end().saturating_add(1));
+
+        // everything in between gets merged
+        let start = self.ranges[first..last]
+            .first()
+            .map_or(*range.start(), |r| *r.start().min(range.start()));
+        let end = self.ranges[first..last]
+            .last()
+            .map_or(*range.end(), |r| *r.end().max(range.end()));
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    /// Adds every range in `other`.
+    pub fn merge(&mut self, other: &RangeSet) {
+        for range in other.iter() {
+            self.insert(range.clone());
+        }
+    }
+
+    /// Takes out every number in `range`, splitting any range it cuts through the middle of.
+    pub fn remove(&mut self, range: RangeInclusive<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // only the ranges overlapping the cut change
+        let first = self.ranges.partition_point(|r| r.end() < range.start());
+        let last = self.ranges.partition_point(|r| r.start() <= range.end());
+
+        let left: Vec<RangeInclusive<usize>> = self.ranges[first..last]
+            .iter()
+            .flat_map(|r| {
+                let (before, after) = subtract_range(r, &range);
+                before.into_iter().chain(after)
+            })
+            .collect();
+        self.ranges.splice(first..last, left);
+    }
+
+    /// Splits the set into the numbers below `at` and those from `at` on.
+    pub fn split_at(&self, at: usize) -> (RangeSet, RangeSet) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for range in &self.ranges {
+            let (before, after) = split_range(range, at);
+            below.extend(before);
+            above.extend(after);
+        }
+
+        (RangeSet { ranges: below }, RangeSet { ranges: above })
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        // the only range which could contain n is the last one starting at or before it
+        match self.ranges.partition_point(|r| *r.start() <= n) {
+            0 => false,
+            pos => self.ranges[pos - 1].contains(&n),
+        }
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.end() - r.start() + 1).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers in `range` are in the set.
+    pub fn count_in(&self, range: RangeInclusive<usize>) -> usize {
+        if range.is_empty() {
+            return 0;
+        }
+
+        // only the ranges overlapping this one count, and only the ends can stick out of it
+        let first = self.ranges.partition_point(|r| r.end() < range.start());
+        let last = self.ranges.partition_point(|r| r.start() <= range.end());
+        self.ranges[first..last]
+            .iter()
+            .map(|r| r.end().min(range.end()) - r.start().max(range.start()) + 1)
+            .sum()
+    }
+
+    /// The `n`th number in the set, counting from 0 at the lowest, like `Iterator::nth`. None when
+    /// the set has `n` numbers or fewer.
+    pub fn nth(&self, mut n: usize) -> Option<usize> {
+        // skip whole ranges until reaching the one n lands in
+        for range in &self.ranges {
+            let len = range.end() - range.start() + 1;
+            if n < len {
+                return Some(range.start() + n);
+            }
+            n -= len;
+        }
+
+        None
+    }
+
+    /// The disjoint ranges, from lowest to highest.
+    pub fn iter(&self) -> impl Iterator<Item = &RangeInclusive<usize>> {
+        self.ranges.iter()
+    }
+
+    /// Every number in either set.
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+        union.merge(other);
+        union
+    }
+
+    /// Every number in both sets.
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(ra), Some(rb)) = (a.peek(), b.peek()) {
+            let start = *ra.start().max(rb.start());
+            let end = *ra.end().min(rb.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+
+            // whichever ends first can't overlap anything else
+            if ra.end() < rb.end() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        RangeSet { ranges }
+    }
+
+    /// Every number in this set, but not in `other`.
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+
+        for range in &self.ranges {
+            let (mut start, end) = (*range.start(), *range.end());
+            let mut cut_to_the_end = false;
+
+            // skip what ends before this range, then cut out what overlaps it
+            while others.next_if(|o| *o.end() < start).is_some() {}
+            while let Some(o) = others.next_if(|o| *o.start() <= end && *o.end() < end) {
+                if *o.start() > start {
+                    ranges.push(start..=o.start() - 1);
+                }
+                start = o.end() + 1;
+            }
+
+            // the last overlap may carry on into the next range, so it's left for that one
+            if let Some(o) = others.peek().filter(|o| *o.start() <= end) {
+                if *o.start() > start {
+                    ranges.push(start..=o.start() - 1);
+                }
+                cut_to_the_end = true;
+            }
+
+            if !cut_to_the_end {
+                ranges.push(start..=end);
+            }
+        }
+
+        RangeSet { ranges }
+    }
+}
+
+/// Sorts the ranges first, then merges each into the last, so collecting is O(n log n) rather
+/// than inserting each in turn.
+impl FromIterator<RangeInclusive<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<usize>>>(iter: I) -> Self {
+        // Sorting simplifies merging, since each range can only overlap the ones just before it.
+        let mut sorted: Vec<_> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_unstable_by_key(|r| *r.start());
+
+        let mut ranges: Vec<RangeInclusive<usize>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                // When a new range starts inside (or right after) the previous, extend previous
+                Some(previous) if *range.start() <= previous.end().saturating_add(1) => {
+                    if range.end() > previous.end() {
+                        *previous = *previous.start()..=*range.end();
+                    }
+                }
+                _ => ranges.push(range),
+            }
+        }
+
+        RangeSet { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    fn range_set(ranges: &[(usize, usize)]) -> RangeSet {
+        ranges.iter().map(|&(start, end)| start..=end).collect()
+    }
+
+    #[test]
+    fn range_set_merges_overlapping_and_touching_ranges() {
+        let set = range_set(&[(10, 14), (3, 5), (12, 18), (16, 20), (6, 7)]);
+
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), [3..=7, 10..=20]);
+        assert_eq!(set.len(), 16);
+        assert!(set.contains(3));
+        assert!(set.contains(20));
+        assert!(!set.contains(8));
+        assert!(!set.contains(21));
+    }
+
+    #[test]
+    fn range_set_nested_ranges() {
+        // whichever way round they come, the inner range disappears into the outer one
+        for set in [range_set(&[(3, 4), (2, 5)]), range_set(&[(2, 5), (3, 4)])] {
+            assert_eq!(set, range_set(&[(2, 5)]));
+            assert!(!set.contains(1));
+            assert!((2..=5).all(|n| set.contains(n)));
+            assert!(!set.contains(6));
+        }
+
+        let mut set = range_set(&[(2, 5)]);
+        set.insert(3..=4);
+        assert_eq!(set, range_set(&[(2, 5)]));
+    }
+
+    #[test]
+    fn range_set_count_in() {
+        let set = range_set(&[(3, 5), (10, 14), (20, 22)]);
+
+        assert_eq!(set.count_in(0..=usize::MAX), set.len());
+        assert_eq!(set.count_in(4..=12), 5);
+        assert_eq!(set.count_in(6..=9), 0);
+        assert_eq!(set.count_in(11..=11), 1);
+        assert_eq!(set.count_in(22..=100), 1);
+        assert_eq!(set.count_in(0..=2), 0);
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = 12..=4;
+        assert_eq!(set.count_in(backwards), 0);
+    }
+
+    #[test]
+    fn range_set_nth() {
+        let set = range_set(&[(3, 5), (10, 11), (20, 20)]);
+        let numbers: Vec<usize> = (0..set.len()).map(|n| set.nth(n).unwrap()).collect();
+
+        assert_eq!(numbers, [3, 4, 5, 10, 11, 20]);
+        assert_eq!(set.nth(6), None);
+        assert_eq!(RangeSet::new().nth(0), None);
+        assert_eq!(range_set(&[(0, usize::MAX - 1)]).nth(7), Some(7));
+    }
+
+    #[test]
+    fn range_set_insert() {
+        let mut set = range_set(&[(3, 5), (10, 14), (20, 22)]);
+
+        set.insert(7..=8);
+        assert_eq!(set, range_set(&[(3, 5), (7, 8), (10, 14), (20, 22)]));
+
+        set.insert(6..=11);
+        assert_eq!(set, range_set(&[(3, 14), (20, 22)]));
+
+        set.insert(0..=0);
+        set.insert(25..=30);
+        assert_eq!(set, range_set(&[(0, 0), (3, 14), (20, 22), (25, 30)]));
+    }
+
+    #[test]
+    fn range_set_operations() {
+        let a = range_set(&[(0, 10), (20, 30)]);
+        let b = range_set(&[(5, 8), (9, 25), (40, 40)]);
+
+        assert_eq!(a.union(&b), range_set(&[(0, 30), (40, 40)]));
+        assert_eq!(a.intersection(&b), range_set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), range_set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), range_set(&[(11, 19), (40, 40)]));
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn single_range_operations() {
+        assert_eq!(intersect_ranges(&(0..=10), &(5..=20)), Some(5..=10));
+        assert_eq!(intersect_ranges(&(0..=4), &(5..=20)), None);
+
+        assert_eq!(
+            subtract_range(&(0..=10), &(3..=5)),
+            (Some(0..=2), Some(6..=10))
+        );
+        assert_eq!(subtract_range(&(0..=10), &(0..=5)), (None, Some(6..=10)));
+        assert_eq!(subtract_range(&(0..=10), &(8..=20)), (Some(0..=7), None));
+        assert_eq!(subtract_range(&(3..=5), &(0..=10)), (None, None));
+        assert_eq!(
+            subtract_range(&(3..=5), &(usize::MAX..=usize::MAX)),
+            (Some(3..=5), None)
+        );
+
+        assert_eq!(split_range(&(0..=10), 5), (Some(0..=4), Some(5..=10)));
+        assert_eq!(split_range(&(0..=10), 0), (None, Some(0..=10)));
+        assert_eq!(split_range(&(0..=10), 11), (Some(0..=10), None));
+    }
+
+    #[test]
+    fn range_set_remove_and_split() {
+        let mut set = range_set(&[(0, 10), (20, 30), (40, 50)]);
+        set.remove(5..=25);
+        assert_eq!(set, range_set(&[(0, 4), (26, 30), (40, 50)]));
+
+        set.remove(42..=42);
+        assert_eq!(set, range_set(&[(0, 4), (26, 30), (40, 41), (43, 50)]));
+
+        let (below, above) = set.split_at(28);
+        assert_eq!(below, range_set(&[(0, 4), (26, 27)]));
+        assert_eq!(above, range_set(&[(28, 30), (40, 41), (43, 50)]));
+    }
+
+    #[test]
+    fn mapping_ranges_piecewise() {
+        // move 10..=19 up by 100 and 30..=39 down by 30, leaving everything else where it is
+        let pieces = [(10..=19, 100_isize), (30..=39, -30)];
+        let seeds = range_set(&[(5, 14), (25, 35)]);
+
+        let mut unmoved = seeds.clone();
+        let mut mapped = RangeSet::new();
+        for (from, offset) in &pieces {
+            for range in seeds.iter() {
+                if let Some(moving) = intersect_ranges(range, from) {
+                    let shift = |n: usize| n.checked_add_signed(*offset).unwrap();
+                    mapped.insert(shift(*moving.start())..=shift(*moving.end()));
+                }
+            }
+            unmoved.remove(from.clone());
+        }
+        mapped.merge(&unmoved);
+
+        assert_eq!(mapped, range_set(&[(0, 9), (25, 29), (110, 114)]));
+    }
+
+    /// Short ranges, close enough together that plenty of them overlap.
+    fn ranges() -> impl Strategy<Value = Vec<(usize, usize)>> {
+        vec((0..200_usize, 0..20_usize), 0..20)
+            .prop_map(|ranges| ranges.into_iter().map(|(s, len)| (s, s + len)).collect())
+    }
+
+    fn ids(ranges: &[(usize, usize)]) -> BTreeSet<usize> {
+        ranges
+            .iter()
+            .flat_map(|&(start, end)| start..=end)
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn merged_ranges_never_overlap(ranges in ranges()) {
+            let set = range_set(&ranges);
+
+            for pair in set.ranges.windows(2) {
+                prop_assert!(pair[0].end() + 1 < *pair[1].start(), "{:?} overlaps", pair);
+            }
+        }
+
+        #[test]
+        fn inserting_matches_collecting(ranges in ranges()) {
+            let mut set = RangeSet::new();
+            for &(start, end) in &ranges {
+                set.insert(start..=end);
+            }
+
+            prop_assert_eq!(set, range_set(&ranges));
+        }
+
+        #[test]
+        fn range_set_operations_match_sets(a in ranges(), b in ranges()) {
+            let (set_a, set_b) = (range_set(&a), range_set(&b));
+            let (ids_a, ids_b) = (ids(&a), ids(&b));
+            let ids_of = |set: RangeSet| set.iter().flat_map(|r| r.clone()).collect::<BTreeSet<_>>();
+
+            prop_assert_eq!(set_a.len(), ids_a.len());
+            prop_assert_eq!(ids_of(set_a.union(&set_b)), &ids_a | &ids_b);
+            prop_assert_eq!(ids_of(set_a.intersection(&set_b)), &ids_a & &ids_b);
+            prop_assert_eq!(ids_of(set_a.difference(&set_b)), &ids_a - &ids_b);
+        }
+
+        #[test]
+        fn removing_matches_difference(a in ranges(), cut in (0..220_usize, 0..40_usize)) {
+            let (start, end) = (cut.0, cut.0 + cut.1);
+            let mut set = range_set(&a);
+            set.remove(start..=end);
+
+            prop_assert_eq!(&set, &range_set(&a).difference(&range_set(&[(start, end)])));
+            for pair in set.ranges.windows(2) {
+                prop_assert!(pair[0].end() + 1 < *pair[1].start(), "{:?} overlaps", pair);
+            }
+        }
+
+        #[test]
+        fn counting_in_matches_sets(a in ranges(), span in (0..220_usize, 0..40_usize)) {
+            let (start, end) = (span.0, span.0 + span.1);
+            let expected = ids(&a).range(start..=end).count();
+            prop_assert_eq!(range_set(&a).count_in(start..=end), expected);
+        }
+
+        #[test]
+        fn splitting_keeps_every_number(a in ranges(), at in 0..230_usize) {
+            let (below, above) = range_set(&a).split_at(at);
+
+            prop_assert!(below.iter().all(|r| *r.end() < at));
+            prop_assert!(above.iter().all(|r| *r.start() >= at));
+            prop_assert_eq!(below.union(&above), range_set(&a));
+        }
+    }
+}
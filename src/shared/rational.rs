@@ -0,0 +1,234 @@
+//! Exact fractions, for maths which floats would round off.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// A fraction, always kept in lowest terms with a positive denominator, so equal fractions are
+/// equal field by field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numer: 0, denom: 1 };
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    /// Panics if `denom` is 0, the same as dividing by zero would.
+    pub fn new(numer: i128, denom: i128) -> Self {
+        assert!(denom != 0, "denominator should not be zero");
+
+        let divisor = gcd(numer.unsigned_abs(), denom.unsigned_abs()) as i128;
+        let sign = denom.signum();
+        Self {
+            numer: sign * numer / divisor,
+            denom: sign * denom / divisor,
+        }
+    }
+
+    pub fn numer(self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denom == 1
+    }
+
+    /// The whole number, if this is one.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            numer: self.numer.abs(),
+            denom: self.denom,
+        }
+    }
+
+    /// One over this. Panics for zero.
+    pub fn recip(self) -> Self {
+        Self::new(self.denom, self.numer)
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self { numer: n, denom: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::from(i128::from(n))
+    }
+}
+
+impl From<usize> for Rational {
+    fn from(n: usize) -> Self {
+        Self::from(n as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.numer * other.denom + other.numer * self.denom,
+            self.denom * other.denom,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(self.numer * other.numer, self.denom * other.denom)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// Panics when dividing by zero.
+    fn div(self, other: Self) -> Self {
+        Self::new(self.numer * other.denom, self.denom * other.numer)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Rational {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        (self.numer * other.denom).cmp(&(other.numer * self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Whole numbers are written without a denominator, like `3` rather than `3/1`.
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom)
+    }
+
+    #[test]
+    fn normalized_to_lowest_terms() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(3, -6), r(-1, 2));
+        assert_eq!(r(0, -5), Rational::ZERO);
+        assert_eq!((r(-3, -6).numer(), r(-3, -6).denom()), (1, 2));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(2, 3), r(-1, 6));
+        assert_eq!(r(2, 3) * r(3, 4), r(1, 2));
+        assert_eq!(r(1, 2) / r(-1, 4), Rational::from(-2_i64));
+        assert_eq!(-r(1, 2), r(-1, 2));
+
+        // a tenth added ten times is exactly one, unlike with floats
+        let tenths = (0..10).fold(Rational::ZERO, |sum, _| sum + r(1, 10));
+        assert_eq!(tenths, Rational::ONE);
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert_eq!(r(5, 2).max(r(7, 3)), r(5, 2));
+        assert_eq!(r(-5, 2).abs(), r(5, 2));
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(r(6, 3).to_integer(), Some(2));
+        assert_eq!(r(7, 3).to_integer(), None);
+        assert_eq!(r(6, 3).to_string(), "2");
+        assert_eq!(r(-7, 3).to_string(), "-7/3");
+    }
+
+    #[test]
+    #[should_panic(expected = "denominator should not be zero")]
+    fn zero_denominator_panics() {
+        Rational::new(1, 0);
+    }
+}
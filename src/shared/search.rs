@@ -0,0 +1,319 @@
+//! Searches over graphs given as a function from a node to its neighbors: shortest paths, every
+//! pair's distance, and an order that puts each node after the ones it depends on.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+use thiserror::Error;
+
+/// Finds the cheapest cost from `start` to every reachable node, where `neighbors` lists the nodes
+/// one step away along with the cost of that step.
+pub fn dijkstra<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    search(start, None, neighbors).0
+}
+
+/// Finds the cheapest path from `start` to `goal`, returning its cost and every node along it,
+/// including both ends. None if the goal can't be reached.
+pub fn dijkstra_path<N, I>(
+    start: N,
+    goal: &N,
+    neighbors: impl FnMut(&N) -> I,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let (costs, previous) = search(start, Some(goal), neighbors);
+    let cost = *costs.get(goal)?;
+
+    let mut path = vec![goal.clone()];
+    while let Some(node) = previous.get(path.last().expect("path starts with the goal")) {
+        path.push(node.clone());
+    }
+    path.reverse();
+
+    Some((cost, path))
+}
+
+/// The cheapest cost to each node, and the node each was reached from. Stops early once `goal` is
+/// settled, if there is one.
+fn search<N, I>(
+    start: N,
+    goal: Option<&N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> (HashMap<N, usize>, HashMap<N, N>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut costs = HashMap::from([(start.clone(), 0)]);
+    let mut previous = HashMap::new();
+    let mut heap = BinaryHeap::from([Visit {
+        cost: 0,
+        node: start,
+    }]);
+
+    while let Some(Visit { cost, node }) = heap.pop() {
+        // a cheaper way here was already found, so this visit is stale
+        if costs.get(&node).is_some_and(|&best| best < cost) {
+            continue;
+        }
+        if goal == Some(&node) {
+            break;
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                costs.insert(next.clone(), next_cost);
+                previous.insert(next.clone(), node.clone());
+                heap.push(Visit {
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    (costs, previous)
+}
+
+/// A node waiting to be visited, ordered so the heap pops the cheapest first.
+struct Visit<N> {
+    cost: usize,
+    node: N,
+}
+
+impl<N> Ord for Visit<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed, so heap will be a min heap.
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl<N> PartialOrd for Visit<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> PartialEq for Visit<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<N> Eq for Visit<N> {}
+
+/// The cheapest cost between every pair of nodes `0..len`, as `distances[from][to]`, by the
+/// Floyd-Warshall algorithm. Edges are `(from, to, cost)` and only go one way. None where `to`
+/// can't be reached from `from`.
+///
+/// Takes O(n³), so it's for small graphs where the same distances are needed over and over.
+pub fn floyd_warshall(
+    len: usize,
+    edges: impl IntoIterator<Item = (usize, usize, usize)>,
+) -> Vec<Vec<Option<usize>>> {
+    let mut distances = vec![vec![None; len]; len];
+    for (node, row) in distances.iter_mut().enumerate() {
+        row[node] = Some(0);
+    }
+    for (from, to, cost) in edges {
+        let distance = &mut distances[from][to];
+        *distance = Some(distance.map_or(cost, |d: usize| d.min(cost)));
+    }
+
+    // let each node in turn be a stop along the way between every pair
+    for via in 0..len {
+        let from_via = distances[via].clone();
+        for row in &mut distances {
+            let Some(to_via) = row[via] else {
+                continue;
+            };
+            for (distance, from_via) in row.iter_mut().zip(&from_via) {
+                if let Some(from_via) = from_via {
+                    let through = to_via + from_via;
+                    if distance.is_none_or(|d| through < d) {
+                        *distance = Some(through);
+                    }
+                }
+            }
+        }
+    }
+
+    distances
+}
+
+/// The graph given to `topo_sort` loops back on itself, so no node in the loop can go first.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the graph has a cycle, so it can't be ordered")]
+pub struct CycleError;
+
+/// Orders the nodes so every edge `(from, to)` has `from` before `to`. Nodes only mentioned by an
+/// edge are included too. Ties keep the order the nodes were first seen in.
+pub fn topo_sort<N: Clone + Eq + Hash>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, CycleError> {
+    let mut ids: HashMap<N, usize> = HashMap::new();
+    let mut all_nodes: Vec<N> = Vec::new();
+    let mut id = |node: N| {
+        *ids.entry(node.clone()).or_insert_with(|| {
+            all_nodes.push(node);
+            all_nodes.len() - 1
+        })
+    };
+
+    for node in nodes {
+        id(node);
+    }
+    let edges: Vec<(usize, usize)> = edges.into_iter().map(|(a, b)| (id(a), id(b))).collect();
+
+    let mut outgoing = vec![Vec::new(); all_nodes.len()];
+    let mut incoming_counts = vec![0; all_nodes.len()];
+    for (from, to) in edges {
+        outgoing[from].push(to);
+        incoming_counts[to] += 1;
+    }
+
+    // repeatedly take a node nothing is left pointing at, which frees up the nodes it points at
+    let mut ready: Vec<usize> = (0..all_nodes.len())
+        .filter(|&node| incoming_counts[node] == 0)
+        .rev()
+        .collect();
+    let mut order = Vec::with_capacity(all_nodes.len());
+    while let Some(node) = ready.pop() {
+        order.push(node);
+        for &next in outgoing[node].iter().rev() {
+            incoming_counts[next] -= 1;
+            if incoming_counts[next] == 0 {
+                ready.push(next);
+            }
+        }
+    }
+
+    // anything never freed up is stuck in a cycle
+    if order.len() < all_nodes.len() {
+        return Err(CycleError);
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|node| all_nodes[node].clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+    use crate::shared::Grid;
+
+    /// A small weighted graph, where going around through b is cheaper than going straight to c.
+    fn graph(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 10)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_costs() {
+        let costs = dijkstra('a', graph);
+
+        assert_eq!(costs[&'a'], 0);
+        assert_eq!(costs[&'c'], 3);
+        assert_eq!(costs[&'d'], 4);
+        assert!(!costs.contains_key(&'e'));
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_path() {
+        assert_eq!(
+            dijkstra_path('a', &'d', graph),
+            Some((4, vec!['a', 'b', 'c', 'd']))
+        );
+        assert_eq!(dijkstra_path('a', &'a', graph), Some((0, vec!['a'])));
+        assert_eq!(dijkstra_path('b', &'a', graph), None);
+    }
+
+    #[test]
+    fn dijkstra_on_a_grid() {
+        let grid = Grid::from_str("15\n11", |c| c.to_digit(10).map(|d| d as usize)).unwrap();
+        let costs = dijkstra((0, 0), |&(x, y)| {
+            grid.neighbors(x, y)
+                .filter(move |&(nx, ny)| nx == x || ny == y)
+                .map(|next| (next, grid[next]))
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(costs[&(1, 0)], 5);
+        assert_eq!(costs[&(1, 1)], 2);
+    }
+
+    #[test]
+    fn floyd_warshall_finds_every_distance() {
+        // 0 -> 1 -> 2 is cheaper than 0 -> 2, and nothing leads to 3
+        let edges = [(0, 1, 1), (1, 2, 2), (0, 2, 5), (2, 0, 1), (3, 0, 1)];
+        let distances = floyd_warshall(4, edges);
+
+        assert_eq!(distances[0], [Some(0), Some(1), Some(3), None]);
+        assert_eq!(distances[2], [Some(1), Some(2), Some(0), None]);
+        assert_eq!(distances[3], [Some(1), Some(2), Some(4), Some(0)]);
+    }
+
+    proptest! {
+        #[test]
+        fn floyd_warshall_matches_dijkstra(
+            edges in vec((0..8_usize, 0..8_usize, 1..20_usize), 0..30),
+        ) {
+            let distances = floyd_warshall(8, edges.clone());
+            let neighbors = |&node: &usize| {
+                edges
+                    .iter()
+                    .filter(move |&&(from, _, _)| from == node)
+                    .map(|&(_, to, cost)| (to, cost))
+                    .collect::<Vec<_>>()
+            };
+
+            for (from, row) in distances.iter().enumerate() {
+                let costs = dijkstra(from, neighbors);
+                for (to, &distance) in row.iter().enumerate() {
+                    prop_assert_eq!(distance, costs.get(&to).copied());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn topo_sort_puts_edges_in_order() {
+        let edges = [("shirt", "tie"), ("tie", "jacket"), ("pants", "shoes")];
+        let order = topo_sort(["jacket", "shoes", "shirt", "pants", "socks"], edges).unwrap();
+
+        let position = |node| order.iter().position(|&n| n == node).unwrap();
+        for (from, to) in edges {
+            assert!(position(from) < position(to), "{from} before {to}");
+        }
+        assert_eq!(order.len(), 6);
+    }
+
+    #[test]
+    fn topo_sort_includes_nodes_only_in_edges() {
+        assert_eq!(topo_sort([], [(1, 2), (2, 3)]), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn topo_sort_finds_cycles() {
+        assert_eq!(topo_sort([0], [(0, 1), (1, 2), (2, 1)]), Err(CycleError));
+    }
+}
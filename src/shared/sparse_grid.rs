@@ -0,0 +1,176 @@
+//! A grid that only stores the cells which are set, for puzzles that spread over a plane with no
+//! edges.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use super::{NEIGHBOR_DELTAS, Point2};
+
+/// A grid with no edges, which only stores the cells that are set. Coordinates can be negative,
+/// and y grows downward like in `Grid`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<i64>, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many cells are set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cell at `point`, or None when it isn't set.
+    pub fn get(&self, point: Point2<i64>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    /// Sets the cell at `point`, returning what was there before.
+    pub fn insert(&mut self, point: Point2<i64>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    /// Clears the cell at `point`, returning what was there.
+    pub fn remove(&mut self, point: Point2<i64>) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    /// Every set cell along with its point, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(&point, cell)| (point, cell))
+    }
+
+    /// The smallest box holding every set cell, as its top left and bottom right corners, or None
+    /// when nothing is set.
+    pub fn bounds(&self) -> Option<(Point2<i64>, Point2<i64>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+
+        Some(points.fold((first, first), |(min, max), point| {
+            (
+                Point2::new(min.x.min(point.x), min.y.min(point.y)),
+                Point2::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        }))
+    }
+
+    /// The eight points around `point`, whether they're set or not.
+    pub fn neighbors(point: Point2<i64>) -> impl Iterator<Item = Point2<i64>> {
+        NEIGHBOR_DELTAS
+            .iter()
+            .map(move |&(dx, dy)| Point2::new(point.x + i64::from(dx), point.y + i64::from(dy)))
+    }
+
+    /// The set cells among the eight around `point`.
+    pub fn occupied_neighbors(
+        &self,
+        point: Point2<i64>,
+    ) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        Self::neighbors(point).filter_map(|neighbor| Some((neighbor, self.get(neighbor)?)))
+    }
+}
+
+impl<T> FromIterator<(Point2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2<i64>, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Draws the box around the set cells with a line per row, leaving cells which aren't set as `.`.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.get(Point2::new(x, y)) {
+                    Some(cell) => write!(f, "{cell}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn sparse_grid() -> SparseGrid<char> {
+        [((-2, -1), 'a'), ((1, 0), 'b'), ((0, 2), 'c')]
+            .into_iter()
+            .map(|(point, cell)| (Point2::from(point), cell))
+            .collect()
+    }
+
+    #[test]
+    fn sparse_grid_get_and_set() {
+        let mut grid = sparse_grid();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(Point2::new(-2, -1)), Some(&'a'));
+        assert_eq!(grid.get(Point2::new(0, 0)), None);
+
+        assert_eq!(grid.insert(Point2::new(1, 0), 'z'), Some('b'));
+        assert_eq!(grid.remove(Point2::new(0, 2)), Some('c'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.remove(Point2::new(0, 2)), None);
+    }
+
+    #[test]
+    fn sparse_grid_bounds() {
+        assert_eq!(
+            sparse_grid().bounds(),
+            Some((Point2::new(-2, -1), Point2::new(1, 2)))
+        );
+        assert_eq!(SparseGrid::<char>::new().bounds(), None);
+    }
+
+    #[test]
+    fn sparse_grid_neighbors() {
+        let grid = sparse_grid();
+        let around_origin: BTreeSet<(i64, i64)> = SparseGrid::<char>::neighbors(Point2::new(0, 0))
+            .map(<(i64, i64)>::from)
+            .collect();
+        assert_eq!(around_origin.len(), 8);
+        assert!(around_origin.contains(&(-1, -1)));
+        assert!(!around_origin.contains(&(0, 0)));
+
+        let occupied: BTreeSet<char> = grid
+            .occupied_neighbors(Point2::new(0, 1))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(occupied, BTreeSet::from(['b', 'c']));
+    }
+
+    #[test]
+    fn display_sparse_grid() {
+        assert_eq!(sparse_grid().to_string(), "a...\n...b\n....\n..c.\n");
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+    }
+}
@@ -0,0 +1,273 @@
+//! Rooted trees, for puzzles like orbit maps where everything hangs off one root, and questions
+//! come down to how deep something is or where two branches meet.
+
+use std::collections::VecDeque;
+
+use super::Graph;
+
+/// A tree over nodes `0..len`, hanging from `root`, which answers lowest common ancestor queries
+/// in O(log n) by binary lifting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    root: usize,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    children: Vec<Vec<usize>>,
+
+    // every node, with each one after its parent
+    top_down: Vec<usize>,
+
+    // `lifts[k][node]` is the ancestor 2^k steps above `node`, stopping at the root
+    lifts: Vec<Vec<usize>>,
+}
+
+impl Tree {
+    /// Hangs the nodes `0..len` from `root`, where each edge can be given either way round. None
+    /// when the edges don't make a tree, because they loop or leave some nodes unconnected.
+    pub fn new(
+        len: usize,
+        root: usize,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Option<Self> {
+        let mut adjacency = vec![Vec::new(); len];
+        let mut edge_count = 0;
+        for (a, b) in edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+            edge_count += 1;
+        }
+
+        // with exactly one edge fewer than there are nodes, reaching them all means there's no loop
+        if root >= len || edge_count != len - 1 {
+            return None;
+        }
+
+        let mut parents = vec![None; len];
+        let mut depths = vec![0; len];
+        let mut children = vec![Vec::new(); len];
+        let mut top_down = Vec::with_capacity(len);
+        let mut reached = vec![false; len];
+        reached[root] = true;
+
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            top_down.push(node);
+            for &next in &adjacency[node] {
+                if !reached[next] {
+                    reached[next] = true;
+                    parents[next] = Some(node);
+                    depths[next] = depths[node] + 1;
+                    children[node].push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if top_down.len() != len {
+            return None;
+        }
+
+        let first_lift: Vec<usize> = (0..len).map(|node| parents[node].unwrap_or(root)).collect();
+        let mut lifts = vec![first_lift];
+        while 1 << lifts.len() < len {
+            let last = lifts.last().expect("starts with the first lift");
+            lifts.push((0..len).map(|node| last[last[node]]).collect());
+        }
+
+        Some(Self {
+            root,
+            parents,
+            depths,
+            children,
+            top_down,
+            lifts,
+        })
+    }
+
+    /// Hangs an undirected graph's nodes from `root`. None when it isn't a tree.
+    pub fn from_graph(graph: &Graph, root: usize) -> Option<Self> {
+        Self::new(graph.len(), root, graph.edges())
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The node `node` hangs from, or None for the root.
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        self.parents[node]
+    }
+
+    /// How many steps `node` is below the root.
+    pub fn depth(&self, node: usize) -> usize {
+        self.depths[node]
+    }
+
+    pub fn children(&self, node: usize) -> &[usize] {
+        &self.children[node]
+    }
+
+    /// Every node above `node`, from its parent up to the root.
+    pub fn ancestors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.parents[node], |&node| self.parents[node])
+    }
+
+    /// Every node, with each one after its parent, for passing things down from the root.
+    pub fn top_down(&self) -> impl Iterator<Item = usize> + '_ {
+        self.top_down.iter().copied()
+    }
+
+    /// Every node, with each one before its parent, for adding things up over subtrees.
+    pub fn bottom_up(&self) -> impl Iterator<Item = usize> + '_ {
+        self.top_down.iter().rev().copied()
+    }
+
+    /// The ancestor `steps` above `node`, stopping at the root.
+    fn lift(&self, node: usize, steps: usize) -> usize {
+        self.lifts
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| steps & (1 << k) != 0)
+            .fold(node, |node, (_, lift)| lift[node])
+    }
+
+    /// The deepest node which both `a` and `b` are under, counting each as under itself.
+    pub fn lowest_common_ancestor(&self, a: usize, b: usize) -> usize {
+        let (shallow, deep) = if self.depths[a] <= self.depths[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let (mut a, mut b) = (
+            shallow,
+            self.lift(deep, self.depths[deep] - self.depths[shallow]),
+        );
+        if a == b {
+            return a;
+        }
+
+        // climb as far as possible while still below where they meet
+        for lift in self.lifts.iter().rev() {
+            if lift[a] != lift[b] {
+                (a, b) = (lift[a], lift[b]);
+            }
+        }
+
+        self.lifts[0][a]
+    }
+
+    /// How many edges are on the path from `a` to `b`.
+    pub fn distance(&self, a: usize, b: usize) -> usize {
+        let meet = self.lowest_common_ancestor(a, b);
+        self.depths[a] + self.depths[b] - 2 * self.depths[meet]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    /// The example orbit map, where `B)C` means C orbits B.
+    fn orbits() -> (Graph, Tree) {
+        let map = "COM)B B)C C)D D)E E)F B)G G)H D)I E)J J)K K)L K)YOU I)SAN";
+
+        let mut graph = Graph::undirected();
+        for orbit in map.split(' ') {
+            let (center, orbiter) = orbit.split_once(')').unwrap();
+            let (center, orbiter) = (graph.add_node(center), graph.add_node(orbiter));
+            graph.add_edge(center, orbiter);
+        }
+
+        let tree = Tree::from_graph(&graph, graph.id("COM").unwrap()).unwrap();
+        (graph, tree)
+    }
+
+    #[test]
+    fn orbit_map() {
+        let (graph, tree) = orbits();
+        let node = |label| graph.id(label).unwrap();
+
+        // 42 without YOU and SAN, which add another 7 and 5
+        let total_orbits: usize = (0..tree.len()).map(|n| tree.depth(n)).sum();
+        assert_eq!(total_orbits, 54);
+
+        let (you, san) = (node("YOU"), node("SAN"));
+        assert_eq!(tree.lowest_common_ancestor(you, san), node("D"));
+        assert_eq!(
+            tree.distance(tree.parent(you).unwrap(), tree.parent(san).unwrap()),
+            4
+        );
+
+        assert_eq!(tree.lowest_common_ancestor(node("L"), node("E")), node("E"));
+        assert_eq!(tree.lowest_common_ancestor(node("H"), node("H")), node("H"));
+        assert_eq!(tree.parent(node("COM")), None);
+
+        let ancestors: Vec<&str> = tree.ancestors(node("F")).map(|n| graph.label(n)).collect();
+        assert_eq!(ancestors, ["E", "D", "C", "B", "COM"]);
+    }
+
+    #[test]
+    fn subtree_sizes_bottom_up() {
+        let (graph, tree) = orbits();
+
+        let mut sizes = vec![1; tree.len()];
+        for node in tree.bottom_up() {
+            if let Some(parent) = tree.parent(node) {
+                sizes[parent] += sizes[node];
+            }
+        }
+
+        assert_eq!(sizes[tree.root()], 14);
+        assert_eq!(sizes[graph.id("E").unwrap()], 6);
+        assert_eq!(tree.children(graph.id("B").unwrap()).len(), 2);
+    }
+
+    #[test]
+    fn not_a_tree() {
+        // a loop, then a node left out
+        assert_eq!(Tree::new(3, 0, [(0, 1), (1, 2), (2, 0)]), None);
+        assert_eq!(Tree::new(4, 0, [(0, 1), (1, 2), (2, 0)]), None);
+        assert_eq!(Tree::new(3, 0, [(0, 1)]), None);
+        assert_eq!(Tree::new(2, 5, [(0, 1)]), None);
+
+        assert!(Tree::new(1, 0, []).is_some());
+    }
+
+    proptest! {
+        #[test]
+        fn lca_matches_climbing(
+            parents in vec(any::<prop::sample::Index>(), 1..60),
+            pairs in vec((any::<prop::sample::Index>(), any::<prop::sample::Index>()), 1..10),
+        ) {
+            // node i + 1 hangs from some node before it
+            let edges: Vec<(usize, usize)> = parents
+                .iter()
+                .enumerate()
+                .map(|(idx, parent)| (parent.index(idx + 1), idx + 1))
+                .collect();
+            let len = parents.len() + 1;
+            let tree = Tree::new(len, 0, edges).unwrap();
+
+            for (a, b) in pairs {
+                let (a, b) = (a.index(len), b.index(len));
+                let above_a: Vec<usize> = std::iter::once(a).chain(tree.ancestors(a)).collect();
+                let expected = std::iter::once(b)
+                    .chain(tree.ancestors(b))
+                    .find(|n| above_a.contains(n))
+                    .unwrap();
+
+                prop_assert_eq!(tree.lowest_common_ancestor(a, b), expected);
+            }
+        }
+    }
+}
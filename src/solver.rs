@@ -0,0 +1,377 @@
+use std::{
+    convert::Infallible,
+    fmt::{self, Display},
+};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    problems::*,
+    shared::{Answer, BigUint},
+};
+
+/// A solution to one day's puzzle, with an entry point per part so one can be run without the
+/// other.
+pub trait Solver: Sync {
+    /// Describes the puzzle this solves.
+    fn metadata(&self) -> Metadata;
+
+    /// The day of the puzzle this solves.
+    fn day(&self) -> u32 {
+        self.metadata().day
+    }
+
+    /// The params this solver reads from its `SolveCtx`. The runner only accepts a `--param` the
+    /// day's solver lists here.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    fn solve_part1(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError>;
+
+    fn solve_part2(&self, input: &str, ctx: &SolveCtx) -> Result<AnswerValue, AocError>;
+
+    /// Whether part 2 should be solved for the requested part(s). A puzzle without a part 2 never
+    /// solves it.
+    fn solves_part2(&self, part: Part) -> bool {
+        part.includes_part2() && self.metadata().has_part2
+    }
+
+    /// Solves the requested part(s). Any part that wasn't requested, or doesn't exist, is left as
+    /// None.
+    fn solve_parts(
+        &self,
+        input: &str,
+        part: Part,
+        ctx: &SolveCtx,
+    ) -> Result<PartialAnswer, AocError> {
+        Ok(PartialAnswer {
+            part1: part
+                .includes_part1()
+                .then(|| self.solve_part1(input, ctx))
+                .transpose()?,
+            part2: self
+                .solves_part2(part)
+                .then(|| self.solve_part2(input, ctx))
+                .transpose()?,
+        })
+    }
+}
+
+/// What a solver solves, for listing the catalog of solved puzzles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Metadata {
+    pub day: u32,
+    pub title: &'static str,
+
+    /// The kinds of problem it is, or techniques used to solve it, like "grid" or "graph".
+    pub tags: &'static [&'static str],
+
+    /// False when the puzzle has no part 2, like the final day.
+    pub has_part2: bool,
+}
+
+/// A number from the puzzle's rules which can be changed with `--param name=value`, to try the
+/// same solver on variants of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+
+    /// What it changes, and the puzzle's own value.
+    pub about: &'static str,
+}
+
+/// Which part(s) of a puzzle to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Part {
+    One,
+    Two,
+    #[default]
+    Both,
+}
+
+impl Part {
+    pub fn includes_part1(self) -> bool {
+        matches!(self, Part::One | Part::Both)
+    }
+
+    pub fn includes_part2(self) -> bool {
+        matches!(self, Part::Two | Part::Both)
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            _ => Err(format!("part should be 1, 2, or both: {s}")),
+        }
+    }
+}
+
+/// One part's answer. Almost every answer fits in a `usize`, but some outgrow it, and some aren't
+/// numbers at all, like letters drawn in ASCII art. Values are always stored as the narrowest
+/// variant that holds them, so the same answer is equal however it was made.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "RawAnswerValue")]
+pub enum AnswerValue {
+    Int(usize),
+    Big(BigUint),
+    Text(String),
+}
+
+impl From<usize> for AnswerValue {
+    fn from(n: usize) -> Self {
+        AnswerValue::Int(n)
+    }
+}
+
+impl From<BigUint> for AnswerValue {
+    fn from(n: BigUint) -> Self {
+        match n.to_usize() {
+            Some(n) => AnswerValue::Int(n),
+            None => AnswerValue::Big(n),
+        }
+    }
+}
+
+/// Text which is a whole number becomes that number, so it matches a solver's numeric answer.
+impl From<String> for AnswerValue {
+    fn from(s: String) -> Self {
+        match s.parse::<BigUint>() {
+            Ok(n) => AnswerValue::from(n),
+            Err(_) => AnswerValue::Text(s),
+        }
+    }
+}
+
+impl From<&str> for AnswerValue {
+    fn from(s: &str) -> Self {
+        AnswerValue::from(s.to_string())
+    }
+}
+
+impl Display for AnswerValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerValue::Int(n) => write!(f, "{n}"),
+            AnswerValue::Big(n) => write!(f, "{n}"),
+            AnswerValue::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Parses an answer given on the command line. Anything which isn't a whole number is text.
+impl std::str::FromStr for AnswerValue {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(AnswerValue::from(s))
+    }
+}
+
+/// Numbers too big for JSON readers to hold exactly are written as strings.
+impl Serialize for AnswerValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AnswerValue::Int(n) => serializer.serialize_u64(*n as u64),
+            AnswerValue::Big(n) => serializer.serialize_str(&n.to_string()),
+            AnswerValue::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+/// An answer as written in JSON, either a number or a string, which may hold a bigger number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawAnswerValue {
+    Int(usize),
+    Text(String),
+}
+
+impl From<RawAnswerValue> for AnswerValue {
+    fn from(raw: RawAnswerValue) -> Self {
+        match raw {
+            RawAnswerValue::Int(n) => AnswerValue::Int(n),
+            RawAnswerValue::Text(s) => AnswerValue::from(s),
+        }
+    }
+}
+
+/// The answer to whichever parts were solved.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PartialAnswer {
+    pub part1: Option<AnswerValue>,
+    pub part2: Option<AnswerValue>,
+}
+
+impl<T: Into<AnswerValue>> From<Answer<T>> for PartialAnswer {
+    fn from(answer: Answer<T>) -> Self {
+        Self {
+            part1: Some(answer.part1.into()),
+            part2: answer.part2.map(Into::into),
+        }
+    }
+}
+
+/// Leaves out parts which weren't solved. The alternate form, `{:#}`, shows them as "none" instead,
+/// for when both parts were asked for and a missing one matters.
+impl Display for PartialAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [("part1", &self.part1), ("part2", &self.part2)]
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                Some(v) => Some(format!("{name}: {v}")),
+                None if f.alternate() => Some(format!("{name}: none")),
+                None => None,
+            })
+            .collect();
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Every implemented solver, in day order.
+pub static SOLVERS: &[&dyn Solver] = &[
+    &problem1::Problem1,
+    &problem2::Problem2,
+    &problem3::Problem3,
+    &problem4::Problem4,
+    &problem5::Problem5,
+    &problem6::Problem6,
+    &problem7::Problem7,
+    &problem8::Problem8,
+    &problem9::Problem9,
+    &problem10::Problem10,
+    &problem11::Problem11,
+    &problem12::Problem12,
+];
+
+/// Finds the solver for a day, like "9".
+pub fn find(day: &str) -> Option<&'static dyn Solver> {
+    let day: u32 = day.parse().ok()?;
+    SOLVERS.iter().find(|s| s.day() == day).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn solvers_are_in_day_order() {
+        for (idx, solver) in SOLVERS.iter().enumerate() {
+            assert_eq!(solver.day() as usize, idx + 1);
+        }
+    }
+
+    #[test]
+    fn only_the_final_day_lacks_part2() {
+        let without_part2: Vec<u32> = SOLVERS
+            .iter()
+            .map(|s| s.metadata())
+            .filter(|m| !m.has_part2)
+            .map(|m| m.day)
+            .collect();
+        assert_eq!(without_part2, [12]);
+    }
+
+    #[test]
+    fn missing_part2_is_not_solved() {
+        let input = examples::read("12");
+        let answer = find("12")
+            .unwrap()
+            .solve_parts(&input, Part::Both, &SolveCtx::default())
+            .unwrap();
+
+        assert_eq!(answer.part1, Some(AnswerValue::Int(3)));
+        assert_eq!(answer.part2, None);
+    }
+
+    #[test]
+    fn find_by_day() {
+        assert_eq!(find("9").map(|s| s.day()), Some(9));
+        assert!(find("13").is_none());
+        assert!(find("nine").is_none());
+    }
+
+    #[test]
+    fn solve_only_requested_parts() {
+        let input = examples::read("1");
+        let solver = find("1").unwrap();
+        let ctx = SolveCtx::default();
+
+        let answer = solver.solve_parts(&input, Part::One, &ctx).unwrap();
+        assert_eq!(answer.part1, Some(AnswerValue::Int(3)));
+        assert_eq!(answer.part2, None);
+
+        let answer = solver.solve_parts(&input, Part::Two, &ctx).unwrap();
+        assert_eq!(answer.part1, None);
+        assert_eq!(answer.part2, Some(AnswerValue::Int(6)));
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        let answer = find("1")
+            .unwrap()
+            .solve_parts("L68\nX30", Part::Both, &SolveCtx::default());
+
+        assert!(matches!(answer, Err(AocError::Parse(_))));
+    }
+
+    #[test]
+    fn display_skips_unsolved_parts() {
+        let answer = PartialAnswer {
+            part1: Some(AnswerValue::Int(50)),
+            part2: None,
+        };
+        assert_eq!(answer.to_string(), "part1: 50");
+        assert_eq!(format!("{answer:#}"), "part1: 50, part2: none");
+
+        let answer = PartialAnswer::from(Answer {
+            part1: 50,
+            part2: Some(24),
+        });
+        assert_eq!(answer.to_string(), "part1: 50, part2: 24");
+    }
+
+    #[test]
+    fn answer_values_are_stored_narrowest() {
+        assert_eq!(AnswerValue::from("42"), AnswerValue::Int(42));
+        assert!(matches!(
+            AnswerValue::from("123456789012345678901234567890"),
+            AnswerValue::Big(_)
+        ));
+        assert_eq!(
+            AnswerValue::from("EFJKZ"),
+            AnswerValue::Text("EFJKZ".to_string())
+        );
+        assert_eq!(
+            AnswerValue::from("1,2,3"),
+            AnswerValue::Text("1,2,3".to_string())
+        );
+    }
+
+    #[test]
+    fn text_answers_convert_like_numbers() {
+        let answer = PartialAnswer::from(Answer {
+            part1: "4,6,3".to_string(),
+            part2: Some("EFJKZ".to_string()),
+        });
+        assert_eq!(answer.to_string(), "part1: 4,6,3, part2: EFJKZ");
+    }
+
+    #[test]
+    fn parse_part() {
+        assert_eq!("1".parse(), Ok(Part::One));
+        assert_eq!("2".parse(), Ok(Part::Two));
+        assert_eq!("both".parse(), Ok(Part::Both));
+        assert!("3".parse::<Part>().is_err());
+    }
+}
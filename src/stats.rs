@@ -0,0 +1,209 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::Report;
+
+const STATS_DIR: &str = "results";
+
+/// When day 1 was released: midnight EST on December 1st, 2025, in unix seconds. Each following
+/// day is released 24 hours later.
+const FIRST_RELEASE: u64 = 1764565200;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// When each part first produced a verified-correct answer, by day.
+pub type SolveTimes = BTreeMap<u32, Solved>;
+
+/// When each part of a day was first solved, in unix seconds. Parts which haven't been solved yet
+/// are None.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Solved {
+    pub part1: Option<u64>,
+    pub part2: Option<u64>,
+}
+
+/// Where the solve times are stored.
+pub fn path() -> PathBuf {
+    PathBuf::from(STATS_DIR).join("stats.json")
+}
+
+/// Loads the solve times. Having none stored yet is fine, nothing has been tracked.
+pub fn load() -> Result<SolveTimes, String> {
+    match std::fs::read_to_string(path()) {
+        Ok(json) => {
+            serde_json::from_str(&json).map_err(|e| format!("could not parse solve times: {e}"))
+        }
+        Err(_) => Ok(SolveTimes::new()),
+    }
+}
+
+pub fn save(times: &SolveTimes) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(times).expect("solve times should serialize");
+    std::fs::create_dir_all(STATS_DIR)
+        .and_then(|_| std::fs::write(path(), json))
+        .map_err(|e| format!("could not save solve times: {e}"))
+}
+
+/// The current time, in unix seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// When a day's puzzle was released, in unix seconds.
+pub fn release(day: u32) -> u64 {
+    FIRST_RELEASE + u64::from(day.saturating_sub(1)) * DAY_SECS
+}
+
+/// Records `now` for each part the report verified as correct, unless that part was already
+/// solved. Returns whether anything was recorded.
+pub fn record(times: &mut SolveTimes, report: &Report, now: u64) -> bool {
+    let solved = times.entry(report.day).or_default();
+    let correct1 = report
+        .part1
+        .as_ref()
+        .and_then(|a| report.expected.check_part1(a))
+        == Some(true);
+    let correct2 = report
+        .part2
+        .as_ref()
+        .and_then(|a| report.expected.check_part2(a))
+        == Some(true);
+
+    let mut recorded = false;
+    for (correct, at) in [(correct1, &mut solved.part1), (correct2, &mut solved.part2)] {
+        if correct && at.is_none() {
+            *at = Some(now);
+            recorded = true;
+        }
+    }
+
+    recorded
+}
+
+/// Renders how long after its release each part of each day was solved, like:
+///
+/// ```text
+/// Day  part1       part2
+///   1  0d 00h 12m  0d 00h 31m
+///   2  0d 09h 05m  -
+/// ```
+pub fn render(times: &SolveTimes, days: &[u32]) -> String {
+    let mut lines = vec![format!("Day  {:<10}  part2", "part1")];
+    for &day in days {
+        let solved = times.get(&day).copied().unwrap_or_default();
+        let describe = |at: Option<u64>| at.map_or("-".to_string(), |at| since_release(day, at));
+
+        lines.push(format!(
+            "{day:>3}  {:<10}  {}",
+            describe(solved.part1),
+            describe(solved.part2)
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// How long after the day's release `at` was, like: `1d 02h 13m`
+fn since_release(day: u32, at: u64) -> String {
+    let mins = at.saturating_sub(release(day)) / 60;
+    format!(
+        "{}d {:02}h {:02}m",
+        mins / (24 * 60),
+        mins / 60 % 24,
+        mins % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc::solver::{AnswerValue, PartialAnswer};
+
+    use super::*;
+    use crate::expected::Expected;
+
+    fn report(day: u32, part1: usize, part2: usize) -> Report {
+        let answer = PartialAnswer {
+            part1: Some(part1.into()),
+            part2: Some(part2.into()),
+        };
+        let mut report = Report::new(day, answer, Duration::ZERO);
+        report.expected = Expected {
+            part1: Some(AnswerValue::Int(50)),
+            part2: Some(AnswerValue::Int(24)),
+        };
+        report
+    }
+
+    #[test]
+    fn days_release_a_day_apart() {
+        assert_eq!(release(1), FIRST_RELEASE);
+        assert_eq!(release(12) - release(1), 11 * DAY_SECS);
+    }
+
+    #[test]
+    fn only_correct_answers_are_recorded() {
+        let mut times = SolveTimes::new();
+
+        assert!(record(&mut times, &report(1, 50, 23), 100));
+        assert_eq!(
+            times[&1],
+            Solved {
+                part1: Some(100),
+                part2: None
+            }
+        );
+    }
+
+    #[test]
+    fn first_solve_is_kept() {
+        let mut times = SolveTimes::new();
+        record(&mut times, &report(1, 50, 23), 100);
+
+        assert!(record(&mut times, &report(1, 50, 24), 200));
+        assert!(!record(&mut times, &report(1, 50, 24), 300));
+        assert_eq!(
+            times[&1],
+            Solved {
+                part1: Some(100),
+                part2: Some(200)
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_answers_are_not_recorded() {
+        let mut times = SolveTimes::new();
+        let mut report = report(1, 50, 24);
+        report.expected = Expected::default();
+
+        assert!(!record(&mut times, &report, 100));
+    }
+
+    #[test]
+    fn render_time_since_release() {
+        let mut times = SolveTimes::new();
+        times.insert(
+            2,
+            Solved {
+                part1: Some(release(2) + 12 * 60),
+                part2: Some(release(2) + DAY_SECS + 2 * 3600 + 13 * 60),
+            },
+        );
+
+        assert_eq!(
+            render(&times, &[2, 3]),
+            "Day  part1       part2\n  \
+               2  0d 00h 12m  1d 02h 13m\n  \
+               3  -           -"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use aoc::solver::AnswerValue;
+
+/// The year every puzzle in this repo is from.
+const YEAR: u32 = 2025;
+
+/// The environment variable holding the adventofcode.com session cookie, which can be copied from
+/// a logged in browser.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// What adventofcode.com said about a submitted answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+
+    /// Wrong, sometimes with a hint like "too high".
+    Incorrect {
+        hint: Option<&'static str>,
+    },
+
+    /// Answers are rate limited after a wrong one.
+    TooSoon,
+
+    /// The part was already solved, or part 1 hasn't been solved yet.
+    WrongLevel,
+
+    /// The response wasn't recognized, so its text is kept.
+    Unknown(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "that's the right answer"),
+            Verdict::Incorrect { hint: Some(hint) } => {
+                write!(f, "that's not the right answer, {hint}")
+            }
+            Verdict::Incorrect { hint: None } => write!(f, "that's not the right answer"),
+            Verdict::TooSoon => write!(f, "answered too recently, wait a bit and try again"),
+            Verdict::WrongLevel => write!(f, "that part is already solved, or isn't unlocked yet"),
+            Verdict::Unknown(text) => write!(f, "unrecognized response: {text}"),
+        }
+    }
+}
+
+/// Submits an answer for one part of a day's puzzle, using the session from `SESSION_ENV`.
+pub fn submit(day: u32, part: u8, answer: &AnswerValue) -> Result<Verdict, String> {
+    let session = std::env::var(SESSION_ENV)
+        .map_err(|_| format!("{SESSION_ENV} should be set to your adventofcode.com session"))?;
+
+    let url = format!("https://adventofcode.com/{YEAR}/day/{day}/answer");
+    let mut response = ureq::post(&url)
+        .header("Cookie", format!("session={session}"))
+        .send_form([("level", part.to_string()), ("answer", answer.to_string())])
+        .map_err(|e| format!("could not submit answer: {e}"))?;
+
+    let html = response
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| format!("could not read response: {e}"))?;
+
+    Ok(verdict(&html))
+}
+
+/// Works out the verdict from the response page, which says it in plain English.
+fn verdict(html: &str) -> Verdict {
+    if html.contains("That's the right answer") {
+        Verdict::Correct
+    } else if html.contains("That's not the right answer") {
+        let hint = ["too high", "too low"]
+            .into_iter()
+            .find(|hint| html.contains(&format!("your answer is {hint}")));
+        Verdict::Incorrect { hint }
+    } else if html.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if html.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown(article_text(html))
+    }
+}
+
+/// The text of the page's main article, without tags, which is where the message is.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article>")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!("<html><main><article><p>{message}</p></article></main></html>")
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(
+            verdict(&page(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Verdict::Correct
+        );
+        assert_eq!(
+            verdict(&page(
+                "That's not the right answer; your answer is too high. Please wait one minute."
+            )),
+            Verdict::Incorrect {
+                hint: Some("too high")
+            }
+        );
+        assert_eq!(
+            verdict(&page(
+                "That's not the right answer. Please wait one minute."
+            )),
+            Verdict::Incorrect { hint: None }
+        );
+        assert_eq!(
+            verdict(&page(
+                "You gave an answer too recently; you have 30s left to wait."
+            )),
+            Verdict::TooSoon
+        );
+        assert_eq!(
+            verdict(&page(
+                "You don't seem to be solving the right level. Did you already complete it?"
+            )),
+            Verdict::WrongLevel
+        );
+    }
+
+    #[test]
+    fn unknown_verdicts_keep_the_article_text() {
+        assert_eq!(
+            verdict(&page("Something <em>new</em>\n  happened.")),
+            Verdict::Unknown("Something new happened.".to_string())
+        );
+    }
+}
@@ -0,0 +1,28 @@
+//! Entry points for running the solvers in a browser, through `wasm-bindgen`. Build with:
+//! `cargo build --lib --release --target wasm32-unknown-unknown`, then `wasm-bindgen`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    ctx::SolveCtx,
+    error::AocError,
+    solver::{self, Metadata, Part},
+};
+
+/// Solves both parts of a day's input, returning `{ part1, part2 }`. A part without a puzzle is
+/// `null`. Input the solver can't make sense of throws, with the same message the runner prints.
+#[wasm_bindgen]
+pub fn solve(day: u32, input: &str) -> Result<JsValue, JsError> {
+    let answer = solver::find(&day.to_string())
+        .ok_or_else(|| AocError::UnknownDay(day.to_string()))
+        .and_then(|solver| solver.solve_parts(input, Part::Both, &SolveCtx::default()))?;
+
+    Ok(serde_wasm_bindgen::to_value(&answer)?)
+}
+
+/// Every solver's metadata, for listing the days in the playground.
+#[wasm_bindgen]
+pub fn solvers() -> Result<JsValue, JsError> {
+    let catalog: Vec<Metadata> = solver::SOLVERS.iter().map(|s| s.metadata()).collect();
+    Ok(serde_wasm_bindgen::to_value(&catalog)?)
+}
@@ -0,0 +1,87 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process, thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Re-runs a day every time its source or input changes, until interrupted.
+///
+/// Source changes need a rebuild to take effect, so each run goes through `cargo run` using the
+/// same arguments this process was given (minus `--watch`).
+pub fn watch(day: u32, input_path: PathBuf, args: &[String]) -> ! {
+    let paths = watched_paths(day, input_path);
+    let run_args: Vec<&String> = args.iter().filter(|a| *a != "--watch").collect();
+
+    let mut last_modified = modified_times(&paths);
+    run(&run_args);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let modified = modified_times(&paths);
+        if modified != last_modified {
+            last_modified = modified;
+            run(&run_args);
+        }
+    }
+}
+
+/// The files which should trigger a re-run when they change.
+fn watched_paths(day: u32, input_path: PathBuf) -> Vec<PathBuf> {
+    vec![
+        PathBuf::from(format!("src/problems/problem{day}.rs")),
+        PathBuf::from("src/shared.rs"),
+        input_path,
+    ]
+}
+
+/// When each path was last modified. A missing file is None, so its creation counts as a change.
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|p| p.metadata().and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+fn run(args: &[&String]) {
+    print!("\x1B[2J\x1B[H"); // clear screen, so only the latest run is visible
+    let _ = io::stdout().flush();
+
+    let status = process::Command::new("cargo")
+        .args(["run", "--quiet", "--"])
+        .args(args)
+        .status();
+
+    match status {
+        Ok(status) if !status.success() => eprintln!("run failed: {status}"),
+        Ok(_) => {}
+        Err(e) => eprintln!("ERROR: could not start cargo: {e}"),
+    }
+    println!("watching for changes...");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watches_source_and_input() {
+        let paths = watched_paths(9, PathBuf::from("inputs/9.txt"));
+        assert!(paths.contains(&PathBuf::from("src/problems/problem9.rs")));
+        assert!(paths.contains(&PathBuf::from("inputs/9.txt")));
+    }
+
+    #[test]
+    fn missing_files_have_no_modified_time() {
+        let times = modified_times(&[
+            PathBuf::from("src/shared.rs"),
+            PathBuf::from("does/not/exist.txt"),
+        ]);
+        assert!(times[0].is_some());
+        assert!(times[1].is_none());
+    }
+}
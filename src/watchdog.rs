@@ -0,0 +1,297 @@
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use aoc::ctx::{Cancel, SolveEvent};
+
+use crate::{progress_bar, recording::Recording};
+
+/// How often status lines are printed, once the soft deadline has passed.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The progress bar is redrawn at most this often, so chatty solvers don't flood the terminal.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// While waiting on a quiet solver, the watchdog wakes this often to see if it was cancelled.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Watches the events reported by a solver. If the solver is still running after the soft
+/// deadline, a status line is printed to stderr every `interval` so it doesn't look hung.
+///
+/// Status lines are prefixed with the day, since several solvers may be running at once.
+///
+/// With `show_progress`, reported progress is also drawn as a progress bar on stderr. That only
+/// makes sense when one solver is running in a terminal.
+///
+/// Frames are drawn as they arrive, and also kept when given a `recording` to add them to.
+///
+/// The watchdog stops once the solver's `SolveCtx` is dropped, returning when each reported
+/// phase started so the runner can tell where the time went. It also stops once the solver is
+/// cancelled, since the runner has stopped waiting for it.
+pub fn spawn(
+    day: u32,
+    events: Receiver<SolveEvent>,
+    cancel: Cancel,
+    soft_deadline: Duration,
+    interval: Duration,
+    show_progress: bool,
+    mut recording: Option<Recording>,
+) -> JoinHandle<Watched> {
+    thread::spawn(move || {
+        let start = Instant::now();
+        let mut status = Status::default();
+        let mut next_report = start + soft_deadline;
+        let mut last_draw: Option<Instant> = None;
+        let mut phase_starts = Vec::new();
+
+        while !cancel.is_cancelled() {
+            let wait = next_report
+                .saturating_duration_since(Instant::now())
+                .min(CANCEL_CHECK_INTERVAL);
+            match events.recv_timeout(wait) {
+                Ok(event) => {
+                    match &event {
+                        SolveEvent::Phase { name, at } => phase_starts.push((*name, *at)),
+                        SolveEvent::Frame(frame) => {
+                            draw_frame(frame);
+                            if let Some(recording) = &mut recording {
+                                recording.push(frame);
+                            }
+                        }
+                        SolveEvent::Progress { .. } => {}
+                    }
+                    status.update(event);
+
+                    let due = last_draw.is_none_or(|at| at.elapsed() >= REDRAW_INTERVAL);
+                    if let (true, true, Some(bar)) = (show_progress, due, status.bar()) {
+                        progress_bar::draw(&bar);
+                        last_draw = Some(Instant::now());
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // only woken to check for cancelling, with no status due yet
+                    if Instant::now() < next_report {
+                        continue;
+                    }
+
+                    if last_draw.is_some() {
+                        progress_bar::clear();
+                    }
+                    eprintln!("day {day}: {}", status.describe(start.elapsed()));
+                    next_report += interval;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        if last_draw.is_some() {
+            progress_bar::clear();
+        }
+
+        Watched {
+            phase_starts,
+            recording,
+        }
+    })
+}
+
+/// Waits up to `grace` for a watchdog to stop, for when its solver was cancelled rather than
+/// finishing. Returns None if it's still going by then, leaving it to stop in the background.
+pub fn join_within(watchdog: JoinHandle<Watched>, grace: Duration) -> Option<Watched> {
+    let deadline = Instant::now() + grace;
+    while !watchdog.is_finished() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    Some(watchdog.join().expect("watchdog should not panic"))
+}
+
+/// What the watchdog saw, once the solver is done.
+pub struct Watched {
+    /// When each reported phase started.
+    pub phase_starts: Vec<(&'static str, Instant)>,
+
+    /// Every frame drawn, when recording them.
+    pub recording: Option<Recording>,
+}
+
+/// Draws a frame over the last one, in place of whatever's on the terminal, so they play like an
+/// animation.
+fn draw_frame(frame: &str) {
+    eprintln!("\x1b[2J\x1b[H{frame}");
+}
+
+/// The latest of what a solver has reported.
+#[derive(Default)]
+struct Status {
+    phase: Option<&'static str>,
+    progress: Option<(usize, usize)>,
+}
+
+impl Status {
+    fn update(&mut self, event: SolveEvent) {
+        match event {
+            SolveEvent::Progress { done, total } => self.progress = Some((done, total)),
+            SolveEvent::Phase { name, .. } => {
+                // progress belongs to a phase, so it's stale once the phase changes
+                self.phase = Some(name);
+                self.progress = None;
+            }
+            SolveEvent::Frame(_) => {}
+        }
+    }
+
+    /// The progress bar for the latest progress, if any has been reported in this phase.
+    fn bar(&self) -> Option<String> {
+        self.progress
+            .map(|(done, total)| progress_bar::render(self.phase, done, total))
+    }
+
+    fn describe(&self, elapsed: Duration) -> String {
+        let phase = self.phase.unwrap_or("unknown");
+        let progress = match self.progress {
+            Some((done, total)) if total > 0 => {
+                format!(
+                    "{done}/{total} ({:.1}%)",
+                    done as f64 * 100.0 / total as f64
+                )
+            }
+            Some((done, _)) => format!("{done}"),
+            None => "none reported".to_string(),
+        };
+
+        format!(
+            "still running after {:.0?}: phase {phase}, progress {progress}",
+            elapsed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::ctx::SolveCtx;
+
+    fn phase(name: &'static str) -> SolveEvent {
+        SolveEvent::Phase {
+            name,
+            at: std::time::Instant::now(),
+        }
+    }
+
+    #[test]
+    fn describe_without_reports() {
+        let status = Status::default();
+        assert_eq!(
+            status.describe(Duration::from_secs(12)),
+            "still running after 12s: phase unknown, progress none reported"
+        );
+    }
+
+    #[test]
+    fn describe_latest_reports() {
+        let mut status = Status::default();
+        status.update(phase("search"));
+        status.update(SolveEvent::Progress { done: 1, total: 8 });
+        status.update(SolveEvent::Progress { done: 2, total: 8 });
+
+        assert_eq!(
+            status.describe(Duration::from_secs(30)),
+            "still running after 30s: phase search, progress 2/8 (25.0%)"
+        );
+    }
+
+    #[test]
+    fn new_phase_clears_progress() {
+        let mut status = Status::default();
+        status.update(phase("parse"));
+        status.update(SolveEvent::Progress { done: 5, total: 5 });
+        status.update(phase("search"));
+
+        assert_eq!(status.progress, None);
+        assert_eq!(status.bar(), None);
+    }
+
+    #[test]
+    fn bar_shows_phase_and_progress() {
+        let mut status = Status::default();
+        status.update(phase("search"));
+        status.update(SolveEvent::Progress { done: 2, total: 8 });
+
+        assert_eq!(
+            status.bar(),
+            Some(progress_bar::render(Some("search"), 2, 8))
+        );
+    }
+
+    #[test]
+    fn stops_when_ctx_is_dropped() {
+        let (ctx, events) = SolveCtx::new();
+        let watchdog = spawn(
+            9,
+            events,
+            Cancel::default(),
+            Duration::from_secs(60),
+            STATUS_INTERVAL,
+            false,
+            None,
+        );
+
+        ctx.phase("search");
+        drop(ctx);
+
+        let phases = watchdog.join().unwrap().phase_starts;
+        assert_eq!(phases.len(), 1);
+        assert_eq!(phases[0].0, "search");
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let (ctx, events) = SolveCtx::new();
+        let cancel = ctx.cancel_handle();
+        cancel.cancel();
+
+        // the ctx is still alive, like it would be in a solver which never reports
+        let watchdog = spawn(
+            9,
+            events,
+            cancel,
+            Duration::from_secs(60),
+            STATUS_INTERVAL,
+            false,
+            None,
+        );
+        watchdog.join().unwrap();
+        drop(ctx);
+    }
+
+    #[test]
+    fn notices_cancelling_while_the_solver_is_quiet() {
+        let (ctx, events) = SolveCtx::new();
+        let cancel = ctx.cancel_handle();
+        let mut recording = Recording::default();
+        recording.push("frame");
+
+        let watchdog = spawn(
+            9,
+            events,
+            cancel.clone(),
+            Duration::from_secs(60),
+            STATUS_INTERVAL,
+            false,
+            Some(recording.clone()),
+        );
+        ctx.phase("search");
+
+        // long before the soft deadline, and with the ctx still alive
+        cancel.cancel();
+        let watched = join_within(watchdog, Duration::from_secs(5)).unwrap();
+        assert_eq!(watched.recording, Some(recording));
+        drop(ctx);
+    }
+}